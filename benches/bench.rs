@@ -1,8 +1,9 @@
 #![feature(stdarch_x86_avx512)]
 
 use std::{hint::black_box, time::Duration};
-use ring_arith::{cyclotomic_ring::*, hexl::bindings::eltwise_add_mod};
+use ring_arith::{algebra::{PolynomialRing, RingElement}, ccs::*, commitment::*, cyclotomic_ring::*, decomposition::*, ext_field::ExtField, latticefold::*, latticefold_plus::*, mle::*, polymul::*, power_of_two_ring::*, sumcheck::*, transcript::Transcript, wide_ring::*};
 use criterion::{criterion_group, criterion_main, Criterion};
+use rand::Rng;
 
 const N: usize = 64;
 // const MOD_Q: u64 = 4546383823830515713; // Example modulus
@@ -12,6 +13,9 @@ const K: usize = 2;
 const WIT_DIM: usize = 1048576; // 2^20
 // const WIT_DIM: usize = 1024; // 2^10
 const LOG_B:usize = 11;
+// Commitment heights of LFP and LFPP at this witness size.
+const KAPPA_LFP: usize = 23;
+const KAPPA_LFPP: usize = 19;
 
 
 fn add_avx512(data: [u64; N], other: [u64; N]) -> [u64; N] {
@@ -58,8 +62,6 @@ fn bench_lfpp(c: &mut Criterion) {
     //     )
     // });
 
-    const KAPPA_LFP: usize = 23;
-
     // 1.9497 s
     c.bench_function("lfp compute double commitment no mod", |b| {
         b.iter_with_setup(
@@ -81,7 +83,6 @@ fn bench_lfpp(c: &mut Criterion) {
         )
     });
 
-    const KAPPA_LFPP: usize = 19;
     // 1.6817 s
     c.bench_function("lfpp compute extension commitment", |b| {
         b.iter_with_setup(
@@ -125,7 +126,324 @@ fn bench_lfpp(c: &mut Criterion) {
 }
 
 
+// Length of the commitment mat-vec rows timed below.
+const INNER_PRODUCT_LEN: usize = 1024;
+
+// A synthetic R1CS whose witness has INNER_PRODUCT_LEN short entries.
+fn synthetic_witness_instance() -> (R1cs<MOD_Q, N>, Vec<CyclotomicRing<MOD_Q, N>>, Vec<CyclotomicRing<MOD_Q, N>>) {
+    let params = SyntheticParams {
+        num_constraints: INNER_PRODUCT_LEN,
+        num_public: 1,
+        num_witness: INNER_PRODUCT_LEN,
+        row_weight: 4,
+        degree: 2,
+        witness_bound: 2,
+    };
+    synthetic_r1cs(params, &mut rand::rng())
+}
+
+fn bench_inner_product(c: &mut Criterion) {
+    let (_, _, witness) = synthetic_witness_instance();
+    let setup = || {
+        let mut left: Vec<_> = (0..INNER_PRODUCT_LEN).map(|_| CyclotomicRing::<MOD_Q, N>::random()).collect();
+        let mut right = witness.clone();
+        left.iter_mut().for_each(|x| x.to_incomplete_ntt_representation());
+        right.iter_mut().for_each(|x| x.to_incomplete_ntt_representation());
+        (left, right)
+    };
+
+    c.bench_function("incomplete ntt inner product", |b| {
+        b.iter_with_setup(setup, |(mut left, mut right)| {
+            black_box(incomplete_ntt_inner_product(&mut left, &mut right))
+        })
+    });
+
+    c.bench_function("incomplete ntt multiply then add", |b| {
+        b.iter_with_setup(setup, |(mut left, mut right)| {
+            let mut sum = CyclotomicRing::<MOD_Q, N>::new();
+            sum.to_incomplete_ntt_representation();
+            for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+                sum = sum + incomplete_ntt_multiplication(l, r, true);
+            }
+            black_box(sum)
+        })
+    });
+}
+
+fn bench_ccs(c: &mut Criterion) {
+    let (r1cs, public, witness) = synthetic_witness_instance();
+    let ccs = Ccs::from(r1cs);
+    c.bench_function("r1cs satisfiability check", |b| {
+        b.iter(|| black_box(ccs.check(&public, &witness)).unwrap())
+    });
+}
+
+// The double commitment key has KAPPA_LFP × K·DC_WIT_DIM entries, so time it
+// at a smaller witness than WIT_DIM and run the proxy at the same size.
+const DC_WIT_DIM: usize = 1024; // 2^10
+
+fn bench_double_commitment(c: &mut Criterion) {
+    let len = K * DC_WIT_DIM;
+    let params = LatticeFoldPlusParams { gadget_base: 1 << 13, ..LatticeFoldPlusParams::DEFAULT_64 };
+    let key = DoubleCommitmentKey::<MOD_Q, N>::new(
+        AjtaiKey::setup(KAPPA_LFP, len, b"bench key"),
+        N,
+        params.gadget_base,
+        params.gadget_digits(MOD_Q),
+        b"bench outer",
+    );
+    let mut rng = rand::rng();
+    let columns: Vec<Vec<Option<usize>>> =
+        (0..N).map(|_| (0..len).map(|_| rng.random_bool(0.9).then(|| rng.random_range(0..N))).collect()).collect();
+    c.bench_function("lfp double commitment", |b| {
+        b.iter(|| {
+            let inner: Vec<_> = columns.iter().map(|column| key.commit_monomials(column)).collect();
+            black_box(key.commit_inner(&inner))
+        })
+    });
+
+    c.bench_function("lfp double commitment proxy", |b| {
+        b.iter_with_setup(
+            || (CyclotomicRing::<MOD_Q, N>::random(), CyclotomicRing::<MOD_Q, N>::random()),
+            |(operand1, operand2)| {
+                for _ in 0..DC_WIT_DIM * K * N * KAPPA_LFP {
+                    black_box(add_avx512(black_box(operand1.data), black_box(operand2.data)));
+                }
+            },
+        )
+    });
+}
+
+fn bench_decomposition(c: &mut Criterion) {
+    let decomposition = Decomposition { base: 4, digits: LOG_B };
+    let key = AjtaiKey::<MOD_Q, N>::setup(KAPPA_LFP, DC_WIT_DIM, b"bench key");
+    let witness: Vec<_> = (0..DC_WIT_DIM).map(|_| CyclotomicRing::random_bounded(decomposition.bound().0 + 1)).collect();
+    c.bench_function("lfp decomposition prover", |b| b.iter(|| black_box(decomposition.commit(&key, &witness)).unwrap()));
+
+    let mut rng = rand::rng();
+    let point: Vec<u64> = (0..DC_WIT_DIM.ilog2()).map(|_| rng.random_range(0..MOD_Q)).collect();
+    let evaluate = |w: &[CyclotomicRing<MOD_Q, N>]| vec![MultilinearExtension::new(w.to_vec()).evaluate_scalar(&point)];
+    let (digit_witnesses, commitments) = decomposition.commit(&key, &witness).unwrap();
+    let evaluations = digit_witnesses.iter().map(|w| evaluate(w)).collect();
+    let proof = DecompositionProof { commitments, evaluations };
+    let commitment = key.commit(&witness);
+    let claim = evaluate(&witness);
+    c.bench_function("lfp decomposition verifier", |b| {
+        b.iter(|| black_box(decomposition.verify(&commitment, &claim, &proof)).unwrap())
+    });
+}
+
+// One LatticeFold step on the synthetic R1CS: the instance is folded into
+// its own linearization, which is as costly as folding a different one.
+fn bench_latticefold(c: &mut Criterion) {
+    let (r1cs, public, witness) = synthetic_witness_instance();
+    let lf = LatticeFold::new(Ccs::from(r1cs), FoldingParams::DEFAULT_64, b"bench key");
+    let commitment = lf.commit(&witness);
+    let (accumulator, _) = lf.linearize(&mut Transcript::new(b"bench"), &commitment, &public, &witness).unwrap();
+    c.bench_function("latticefold prover fold step", |b| {
+        b.iter(|| {
+            let mut transcript = Transcript::new(b"bench");
+            black_box(lf.prove(&mut transcript, &accumulator, &witness, &commitment, &public, &witness)).unwrap()
+        })
+    });
+
+    let (_, _, proof) =
+        lf.prove(&mut Transcript::new(b"bench"), &accumulator, &witness, &commitment, &public, &witness).unwrap();
+    c.bench_function("latticefold verifier fold step", |b| {
+        b.iter(|| black_box(lf.verify(&mut Transcript::new(b"bench"), &accumulator, &commitment, &public, &proof)).unwrap())
+    });
+}
+
+fn bench_latticefold_plus(c: &mut Criterion) {
+    let (r1cs, public, witness) = synthetic_witness_instance();
+    let lfp = LatticeFoldPlus::new(Ccs::from(r1cs), LatticeFoldPlusParams::DEFAULT_64, b"bench key");
+    let commitment = lfp.commit(&witness);
+    let (accumulator, _) = lfp.linearize(&mut Transcript::new(b"bench"), &commitment, &public, &witness).unwrap();
+    c.bench_function("latticefold+ prover fold step", |b| {
+        b.iter(|| {
+            let mut transcript = Transcript::new(b"bench");
+            black_box(lfp.prove(&mut transcript, &accumulator, &witness, &commitment, &public, &witness)).unwrap()
+        })
+    });
+
+    let (_, _, proof) =
+        lfp.prove(&mut Transcript::new(b"bench"), &accumulator, &witness, &commitment, &public, &witness).unwrap();
+    c.bench_function("latticefold+ verifier fold step", |b| {
+        b.iter(|| black_box(lfp.verify(&mut Transcript::new(b"bench"), &accumulator, &commitment, &public, &proof)).unwrap())
+    });
+}
+
+// 2^61 - 1 ≡ -1 mod 2N, so X^N + 1 has no NTT-friendly splitting.
+const MOD_Q_NON_NTT: u64 = 2305843009213693951;
+
+fn bench_non_ntt_multiplication(c: &mut Criterion) {
+    let setup = || {
+        (
+            CyclotomicRing::<MOD_Q_NON_NTT, N>::random(),
+            CyclotomicRing::<MOD_Q_NON_NTT, N>::random(),
+        )
+    };
+
+    c.bench_function("non-ntt naive multiplication", |b| {
+        b.iter_with_setup(setup, |(mut x, mut y)| black_box(naive_multiply(&mut x, &mut y)))
+    });
+    c.bench_function("non-ntt karatsuba multiplication", |b| {
+        b.iter_with_setup(setup, |(mut x, mut y)| black_box(karatsuba_multiply(&mut x, &mut y)))
+    });
+    c.bench_function("non-ntt toom-3 multiplication", |b| {
+        b.iter_with_setup(setup, |(mut x, mut y)| black_box(toom_cook_multiply(&mut x, &mut y)))
+    });
+    c.bench_function("non-ntt crt multiplication", |b| {
+        b.iter_with_setup(setup, |(mut x, mut y)| black_box(crt_multiply(&mut x, &mut y)))
+    });
+}
+
+// LFP/LFPP over Z_{2^64}[X]/(X^N + 1): additions need no reduction at all,
+// but the extension commitment has to multiply without an NTT.
+const LOG_Q_POWER_OF_TWO: u32 = 64;
+
+/// The LFP double commitment as additions and the LFPP extension commitment
+/// as products with ternary elements, for any coefficient ring.
+fn bench_lfpp_ring<R: PolynomialRing<N>>(c: &mut Criterion, name: &str) {
+    c.bench_function(&format!("{name} lfp compute double commitment"), |b| {
+        b.iter_with_setup(
+            || (R::random(), R::random()),
+            |(operand1, operand2)| {
+                for _ in 0..WIT_DIM * K * N * KAPPA_LFP {
+                    black_box(black_box(operand1) + black_box(operand2));
+                }
+            },
+        )
+    });
+
+    c.bench_function(&format!("{name} lfpp compute extension commitment"), |b| {
+        b.iter_with_setup(
+            || (R::random(), R::random_bounded(2)),
+            |(operand1, operand2)| {
+                for _ in 0..WIT_DIM * LOG_B * KAPPA_LFPP {
+                    black_box(black_box(operand1) * black_box(operand2));
+                }
+            },
+        )
+    });
+}
+
+fn bench_power_of_two_lfpp(c: &mut Criterion) {
+    bench_lfpp_ring::<PowerOfTwoRing<LOG_Q_POWER_OF_TWO, N>>(c, "power-of-two");
+}
+
+// Single-prime 128-bit moduli against an RNS representation over two
+// 61-bit HEXL primes (a 122-bit modulus). All primes are ≡ 1 mod 2^17.
+const MOD_Q_WIDE_64: u128 = 18446744073707716609;
+const MOD_Q_WIDE_90: u128 = 1237940039285380274898075649;
+const MOD_Q_WIDE_120: u128 = 1329227995784915872903807060273266689;
+const MOD_Q_RNS_1: u64 = 2305843009211596801;
+const MOD_Q_RNS_2: u64 = 2305843009210023937;
+
+fn bench_wide_modulus(c: &mut Criterion) {
+    fn bench_wide<const Q: u128>(c: &mut Criterion, name: &str) {
+        c.bench_function(name, |b| {
+            b.iter_with_setup(
+                || (WideCyclotomicRing::<Q, N>::random(), WideCyclotomicRing::<Q, N>::random()),
+                |(mut x, mut y)| {
+                    let mut product = wide_ntt_multiplication(&mut x, &mut y);
+                    product.to_coeff_representation();
+                    black_box(product)
+                },
+            )
+        });
+    }
+    bench_wide::<MOD_Q_WIDE_64>(c, "wide 64-bit ntt multiplication");
+    bench_wide::<MOD_Q_WIDE_90>(c, "wide 90-bit ntt multiplication");
+    bench_wide::<MOD_Q_WIDE_120>(c, "wide 120-bit ntt multiplication");
+
+    c.bench_function("rns 2x61-bit ntt multiplication", |b| {
+        b.iter_with_setup(
+            || {
+                (
+                    (CyclotomicRing::<MOD_Q_RNS_1, N>::random(), CyclotomicRing::<MOD_Q_RNS_1, N>::random()),
+                    (CyclotomicRing::<MOD_Q_RNS_2, N>::random(), CyclotomicRing::<MOD_Q_RNS_2, N>::random()),
+                )
+            },
+            |((mut x1, mut y1), (mut x2, mut y2))| {
+                let mut product1 = fully_splitting_ntt_multiplication(&mut x1, &mut y1);
+                let mut product2 = fully_splitting_ntt_multiplication(&mut x2, &mut y2);
+                product1.to_coeff_representation();
+                product2.to_coeff_representation();
+                black_box((product1, product2))
+            },
+        )
+    });
+}
+
+// Sumcheck for Σ f·g - h: over F_{q^K} with WIT_DIM entries per table, and
+// over R_q with WIT_DIM / N ring elements, i.e. the same number of Z_q
+// coefficients.
+fn bench_sumcheck(c: &mut Criterion) {
+    fn bench_ring<R: RingElement>(c: &mut Criterion, name: &str, len: usize) {
+        let tables = || -> Vec<Vec<R>> { (0..3).map(|_| (0..len).map(|_| R::random()).collect()).collect() };
+        let combine = |f: &[R]| f[0] * f[1] - f[2];
+        c.bench_function(&format!("sumcheck prover {name}"), |b| {
+            b.iter_with_setup(tables, |tables| {
+                let mut prover = SumcheckProver::new(tables, 2, combine);
+                black_box(prover.prove(|_| R::random()))
+            })
+        });
 
+        let mut prover = SumcheckProver::new(tables(), 2, combine);
+        let claimed_sum = prover.sum();
+        let (proof, point) = prover.prove(|_| R::random());
+        c.bench_function(&format!("sumcheck verifier {name}"), |b| {
+            b.iter(|| {
+                let mut challenges = point.iter().copied();
+                black_box(verify(claimed_sum, &proof, point.len(), 2, |_| challenges.next().unwrap()))
+            })
+        });
+    }
+    bench_ring::<ExtField<MOD_Q, K>>(c, "over F_q^K", WIT_DIM);
+    bench_ring::<CyclotomicRing<MOD_Q, N>>(c, "over R_q", WIT_DIM / N);
+}
+
+// MLE evaluation and eq tables at WIT_DIM entries, on one thread and on all
+// cores. R_q tables again hold WIT_DIM / N ring elements.
+fn bench_mle(c: &mut Criterion) {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let num_variables = WIT_DIM.trailing_zeros() as usize;
+    let mut rng = rand::rng();
+
+    let table: Vec<u64> = (0..WIT_DIM).map(|_| rng.random_range(0..MOD_Q)).collect();
+    let point: Vec<u64> = (0..num_variables).map(|_| rng.random_range(0..MOD_Q)).collect();
+    for (name, threads) in [("", 1), (" parallel", threads)] {
+        c.bench_function(&format!("mle evaluate F_q{name}"), |b| {
+            b.iter(|| black_box(evaluate_fq_parallel(&table, &point, MOD_Q, threads)))
+        });
+        c.bench_function(&format!("eq table F_q{name}"), |b| {
+            b.iter(|| black_box(eq_table_fq_parallel(&point, MOD_Q, threads)))
+        });
+    }
+
+    type F = ExtField<MOD_Q, K>;
+    let f = MultilinearExtension::new((0..WIT_DIM).map(|_| F::random()).collect());
+    let point: Vec<F> = (0..num_variables).map(|_| F::random()).collect();
+    for (name, threads) in [("", 1), (" parallel", threads)] {
+        c.bench_function(&format!("mle evaluate F_q^K{name}"), |b| {
+            b.iter(|| black_box(f.evaluate_parallel(&point, threads)))
+        });
+        c.bench_function(&format!("eq table F_q^K{name}"), |b| {
+            b.iter(|| black_box(eq_table_parallel(&point, threads)))
+        });
+    }
+
+    type R = CyclotomicRing<MOD_Q, N>;
+    let len = WIT_DIM / N;
+    let f = MultilinearExtension::new((0..len).map(|_| <R as RingElement>::random()).collect());
+    let point: Vec<u64> = (0..len.trailing_zeros()).map(|_| rng.random_range(0..MOD_Q)).collect();
+    for (name, threads) in [("", 1), (" parallel", threads)] {
+        c.bench_function(&format!("mle evaluate R_q at a Z_q point{name}"), |b| {
+            b.iter(|| black_box(f.evaluate_scalar_parallel(&point, threads)))
+        });
+    }
+}
 
 fn configure_criterion() -> Criterion {
     Criterion::default().sample_size(30)
@@ -135,6 +453,6 @@ fn configure_criterion() -> Criterion {
 criterion_group! {
     name = benches;
     config = configure_criterion();
-    targets = bench_lfpp
+    targets = bench_lfpp, bench_inner_product, bench_ccs, bench_double_commitment, bench_decomposition, bench_latticefold, bench_latticefold_plus, bench_non_ntt_multiplication, bench_power_of_two_lfpp, bench_wide_modulus, bench_sumcheck, bench_mle
 }
 criterion_main!(benches);
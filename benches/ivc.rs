@@ -0,0 +1,167 @@
+//! Runs T folding steps of LatticeFold and LatticeFold+ over a chain of CCS
+//! instances and reports per-step prover time, verifier time, accumulator
+//! size and accumulated witness norm. Set IVC_STEPS to change T.
+//!
+//! Step t proves c_i = a_i·b_i for ternary a, b, with public input
+//! (s_in, s_out) where s_in = a_0 and s_out = a_(M-1); the next step starts
+//! from s_out, so the instances form a chain as in IVC.
+
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use ring_arith::ccs::{Ccs, R1cs, SparseMatrix};
+use ring_arith::cyclotomic_ring::CyclotomicRing;
+use ring_arith::decomposition::max_norm;
+use ring_arith::latticefold::{FoldingParams, LatticeFold, LinearizationProof, LinearizedInstance};
+use ring_arith::latticefold_plus::{LatticeFoldPlus, LatticeFoldPlusParams};
+use ring_arith::transcript::Transcript;
+
+const N: usize = 64;
+const MOD_Q: u64 = 1125899904679937;
+const PRODUCTS: usize = 64;
+const DEFAULT_STEPS: usize = 10;
+
+type Ring = CyclotomicRing<MOD_Q, N>;
+
+/// z = (1, s_in, s_out, a, b, c).
+fn chain_r1cs() -> R1cs<MOD_Q, N> {
+    let cols = 3 + 3 * PRODUCTS;
+    let rows = PRODUCTS + 2;
+    let (mut a, mut b, mut c) = (SparseMatrix::new(rows, cols), SparseMatrix::new(rows, cols), SparseMatrix::new(rows, cols));
+    for i in 0..PRODUCTS {
+        a.push_scalar(i, 3 + i, 1);
+        b.push_scalar(i, 3 + PRODUCTS + i, 1);
+        c.push_scalar(i, 3 + 2 * PRODUCTS + i, 1);
+    }
+    for (row, (public, wire)) in [(1, 3), (2, 3 + PRODUCTS - 1)].into_iter().enumerate() {
+        a.push_scalar(PRODUCTS + row, public, 1);
+        b.push_scalar(PRODUCTS + row, 0, 1);
+        c.push_scalar(PRODUCTS + row, wire, 1);
+    }
+    R1cs { num_public: 2, a, b, c }
+}
+
+fn ternary<R: Rng>(rng: &mut R) -> Ring {
+    let mut element = Ring::new();
+    element.data.iter_mut().for_each(|c| *c = [0, 1, MOD_Q - 1][rng.random_range(0..3)]);
+    element
+}
+
+/// T chained (public, witness) pairs.
+fn chain(steps: usize) -> Vec<(Vec<Ring>, Vec<Ring>)> {
+    let mut rng = rand::rng();
+    let mut state = ternary(&mut rng);
+    (0..steps)
+        .map(|_| {
+            let mut a: Vec<_> = (0..PRODUCTS).map(|_| ternary(&mut rng)).collect();
+            a[0] = state;
+            let b: Vec<_> = (0..PRODUCTS).map(|_| ternary(&mut rng)).collect();
+            let c: Vec<_> = a.iter().zip(&b).map(|(&x, &y)| x * y).collect();
+            let public = vec![state, a[PRODUCTS - 1]];
+            state = a[PRODUCTS - 1];
+            (public, [a, b, c].concat())
+        })
+        .collect()
+}
+
+struct Step {
+    prover: Duration,
+    verifier: Duration,
+    accumulator_bytes: usize,
+    norm: u64,
+}
+
+fn instance_bytes(instance: &LinearizedInstance<MOD_Q, N>) -> usize {
+    let elements = instance.commitment.len() + instance.public.len() + instance.evaluations.len();
+    (elements * N + instance.point.len()) * std::mem::size_of::<u64>()
+}
+
+/// Runs Π_lin on the first instance, then folds every later one into the
+/// accumulator, with one transcript per side for the whole chain.
+#[allow(clippy::type_complexity)]
+fn run<P>(
+    instances: &[(Vec<Ring>, Vec<Ring>)],
+    commit: impl Fn(&[Ring]) -> Vec<Ring>,
+    linearize: impl Fn(&mut Transcript, &[Ring], &[Ring], &[Ring]) -> (LinearizedInstance<MOD_Q, N>, LinearizationProof<MOD_Q, N>),
+    verify_linearization: impl Fn(&mut Transcript, &[Ring], &[Ring], &LinearizationProof<MOD_Q, N>) -> LinearizedInstance<MOD_Q, N>,
+    prove: impl Fn(&mut Transcript, &LinearizedInstance<MOD_Q, N>, &[Ring], &[Ring], &[Ring], &[Ring]) -> (LinearizedInstance<MOD_Q, N>, Vec<Ring>, P),
+    verify: impl Fn(&mut Transcript, &LinearizedInstance<MOD_Q, N>, &[Ring], &[Ring], &P) -> LinearizedInstance<MOD_Q, N>,
+) -> Vec<Step> {
+    let mut prover_transcript = Transcript::new(b"ivc");
+    let mut verifier_transcript = Transcript::new(b"ivc");
+    let (public, witness) = &instances[0];
+    let commitment = commit(witness);
+    let (mut accumulator, proof) = linearize(&mut prover_transcript, &commitment, public, witness);
+    assert_eq!(verify_linearization(&mut verifier_transcript, &commitment, public, &proof), accumulator);
+    let mut accumulator_witness = witness.clone();
+
+    instances[1..]
+        .iter()
+        .map(|(public, witness)| {
+            // The commitment is part of the instance, not of the fold.
+            let commitment = commit(witness);
+            let start = Instant::now();
+            let (folded, folded_witness, proof) =
+                prove(&mut prover_transcript, &accumulator, &accumulator_witness, &commitment, public, witness);
+            let prover = start.elapsed();
+
+            let start = Instant::now();
+            let verified = verify(&mut verifier_transcript, &accumulator, &commitment, public, &proof);
+            let verifier = start.elapsed();
+            assert_eq!(verified, folded, "verifier disagrees with prover");
+
+            accumulator = folded;
+            accumulator_witness = folded_witness;
+            Step { prover, verifier, accumulator_bytes: instance_bytes(&accumulator), norm: max_norm(&accumulator_witness) }
+        })
+        .collect()
+}
+
+fn report(name: &str, norm_bound: u64, steps: &[Step]) {
+    println!("{name} (witness norm bound {norm_bound})");
+    println!("{:>5} {:>12} {:>12} {:>16} {:>6}", "step", "prover ms", "verifier ms", "accumulator B", "norm");
+    for (t, step) in steps.iter().enumerate() {
+        println!(
+            "{:>5} {:>12.2} {:>12.2} {:>16} {:>6}",
+            t + 1,
+            step.prover.as_secs_f64() * 1e3,
+            step.verifier.as_secs_f64() * 1e3,
+            step.accumulator_bytes,
+            step.norm
+        );
+    }
+    let total = |f: fn(&Step) -> Duration| steps.iter().map(f).sum::<Duration>().as_secs_f64() * 1e3;
+    println!("total prover {:.2} ms, verifier {:.2} ms\n", total(|s| s.prover), total(|s| s.verifier));
+}
+
+fn main() {
+    let steps = std::env::var("IVC_STEPS").ok().and_then(|s| s.parse().ok()).unwrap_or(DEFAULT_STEPS);
+    let instances = chain(steps + 1);
+    let ccs = Ccs::from(chain_r1cs());
+    for (public, witness) in &instances {
+        ccs.check(public, witness).expect("chain instance");
+    }
+    println!("{steps} steps, {} constraints, {} witness elements, N = {N}\n", ccs.num_constraints(), ccs.num_witness());
+
+    let lf = LatticeFold::new(ccs.clone(), FoldingParams::DEFAULT_64, b"ivc key");
+    let lf_steps = run(
+        &instances,
+        |w| lf.commit(w),
+        |t, cm, x, w| lf.linearize(t, cm, x, w).unwrap(),
+        |t, cm, x, proof| lf.verify_linearization(t, cm, x, proof).unwrap(),
+        |t, acc, acc_w, cm, x, w| lf.prove(t, acc, acc_w, cm, x, w).unwrap(),
+        |t, acc, cm, x, proof| lf.verify(t, acc, cm, x, proof).unwrap(),
+    );
+    report("latticefold", lf.params.norm_bound(), &lf_steps);
+
+    let lfp = LatticeFoldPlus::new(ccs, LatticeFoldPlusParams::DEFAULT_64, b"ivc key");
+    let lfp_steps = run(
+        &instances,
+        |w| lfp.commit(w),
+        |t, cm, x, w| lfp.linearize(t, cm, x, w).unwrap(),
+        |t, cm, x, proof| lfp.verify_linearization(t, cm, x, proof).unwrap(),
+        |t, acc, acc_w, cm, x, w| lfp.prove(t, acc, acc_w, cm, x, w).unwrap(),
+        |t, acc, cm, x, proof| lfp.verify(t, acc, cm, x, proof).unwrap(),
+    );
+    report("latticefold+", lfp.params.norm_bound(N), &lfp_steps);
+}
@@ -4,6 +4,7 @@
 #include <unordered_map>
 #include <memory>
 #include <utility>
+#include <vector>
 
 class NTTCache {
 public:
@@ -55,7 +56,7 @@ extern "C" __attribute__((externally_visible)) uint64_t power_mod(uint64_t a, ui
     return intel::hexl::PowMod(a, b, modulus);
 }
 
-extern "C" __attribute__((externally_visible)) void eltwise_mult_mod(uint64_t* result, const uint64_t* operand1, const uint64_t* operand2, uint64_t n, uint64_t modulus) {
+extern "C" __attribute__((externally_visible)) void eltwise_mult_mod(uint64_t* result, const uint64_t* operand1, const uint64_t* operand2, size_t n, uint64_t modulus) {
     // You need to create buffers from your input arrays to pass to EltwiseMultMod.
     // TODO do I really need to?
     // std::vector<uint64_t> op1(operand1, operand1 + n);
@@ -64,17 +65,35 @@ extern "C" __attribute__((externally_visible)) void eltwise_mult_mod(uint64_t* r
     intel::hexl::EltwiseMultMod(result, operand1, operand2, n, modulus, 1);
 }
 
-// extern "C" __attribute__((externally_visible)) void eltwise_fma_mod(uint64_t* result, const uint64_t* operand1, const uint64_t* operand2, const uint64_t* operand3, uint64_t n, uint64_t modulus) {
-//     // You need to create buffers from your input arrays to pass to EltwiseMultMod.
-//     // TODO do I really need to?
-//     // std::vector<uint64_t> op1(operand1, operand1 + n);
-//     // std::vector<uint64_t> op2(operand2, operand2 + n);
+// result = operand1 * scalar + operand3 mod modulus; operand3 may be null.
+extern "C" __attribute__((externally_visible)) void eltwise_fma_mod(uint64_t* result, const uint64_t* operand1, uint64_t scalar, const uint64_t* operand3, size_t n, uint64_t modulus, uint64_t input_mod_factor) {
+    intel::hexl::EltwiseFMAMod(result, operand1, scalar, operand3, n, modulus, input_mod_factor);
+}
 
-//     intel::hexl::EltwiseFMAMod(result, operand1, operand2, operand3, n, modulus, 1);
-// }
+static std::vector<uint64_t>& Scratch(size_t n) {
+    thread_local std::vector<uint64_t> scratch;
+    scratch.resize(n);
+    return scratch;
+}
 
+// result = operand1 * operand2 + operand3 mod modulus; result may alias operand3.
+extern "C" __attribute__((externally_visible)) void eltwise_mult_add_mod(uint64_t* result, const uint64_t* operand1, const uint64_t* operand2, const uint64_t* operand3, size_t n, uint64_t modulus, uint64_t input_mod_factor) {
+    auto& product = Scratch(n);
+    intel::hexl::EltwiseMultMod(product.data(), operand1, operand2, n, modulus, input_mod_factor);
+    intel::hexl::EltwiseAddMod(result, product.data(), operand3, n, modulus);
+}
 
-extern "C" __attribute__((externally_visible)) void multiply_poly(uint64_t* result, const uint64_t* operand1, const uint64_t* operand2, uint64_t n, uint64_t modulus) {
+// accumulator += operand1 * operand2 mod modulus, without reducing the accumulator.
+extern "C" __attribute__((externally_visible)) void eltwise_mult_acc_lazy(uint64_t* accumulator, const uint64_t* operand1, const uint64_t* operand2, size_t n, uint64_t modulus, uint64_t input_mod_factor) {
+    auto& product = Scratch(n);
+    intel::hexl::EltwiseMultMod(product.data(), operand1, operand2, n, modulus, input_mod_factor);
+    for (size_t i = 0; i < n; ++i) {
+        accumulator[i] += product[i];
+    }
+}
+
+
+extern "C" __attribute__((externally_visible)) void multiply_poly(uint64_t* result, const uint64_t* operand1, const uint64_t* operand2, size_t n, uint64_t modulus) {
     // Step 1: Perform forward NTT on both polynomials
     std::vector<uint64_t> op1(operand1, operand1 + n);
     std::vector<uint64_t> op2(operand2, operand2 + n);
@@ -172,5 +191,50 @@ extern "C" __attribute__((externally_visible)) void ntt_inverse_in_place(
 ) {
     std::vector<uint64_t> operand_vec(operand, operand + n);
     auto& ntt = NTTCache::Get(n, modulus);
-    ntt.ComputeInverse(operand, operand, 4, 1);
-}
\ No newline at end of file
+    ntt.ComputeInverse(operand, operand, 2, 1);
+}
+// Lazy variants: the caller chooses the input and output modulus factors, so
+// values may be left in [0, factor * modulus) when the next kernel accepts them.
+extern "C" __attribute__((externally_visible)) void ntt_forward_in_place_lazy(
+    uint64_t* operand,
+    size_t n,
+    uint64_t modulus,
+    uint64_t input_mod_factor,
+    uint64_t output_mod_factor
+) {
+    auto& ntt = NTTCache::Get(n, modulus);
+    ntt.ComputeForward(operand, operand, input_mod_factor, output_mod_factor);
+}
+
+extern "C" __attribute__((externally_visible)) void ntt_inverse_in_place_lazy(
+    uint64_t* operand,
+    size_t n,
+    uint64_t modulus,
+    uint64_t input_mod_factor,
+    uint64_t output_mod_factor
+) {
+    auto& ntt = NTTCache::Get(n, modulus);
+    ntt.ComputeInverse(operand, operand, input_mod_factor, output_mod_factor);
+}
+
+extern "C" __attribute__((externally_visible)) void eltwise_mult_mod_lazy(
+    uint64_t* result,
+    const uint64_t* operand1,
+    const uint64_t* operand2,
+    size_t n,
+    uint64_t modulus,
+    uint64_t input_mod_factor
+) {
+    intel::hexl::EltwiseMultMod(result, operand1, operand2, n, modulus, input_mod_factor);
+}
+
+extern "C" __attribute__((externally_visible)) void eltwise_reduce_mod_lazy(
+    uint64_t* result,
+    const uint64_t* operand,
+    size_t n,
+    uint64_t modulus,
+    uint64_t input_mod_factor,
+    uint64_t output_mod_factor
+) {
+    intel::hexl::EltwiseReduceMod(result, operand, n, modulus, input_mod_factor, output_mod_factor);
+}
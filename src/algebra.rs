@@ -0,0 +1,222 @@
+//! The arithmetic the protocol layers (sumcheck, multilinear extensions)
+//! are generic over: F_q and F_{q^K} as `ExtField<MOD_Q, K>`, and R_q as
+//! `CyclotomicRing<MOD_Q, N>`. [`PolynomialRing`] collects what the rings
+//! Z_q[X]/(X^N + 1) share independently of how q reduces: coefficient
+//! norms and gadget decomposition.
+
+use std::fmt::Debug;
+use std::ops::{Add, Mul, Sub};
+
+use rand::Rng;
+
+use crate::cyclotomic_ring::{CyclotomicRing, Representation};
+use crate::ext_field::ExtField;
+use crate::modular::balanced_digits;
+use crate::power_of_two_ring::PowerOfTwoRing;
+use crate::splitting::MultiplicationStrategy;
+
+/// A commutative ring containing Z_q, with the integers acting as scalars.
+pub trait RingElement:
+    Copy + PartialEq + Debug + Send + Sync + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    /// The characteristic q.
+    const MODULUS: u64;
+
+    fn zero() -> Self;
+
+    fn one() -> Self;
+
+    fn random_with<R: Rng + ?Sized>(rng: &mut R) -> Self;
+
+    /// `scalar · self` for `scalar` ∈ Z_q.
+    fn scalar_mul(&self, scalar: u64) -> Self;
+
+    /// The image of `value` ∈ Z_q.
+    fn from_u64(value: u64) -> Self {
+        Self::one().scalar_mul(value)
+    }
+
+    fn random() -> Self {
+        Self::random_with(&mut rand::rng())
+    }
+
+    /// Moves the element into the form products are computed in, so that
+    /// arithmetic between prepared elements never transforms.
+    fn prepare(&mut self) {}
+}
+
+impl<const MOD_Q: u64, const K: usize> RingElement for ExtField<MOD_Q, K> {
+    const MODULUS: u64 = MOD_Q;
+
+    fn zero() -> Self {
+        ExtField::zero()
+    }
+
+    fn one() -> Self {
+        ExtField::one()
+    }
+
+    fn random_with<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        ExtField::random_with(rng)
+    }
+
+    fn scalar_mul(&self, scalar: u64) -> Self {
+        ExtField::scalar_mul(self, scalar)
+    }
+
+    fn from_u64(value: u64) -> Self {
+        ExtField::from_base(value)
+    }
+}
+
+/// Prepared elements are in the fully splitting NTT form if q allows it, in
+/// the 2-way incomplete form for q ≡ 1 mod N, and in coefficient form
+/// otherwise (the other strategies multiply from coefficients anyway).
+impl<const MOD_Q: u64, const N: usize> RingElement for CyclotomicRing<MOD_Q, N> {
+    const MODULUS: u64 = MOD_Q;
+
+    fn zero() -> Self {
+        // Zero is zero in every representation.
+        let mut zero = CyclotomicRing::new();
+        zero.representation = match Self::MULTIPLICATION_STRATEGY {
+            MultiplicationStrategy::FullySplitting => Representation::NTT,
+            MultiplicationStrategy::IncompleteNtt { degree: 2 } => Representation::IncompleteNTT,
+            _ => Representation::Coefficient,
+        };
+        zero
+    }
+
+    fn one() -> Self {
+        let mut one = CyclotomicRing::one();
+        one.prepare();
+        one
+    }
+
+    fn random_with<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut element = CyclotomicRing::new();
+        for c in element.data.iter_mut() {
+            *c = rng.random_range(0..MOD_Q);
+        }
+        element.prepare();
+        element
+    }
+
+    fn scalar_mul(&self, scalar: u64) -> Self {
+        CyclotomicRing::scalar_mul(self, scalar)
+    }
+
+    fn prepare(&mut self) {
+        match Self::MULTIPLICATION_STRATEGY {
+            MultiplicationStrategy::FullySplitting => self.to_ntt_representation(),
+            MultiplicationStrategy::IncompleteNtt { degree: 2 } => self.to_incomplete_ntt_representation(),
+            _ => {}
+        }
+    }
+}
+
+/// Z_q[X]/(X^N + 1) seen through its coefficients.
+pub trait PolynomialRing<const N: usize>: Copy + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> {
+    /// The element with these coefficients, reduced mod q, in coefficient form.
+    fn from_centered_coefficients(coefficients: [i64; N]) -> Self;
+
+    /// Coefficients as centered representatives of Z_q.
+    fn centered_coefficients(&self) -> [i64; N];
+
+    /// `c · self` for a scalar `c`.
+    fn scalar_mul(&self, scalar: u64) -> Self;
+
+    fn random() -> Self;
+
+    /// Coefficients uniform in `(-bound, bound)`.
+    fn random_bounded(bound: u64) -> Self;
+
+    /// Whether a carry left over by `digits` base-`base` digits vanishes mod q.
+    fn carry_vanishes(_base: u64, _digits: usize) -> bool {
+        false
+    }
+
+    fn infinity_norm(&self) -> u64 {
+        self.centered_coefficients().iter().map(|c| c.unsigned_abs()).max().unwrap_or(0)
+    }
+
+    fn l2_norm_squared(&self) -> u128 {
+        self.centered_coefficients().iter().map(|&c| (c as i128 * c as i128) as u128).sum()
+    }
+
+    /// Balanced gadget decomposition: `self = Σ base^i · parts[i]` with every
+    /// centered coefficient of every part in `(-base/2, base/2]`. The parts
+    /// are in coefficient form. Panics if `digits` digits are too few.
+    fn decompose(&self, base: u64, digits: usize) -> Vec<Self> {
+        let mut parts = vec![[0i64; N]; digits];
+        let mut coefficient_digits = vec![0i64; digits];
+        for (i, c) in self.centered_coefficients().into_iter().enumerate() {
+            assert!(
+                balanced_digits(c as i128, base, &mut coefficient_digits) || Self::carry_vanishes(base, digits),
+                "{digits} base-{base} digits cannot represent coefficient {c}"
+            );
+            for (part, &digit) in parts.iter_mut().zip(coefficient_digits.iter()) {
+                part[i] = digit;
+            }
+        }
+        parts.into_iter().map(Self::from_centered_coefficients).collect()
+    }
+
+    /// Inverse of [`PolynomialRing::decompose`]: `Σ base^i · parts[i]`.
+    fn recompose(parts: &[Self], base: u64) -> Self {
+        parts.iter().rev().fold(Self::from_centered_coefficients([0; N]), |result, &part| result.scalar_mul(base) + part)
+    }
+}
+
+impl<const MOD_Q: u64, const N: usize> PolynomialRing<N> for CyclotomicRing<MOD_Q, N> {
+    fn from_centered_coefficients(coefficients: [i64; N]) -> Self {
+        let mut element = CyclotomicRing::new();
+        element.data = coefficients.map(|c| (c as i128).rem_euclid(MOD_Q as i128) as u64);
+        element
+    }
+
+    fn centered_coefficients(&self) -> [i64; N] {
+        CyclotomicRing::centered_coefficients(self)
+    }
+
+    fn scalar_mul(&self, scalar: u64) -> Self {
+        CyclotomicRing::scalar_mul(self, scalar)
+    }
+
+    fn random() -> Self {
+        CyclotomicRing::random()
+    }
+
+    fn random_bounded(bound: u64) -> Self {
+        CyclotomicRing::random_bounded(bound)
+    }
+}
+
+/// A carry out of the top digit is a multiple of base^digits, which vanishes
+/// mod 2^k once that is a multiple of 2^k.
+impl<const LOG_Q: u32, const N: usize> PolynomialRing<N> for PowerOfTwoRing<LOG_Q, N> {
+    fn from_centered_coefficients(coefficients: [i64; N]) -> Self {
+        let mut element = PowerOfTwoRing::new();
+        element.data = coefficients.map(|c| c as u64 & Self::MASK);
+        element
+    }
+
+    fn centered_coefficients(&self) -> [i64; N] {
+        PowerOfTwoRing::centered_coefficients(self)
+    }
+
+    fn scalar_mul(&self, scalar: u64) -> Self {
+        PowerOfTwoRing::scalar_mul(self, scalar)
+    }
+
+    fn random() -> Self {
+        PowerOfTwoRing::random()
+    }
+
+    fn random_bounded(bound: u64) -> Self {
+        PowerOfTwoRing::random_bounded(bound)
+    }
+
+    fn carry_vanishes(base: u64, digits: usize) -> bool {
+        base.is_power_of_two() && digits as u32 * base.trailing_zeros() >= LOG_Q
+    }
+}
@@ -0,0 +1,335 @@
+//! Customizable constraint systems (Setty–Thaler–Wahby) over R_q.
+//!
+//! A CCS has m × n matrices M_1, ..., M_t, multisets S_1, ..., S_k of
+//! matrix indices and constants c_1, ..., c_k ∈ Z_q. It is satisfied by
+//! z = (1, x, w) ∈ R_q^n, x the public inputs and w the witness, iff
+//!
+//!   Σ_i c_i · ∘_(j ∈ S_i) M_j·z = 0,
+//!
+//! ∘ the entry-wise product. R1CS (A·z) ∘ (B·z) = C·z is the case
+//! t = 3, S = ({A, B}, {C}), c = (1, -1). The wire order (constant one,
+//! public, private) is circom's.
+
+use std::error::Error;
+use std::fmt;
+
+use rand::Rng;
+
+use crate::algebra::RingElement;
+use crate::cyclotomic_ring::CyclotomicRing;
+
+/// A sparse matrix over R_q, stored by rows. Entries are kept in the
+/// multiplication form of [`RingElement::prepare`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SparseMatrix<const MOD_Q: u64, const N: usize> {
+    pub cols: usize,
+    pub rows: Vec<Vec<(usize, CyclotomicRing<MOD_Q, N>)>>,
+}
+
+impl<const MOD_Q: u64, const N: usize> SparseMatrix<MOD_Q, N> {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self { cols, rows: vec![Vec::new(); rows] }
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn num_entries(&self) -> usize {
+        self.rows.iter().map(Vec::len).sum()
+    }
+
+    /// Adds `value` at (`row`, `col`); repeated positions add up.
+    pub fn push(&mut self, row: usize, col: usize, mut value: CyclotomicRing<MOD_Q, N>) {
+        assert!(col < self.cols, "column {col} out of range for {} columns", self.cols);
+        value.prepare();
+        self.rows[row].push((col, value));
+    }
+
+    /// Adds the constant `value` ∈ Z_q at (`row`, `col`).
+    pub fn push_scalar(&mut self, row: usize, col: usize, value: u64) {
+        self.push(row, col, CyclotomicRing::constant(value % MOD_Q));
+    }
+
+    /// M·z, in multiplication form.
+    pub fn mul_vector(&self, z: &[CyclotomicRing<MOD_Q, N>]) -> Vec<CyclotomicRing<MOD_Q, N>> {
+        assert_eq!(z.len(), self.cols, "vector length does not match the matrix");
+        let mut prepared = z.to_vec();
+        prepared.iter_mut().for_each(RingElement::prepare);
+        self.rows
+            .iter()
+            .map(|row| row.iter().fold(RingElement::zero(), |sum, &(col, value)| sum + value * prepared[col]))
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CcsError {
+    /// The public input or witness has the wrong length.
+    WrongLength { expected: usize, actual: usize },
+    /// The first constraint that does not hold.
+    Unsatisfied { row: usize },
+}
+
+impl fmt::Display for CcsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CcsError::WrongLength { expected, actual } => write!(f, "expected {expected} values, got {actual}"),
+            CcsError::Unsatisfied { row } => write!(f, "constraint {row} is not satisfied"),
+        }
+    }
+}
+
+impl Error for CcsError {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ccs<const MOD_Q: u64, const N: usize> {
+    pub num_public: usize,
+    pub matrices: Vec<SparseMatrix<MOD_Q, N>>,
+    pub multisets: Vec<Vec<usize>>,
+    pub constants: Vec<u64>,
+}
+
+impl<const MOD_Q: u64, const N: usize> Ccs<MOD_Q, N> {
+    pub fn new(
+        num_public: usize,
+        matrices: Vec<SparseMatrix<MOD_Q, N>>,
+        multisets: Vec<Vec<usize>>,
+        constants: Vec<u64>,
+    ) -> Self {
+        assert!(!matrices.is_empty(), "a CCS needs at least one matrix");
+        let (rows, cols) = (matrices[0].num_rows(), matrices[0].cols);
+        assert!(matrices.iter().all(|m| m.num_rows() == rows && m.cols == cols), "matrices of different shapes");
+        assert!(num_public < cols, "no room for the constant one and the witness");
+        assert_eq!(multisets.len(), constants.len(), "one constant per multiset");
+        assert!(multisets.iter().flatten().all(|&j| j < matrices.len()), "multiset refers to a missing matrix");
+        Self { num_public, matrices, multisets, constants }
+    }
+
+    /// m
+    pub fn num_constraints(&self) -> usize {
+        self.matrices[0].num_rows()
+    }
+
+    /// n = 1 + public + witness
+    pub fn num_variables(&self) -> usize {
+        self.matrices[0].cols
+    }
+
+    pub fn num_witness(&self) -> usize {
+        self.num_variables() - 1 - self.num_public
+    }
+
+    /// The same constraints over `len` witness elements, the added ones
+    /// unconstrained.
+    pub fn pad_witness(&self, len: usize) -> Self {
+        assert!(len >= self.num_witness(), "padding cannot drop witness elements");
+        let extra = len - self.num_witness();
+        let matrices = self.matrices.iter().map(|m| SparseMatrix { cols: m.cols + extra, rows: m.rows.clone() }).collect();
+        Self { matrices, ..self.clone() }
+    }
+
+    /// Largest multiset, the degree of the constraint polynomial.
+    pub fn degree(&self) -> usize {
+        self.multisets.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    /// z = (1, public, witness).
+    pub fn assemble_z(
+        &self,
+        public: &[CyclotomicRing<MOD_Q, N>],
+        witness: &[CyclotomicRing<MOD_Q, N>],
+    ) -> Result<Vec<CyclotomicRing<MOD_Q, N>>, CcsError> {
+        if public.len() != self.num_public {
+            return Err(CcsError::WrongLength { expected: self.num_public, actual: public.len() });
+        }
+        if witness.len() != self.num_witness() {
+            return Err(CcsError::WrongLength { expected: self.num_witness(), actual: witness.len() });
+        }
+        let mut z = Vec::with_capacity(self.num_variables());
+        z.push(CyclotomicRing::one());
+        z.extend_from_slice(public);
+        z.extend_from_slice(witness);
+        Ok(z)
+    }
+
+    /// Σ_i c_i · ∘_(j ∈ S_i) M_j·z, zero for a satisfying z.
+    pub fn residual(&self, z: &[CyclotomicRing<MOD_Q, N>]) -> Vec<CyclotomicRing<MOD_Q, N>> {
+        let products: Vec<_> = self.matrices.iter().map(|m| m.mul_vector(z)).collect();
+        (0..self.num_constraints())
+            .map(|row| {
+                self.multisets.iter().zip(&self.constants).fold(RingElement::zero(), |sum, (multiset, &c)| {
+                    let term = multiset.iter().fold(RingElement::one(), |product, &j| product * products[j][row]);
+                    sum + RingElement::scalar_mul(&term, c)
+                })
+            })
+            .collect()
+    }
+
+    pub fn check(&self, public: &[CyclotomicRing<MOD_Q, N>], witness: &[CyclotomicRing<MOD_Q, N>]) -> Result<(), CcsError> {
+        let z = self.assemble_z(public, witness)?;
+        match self.residual(&z).iter().position(|r| *r != CyclotomicRing::new()) {
+            Some(row) => Err(CcsError::Unsatisfied { row }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// (A·z) ∘ (B·z) = C·z.
+#[derive(Clone, Debug, PartialEq)]
+pub struct R1cs<const MOD_Q: u64, const N: usize> {
+    pub num_public: usize,
+    pub a: SparseMatrix<MOD_Q, N>,
+    pub b: SparseMatrix<MOD_Q, N>,
+    pub c: SparseMatrix<MOD_Q, N>,
+}
+
+impl<const MOD_Q: u64, const N: usize> R1cs<MOD_Q, N> {
+    pub fn check(&self, public: &[CyclotomicRing<MOD_Q, N>], witness: &[CyclotomicRing<MOD_Q, N>]) -> Result<(), CcsError> {
+        Ccs::from(self.clone()).check(public, witness)
+    }
+}
+
+impl<const MOD_Q: u64, const N: usize> From<R1cs<MOD_Q, N>> for Ccs<MOD_Q, N> {
+    fn from(r1cs: R1cs<MOD_Q, N>) -> Self {
+        Ccs::new(r1cs.num_public, vec![r1cs.a, r1cs.b, r1cs.c], vec![vec![0, 1], vec![2]], vec![1, MOD_Q - 1])
+    }
+}
+
+/// Shape of a synthetic instance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SyntheticParams {
+    pub num_constraints: usize,
+    pub num_public: usize,
+    pub num_witness: usize,
+    /// Nonzero entries per row of each matrix except the last.
+    pub row_weight: usize,
+    /// Product of this many matrices per constraint; 2 for R1CS.
+    pub degree: usize,
+    /// Witness coefficients lie in (-witness_bound, witness_bound).
+    pub witness_bound: u64,
+}
+
+/// A satisfiable CCS with one product term:
+/// (M_1·z) ∘ ... ∘ (M_d·z) = M_(d+1)·z.
+/// The first d matrices get `row_weight` random Z_q entries per row, the
+/// witness is short and random, and M_(d+1) puts each row's product on the
+/// constant-one wire, so any witness norm can be chosen. All randomness
+/// comes from `rng`, so a seeded RNG reproduces the instance.
+pub fn synthetic_ccs<const MOD_Q: u64, const N: usize, R: Rng + ?Sized>(
+    params: SyntheticParams,
+    rng: &mut R,
+) -> (Ccs<MOD_Q, N>, Vec<CyclotomicRing<MOD_Q, N>>, Vec<CyclotomicRing<MOD_Q, N>>) {
+    let SyntheticParams { num_constraints, num_public, num_witness, row_weight, degree, witness_bound } = params;
+    assert!(degree >= 1, "a constraint needs at least one factor");
+    let cols = 1 + num_public + num_witness;
+    let public: Vec<_> = (0..num_public).map(|_| CyclotomicRing::random_bounded_with(rng, witness_bound)).collect();
+    let witness: Vec<_> = (0..num_witness).map(|_| CyclotomicRing::random_bounded_with(rng, witness_bound)).collect();
+
+    let mut matrices: Vec<_> = (0..degree)
+        .map(|_| {
+            let mut matrix = SparseMatrix::new(num_constraints, cols);
+            for row in 0..num_constraints {
+                for _ in 0..row_weight {
+                    matrix.push_scalar(row, rng.random_range(0..cols), rng.random_range(0..MOD_Q));
+                }
+            }
+            matrix
+        })
+        .collect();
+
+    let mut z = vec![CyclotomicRing::one()];
+    z.extend_from_slice(&public);
+    z.extend_from_slice(&witness);
+    let products: Vec<_> = matrices.iter().map(|m| m.mul_vector(&z)).collect();
+    let mut output = SparseMatrix::new(num_constraints, cols);
+    for row in 0..num_constraints {
+        output.push(row, 0, products.iter().fold(RingElement::one(), |product, p| product * p[row]));
+    }
+    matrices.push(output);
+
+    let ccs = Ccs::new(num_public, matrices, vec![(0..degree).collect(), vec![degree]], vec![1, MOD_Q - 1]);
+    (ccs, public, witness)
+}
+
+/// [`synthetic_ccs`] with degree 2, as an R1CS.
+pub fn synthetic_r1cs<const MOD_Q: u64, const N: usize, R: Rng + ?Sized>(
+    params: SyntheticParams,
+    rng: &mut R,
+) -> (R1cs<MOD_Q, N>, Vec<CyclotomicRing<MOD_Q, N>>, Vec<CyclotomicRing<MOD_Q, N>>) {
+    let (ccs, public, witness) = synthetic_ccs(SyntheticParams { degree: 2, ..params }, rng);
+    let [a, b, c]: [SparseMatrix<MOD_Q, N>; 3] = ccs.matrices.try_into().expect("three matrices");
+    (R1cs { num_public: ccs.num_public, a, b, c }, public, witness)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra::PolynomialRing;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const MOD_Q: u64 = 1125899904679937;
+    // q ≡ 65 mod 128: 2-way incomplete NTT.
+    const MOD_Q_INCOMPLETE: u64 = 1125899906842817;
+    const N: usize = 64;
+
+    const PARAMS: SyntheticParams =
+        SyntheticParams { num_constraints: 16, num_public: 2, num_witness: 13, row_weight: 3, degree: 2, witness_bound: 4 };
+
+    #[test]
+    fn test_hand_written_r1cs() {
+        // x · x = w, w · x = 1·out, over z = (1, x, out, w).
+        let mut a = SparseMatrix::<MOD_Q, N>::new(2, 4);
+        let mut b = SparseMatrix::new(2, 4);
+        let mut c = SparseMatrix::new(2, 4);
+        a.push_scalar(0, 1, 1);
+        b.push_scalar(0, 1, 1);
+        c.push_scalar(0, 3, 1);
+        a.push_scalar(1, 3, 1);
+        b.push_scalar(1, 1, 1);
+        c.push_scalar(1, 2, 1);
+        let r1cs = R1cs { num_public: 2, a, b, c };
+
+        let x = CyclotomicRing::random_bounded(3);
+        let w = x * x;
+        let out = w * x;
+        assert_eq!(r1cs.check(&[x, out], &[w]), Ok(()));
+        assert_eq!(r1cs.check(&[x, out + CyclotomicRing::one()], &[w]), Err(CcsError::Unsatisfied { row: 1 }));
+        assert_eq!(r1cs.check(&[x], &[w]), Err(CcsError::WrongLength { expected: 2, actual: 1 }));
+        let ccs = Ccs::from(r1cs);
+        assert_eq!(ccs.degree(), 2);
+
+        // Padding leaves the added witness elements unconstrained.
+        let padded = ccs.pad_witness(3);
+        assert_eq!(padded.num_witness(), 3);
+        assert_eq!(padded.check(&[x, out], &[w, x, out]), Ok(()));
+        assert_eq!(padded.check(&[x, out], &[w]), Err(CcsError::WrongLength { expected: 3, actual: 1 }));
+    }
+
+    #[test]
+    fn test_synthetic_instances_are_satisfied() {
+        let mut rng = rand::rng();
+        let (r1cs, public, witness) = synthetic_r1cs::<MOD_Q, N, _>(PARAMS, &mut rng);
+        assert_eq!(r1cs.check(&public, &witness), Ok(()));
+        assert!(witness.iter().all(|w| w.infinity_norm() < PARAMS.witness_bound));
+
+        let mut tampered = witness.clone();
+        tampered[0] = tampered[0] + CyclotomicRing::one();
+        let touches_first_witness = r1cs.a.rows.iter().chain(&r1cs.b.rows).flatten().any(|&(col, _)| col == 3);
+        assert_eq!(r1cs.check(&public, &tampered).is_err(), touches_first_witness);
+
+        let (ccs, public, witness) =
+            synthetic_ccs::<MOD_Q_INCOMPLETE, N, _>(SyntheticParams { degree: 3, ..PARAMS }, &mut rng);
+        assert_eq!(ccs.degree(), 3);
+        assert_eq!(ccs.num_witness(), PARAMS.num_witness);
+        assert_eq!(ccs.check(&public, &witness), Ok(()));
+    }
+
+    #[test]
+    fn test_synthetic_instances_are_reproducible() {
+        let instance = |seed| synthetic_ccs::<MOD_Q, N, _>(PARAMS, &mut StdRng::seed_from_u64(seed));
+        assert_eq!(instance(5), instance(5));
+        assert_ne!(instance(5).2, instance(6).2);
+    }
+}
@@ -0,0 +1,242 @@
+//! Challenge sets for folding: short ring elements whose pairwise
+//! differences are invertible and whose operator norm is bounded.
+//!
+//! The operator norm of c is the largest |σ_j(c)| over the canonical
+//! embedding σ_j(c) = c(ζ^(2j+1)), ζ = e^(iπ/N), i.e. the factor by which
+//! multiplication with c can stretch the ℓ2 norm. Invertibility of
+//! differences follows from Lyubashevsky–Seiler ("Short, Invertible
+//! Elements in Partially Splitting Cyclotomic Rings", 2018) when X^N + 1
+//! splits into few factors mod q; otherwise it can be checked on a concrete
+//! set of challenges slot by slot.
+
+use rand::Rng;
+
+use crate::cyclotomic_ring::CyclotomicRing;
+use crate::splitting::splitting;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChallengeSet {
+    /// Exactly `weight` coefficients in {-1, 1}, the rest zero.
+    FixedWeightTernary { weight: usize },
+    /// `ones` coefficients in {-1, 1} and `twos` in {-2, 2}, rejected until
+    /// the operator norm is at most `max_operator_norm` (as in LaBRADOR).
+    Labrador { ones: usize, twos: usize, max_operator_norm: f64 },
+}
+
+impl ChallengeSet {
+    /// LaBRADOR's set for N = 64: 23 zeros, 31 ±1s, 10 ±2s, operator norm ≤ 15.
+    pub const LABRADOR_64: ChallengeSet = ChallengeSet::Labrador { ones: 31, twos: 10, max_operator_norm: 15.0 };
+
+    /// Largest absolute coefficient of any challenge.
+    pub fn infinity_norm(&self) -> u64 {
+        match self {
+            ChallengeSet::FixedWeightTernary { .. } => 1,
+            ChallengeSet::Labrador { twos: 0, .. } => 1,
+            ChallengeSet::Labrador { .. } => 2,
+        }
+    }
+
+    /// log2 of the number of candidates for ring degree `n`, before
+    /// rejection. LaBRADOR's rejection step keeps most of them, so this is
+    /// close to the size of the set.
+    pub fn log2_size(&self, n: usize) -> f64 {
+        match *self {
+            ChallengeSet::FixedWeightTernary { weight } => log2_binomial(n, weight) + weight as f64,
+            ChallengeSet::Labrador { ones, twos, .. } => {
+                log2_binomial(n, ones) + log2_binomial(n - ones, twos) + (ones + twos) as f64
+            }
+        }
+    }
+
+    pub fn sample<const MOD_Q: u64, const N: usize>(&self) -> CyclotomicRing<MOD_Q, N> {
+        self.sample_with(&mut rand::rng())
+    }
+
+    /// Samples with the given randomness.
+    pub fn sample_with<const MOD_Q: u64, const N: usize, R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> CyclotomicRing<MOD_Q, N> {
+        self.sample_by(|bound| rng.random_range(0..bound))
+    }
+
+    /// Samples with `uniform_below(b)`, uniform in [0, b), as the only
+    /// source of randomness, so the challenge is fixed by those draws alone
+    /// and not by a library's sampling algorithms, e.g. for a transcript.
+    pub fn sample_by<const MOD_Q: u64, const N: usize>(
+        &self,
+        mut uniform_below: impl FnMut(u64) -> u64,
+    ) -> CyclotomicRing<MOD_Q, N> {
+        match *self {
+            ChallengeSet::FixedWeightTernary { weight } => sample_fixed_weight(&mut uniform_below, &[(weight, 1)]),
+            ChallengeSet::Labrador { ones, twos, max_operator_norm } => loop {
+                let candidate = sample_fixed_weight(&mut uniform_below, &[(ones, 1), (twos, 2)]);
+                if operator_norm(&candidate) <= max_operator_norm {
+                    break candidate;
+                }
+            },
+        }
+    }
+
+    /// Whether every nonzero difference of two challenges is invertible by
+    /// the Lyubashevsky–Seiler bound, without looking at the challenges.
+    pub fn differences_provably_invertible<const MOD_Q: u64, const N: usize>(&self) -> bool {
+        match invertibility_bound(MOD_Q, N) {
+            Some(bound) => ((2 * self.infinity_norm()) as f64) < bound,
+            None => false,
+        }
+    }
+}
+
+fn log2_binomial(n: usize, k: usize) -> f64 {
+    (0..k).map(|i| ((n - i) as f64 / (i + 1) as f64).log2()).sum()
+}
+
+/// Places `count` coefficients of absolute value `magnitude` with random
+/// signs at distinct random positions, for every `(count, magnitude)`. The
+/// positions are the first entries of a partial Fisher–Yates shuffle.
+fn sample_fixed_weight<const MOD_Q: u64, const N: usize>(
+    uniform_below: &mut impl FnMut(u64) -> u64,
+    weights: &[(usize, u64)],
+) -> CyclotomicRing<MOD_Q, N> {
+    let total: usize = weights.iter().map(|&(count, _)| count).sum();
+    assert!(total <= N, "{total} nonzero coefficients do not fit in degree {N}");
+    let mut positions: [usize; N] = std::array::from_fn(|i| i);
+    for i in 0..total {
+        let j = i + uniform_below((N - i) as u64) as usize;
+        positions.swap(i, j);
+    }
+    let mut result = CyclotomicRing::<MOD_Q, N>::new();
+    let mut positions = positions[..total].iter();
+    for &(count, magnitude) in weights {
+        for &position in positions.by_ref().take(count) {
+            result.data[position] = if uniform_below(2) == 1 { MOD_Q - magnitude } else { magnitude };
+        }
+    }
+    result
+}
+
+/// max_j |c(ζ^(2j+1))| over the canonical embedding, ζ = e^(iπ/N). The
+/// coefficients are real, so the conjugate half of the embeddings is
+/// skipped.
+pub fn operator_norm<const MOD_Q: u64, const N: usize>(c: &CyclotomicRing<MOD_Q, N>) -> f64 {
+    let coefficients = c.centered_coefficients();
+    (0..N / 2)
+        .map(|j| {
+            let angle = std::f64::consts::PI * (2 * j + 1) as f64 / N as f64;
+            let (mut re, mut im) = (0.0, 0.0);
+            for (i, &coefficient) in coefficients.iter().enumerate() {
+                let (sin, cos) = (angle * i as f64).sin_cos();
+                re += coefficient as f64 * cos;
+                im += coefficient as f64 * sin;
+            }
+            re.hypot(im)
+        })
+        .fold(0.0, f64::max)
+}
+
+/// The ℓ1 norm, a cheap upper bound on the operator norm.
+pub fn operator_norm_bound<const MOD_Q: u64, const N: usize>(c: &CyclotomicRing<MOD_Q, N>) -> u64 {
+    c.centered_coefficients().iter().map(|x| x.unsigned_abs()).sum()
+}
+
+/// Lyubashevsky–Seiler: if X^N + 1 splits mod the prime q into k binomials
+/// X^(N/k) - r (q ≡ 1 mod 4), every y with 0 < ‖y‖∞ < q^(1/k) / √k is
+/// invertible. Returns that bound, or `None` if the theorem does not apply.
+pub fn invertibility_bound(modulus: u64, n: usize) -> Option<f64> {
+    if modulus % 4 != 1 {
+        return None;
+    }
+    let k = splitting(modulus, n).ok()?.slot_count as f64;
+    Some((modulus as f64).powf(1.0 / k) / k.sqrt())
+}
+
+/// First pair (i, j), i < j, of distinct challenges whose difference is not
+/// invertible, checked slot-wise in the splitting of X^N + 1.
+pub fn find_non_invertible_difference<const MOD_Q: u64, const N: usize>(
+    challenges: &[CyclotomicRing<MOD_Q, N>],
+) -> Option<(usize, usize)> {
+    for i in 0..challenges.len() {
+        for j in i + 1..challenges.len() {
+            let difference = challenges[i] - challenges[j];
+            if difference != CyclotomicRing::new() && !difference.is_invertible() {
+                return Some((i, j));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra::PolynomialRing;
+    use crate::cyclotomic_ring::naive_multiply;
+
+    const MOD_Q: u64 = 1125899904679937;
+    // q ≡ 5 mod 8: X^64 + 1 splits into two factors X^32 ± r.
+    const MOD_Q_TWO_SLOTS: u64 = 1125899906842829;
+    const N: usize = 64;
+
+    #[test]
+    fn test_fixed_weight_ternary() {
+        let set = ChallengeSet::FixedWeightTernary { weight: 20 };
+        let c = set.sample::<MOD_Q, N>();
+        let coefficients = c.centered_coefficients();
+        assert_eq!(coefficients.iter().filter(|&&x| x != 0).count(), 20);
+        assert!(coefficients.iter().all(|x| x.abs() <= 1));
+        assert!(set.log2_size(N) > 60.0);
+    }
+
+    #[test]
+    fn test_labrador_rejection() {
+        let set = ChallengeSet::LABRADOR_64;
+        for _ in 0..20 {
+            let c = set.sample::<MOD_Q, N>();
+            let coefficients = c.centered_coefficients();
+            assert_eq!(coefficients.iter().filter(|&&x| x == 0).count(), 23);
+            assert_eq!(coefficients.iter().filter(|x| x.abs() == 1).count(), 31);
+            assert_eq!(coefficients.iter().filter(|x| x.abs() == 2).count(), 10);
+            assert!(operator_norm(&c) <= 15.0);
+            assert!(operator_norm(&c) <= operator_norm_bound(&c) as f64);
+        }
+    }
+
+    #[test]
+    fn test_operator_norm_bounds_products() {
+        let mut x_to_the_5 = CyclotomicRing::<MOD_Q, N>::new();
+        x_to_the_5.data[5] = 1;
+        assert!((operator_norm(&x_to_the_5) - 1.0).abs() < 1e-9);
+
+        let c = ChallengeSet::LABRADOR_64.sample::<MOD_Q, N>();
+        let norm = operator_norm(&c);
+        for _ in 0..10 {
+            let x = CyclotomicRing::<MOD_Q, N>::random_bounded(1 << 10);
+            let product = naive_multiply(&mut c.clone(), &mut x.clone());
+            let ratio = (product.l2_norm_squared() as f64 / x.l2_norm_squared() as f64).sqrt();
+            assert!(ratio <= norm + 1e-6, "‖cx‖/‖x‖ = {ratio} exceeds ‖c‖_op = {norm}");
+        }
+    }
+
+    #[test]
+    fn test_invertibility_of_differences() {
+        // Fully splitting: the bound q^(1/64)/8 is below 1 and proves nothing,
+        // so check concrete challenges slot by slot instead.
+        let set = ChallengeSet::FixedWeightTernary { weight: 32 };
+        assert!(!set.differences_provably_invertible::<MOD_Q, N>());
+        let challenges: Vec<_> = (0..20).map(|_| set.sample::<MOD_Q, N>()).collect();
+        assert_eq!(find_non_invertible_difference(&challenges), None);
+
+        // Two slots: every difference of norm ≤ 4 < √q / √2 is invertible.
+        assert!(ChallengeSet::LABRADOR_64.differences_provably_invertible::<MOD_Q_TWO_SLOTS, N>());
+        let challenges: Vec<_> = (0..5).map(|_| ChallengeSet::LABRADOR_64.sample::<MOD_Q_TWO_SLOTS, N>()).collect();
+        assert_eq!(find_non_invertible_difference(&challenges), None);
+
+        // Elements that differ in a single NTT slot.
+        let mut c = CyclotomicRing::<MOD_Q, N>::new();
+        c.to_ntt_representation();
+        let mut d = c;
+        d.data[7] = 1;
+        assert_eq!(find_non_invertible_difference(&[c, d]), Some((0, 1)));
+    }
+}
@@ -0,0 +1,526 @@
+//! circom's `.r1cs` and `.wtns` binary formats (iden3 binfile: a magic,
+//! a version and a list of typed, length-prefixed sections, all integers
+//! little-endian and field elements as `n8`-byte little-endian integers
+//! below the prime p).
+//!
+//! circom works over a large prime field (BN254 by default). Re-encoding
+//! over Z_q maps each value to its centered representative in (-p/2, p/2)
+//! and then mod q, and fails if that representative does not fit in
+//! (-q/2, q/2). If p = q the import is exact. Otherwise it is exact for
+//! circuits whose values never wrap around p or q, which
+//! [`Ccs::check`](crate::ccs::Ccs::check) on the imported witness confirms.
+//!
+//! Wire 0 is the constant one, followed by the public outputs, public
+//! inputs, private inputs and internal wires, which matches the z layout of
+//! [`crate::ccs`].
+
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+use crate::ccs::{R1cs, SparseMatrix};
+use crate::cyclotomic_ring::CyclotomicRing;
+use crate::slots::encode_ntt_slots;
+
+const R1CS_MAGIC: &[u8; 4] = b"r1cs";
+const WTNS_MAGIC: &[u8; 4] = b"wtns";
+const R1CS_HEADER: u32 = 1;
+const R1CS_CONSTRAINTS: u32 = 2;
+const WTNS_HEADER: u32 = 1;
+const WTNS_VALUES: u32 = 2;
+
+#[derive(Debug)]
+pub enum CircomError {
+    Io(std::io::Error),
+    BadMagic { expected: &'static str },
+    UnsupportedVersion(u32),
+    MissingSection(u32),
+    /// The file ends inside a section or field.
+    Truncated,
+    /// Field elements must be a positive multiple of 8 bytes.
+    BadFieldSize(u32),
+    /// The witness was computed over a different prime than the circuit.
+    PrimeMismatch,
+    WitnessLength { expected: usize, actual: usize },
+    /// Wire 0 of the witness is not 1.
+    ConstantWireNotOne,
+    /// The header declares no constant wire, or more inputs than wires.
+    InconsistentWires { num_wires: usize, num_inputs: usize },
+    /// A constraint refers to a wire the circuit does not have.
+    WireOutOfRange { constraint: usize, wire: usize },
+    /// A constraint coefficient does not fit in (-q/2, q/2).
+    CoefficientOutOfRange { constraint: usize, modulus: u64 },
+    /// A witness value does not fit in (-q/2, q/2).
+    WitnessOutOfRange { wire: usize, modulus: u64 },
+}
+
+impl fmt::Display for CircomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CircomError::Io(error) => write!(f, "{error}"),
+            CircomError::BadMagic { expected } => write!(f, "not a {expected} file"),
+            CircomError::UnsupportedVersion(version) => write!(f, "unsupported file version {version}"),
+            CircomError::MissingSection(section) => write!(f, "missing section {section}"),
+            CircomError::Truncated => write!(f, "file is truncated"),
+            CircomError::BadFieldSize(n8) => write!(f, "field elements of {n8} bytes are not supported"),
+            CircomError::PrimeMismatch => write!(f, "circuit and witness use different primes"),
+            CircomError::WitnessLength { expected, actual } => {
+                write!(f, "witness has {actual} values, the circuit has {expected} wires")
+            }
+            CircomError::ConstantWireNotOne => write!(f, "wire 0 of the witness is not 1"),
+            CircomError::InconsistentWires { num_wires, num_inputs } => {
+                write!(f, "{num_wires} wires cannot hold the constant wire and {num_inputs} inputs")
+            }
+            CircomError::WireOutOfRange { constraint, wire } => {
+                write!(f, "constraint {constraint} refers to wire {wire}, which does not exist")
+            }
+            CircomError::CoefficientOutOfRange { constraint, modulus } => {
+                write!(f, "a coefficient of constraint {constraint} does not fit modulo {modulus}")
+            }
+            CircomError::WitnessOutOfRange { wire, modulus } => {
+                write!(f, "the value of wire {wire} does not fit modulo {modulus}")
+            }
+        }
+    }
+}
+
+impl Error for CircomError {}
+
+impl From<std::io::Error> for CircomError {
+    fn from(error: std::io::Error) -> Self {
+        CircomError::Io(error)
+    }
+}
+
+/// An element of F_p as its little-endian bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldBytes(pub Vec<u8>);
+
+/// Compares little-endian integers of equal length.
+fn compare(a: &[u8], b: &[u8]) -> Ordering {
+    a.iter().rev().cmp(b.iter().rev())
+}
+
+/// a - b for a ≥ b, little-endian.
+fn subtract(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let mut borrow = 0i16;
+    a.iter()
+        .zip(b)
+        .map(|(&x, &y)| {
+            let mut difference = x as i16 - y as i16 - borrow;
+            borrow = (difference < 0) as i16;
+            if difference < 0 {
+                difference += 256;
+            }
+            difference as u8
+        })
+        .collect()
+}
+
+fn to_u128(bytes: &[u8]) -> Option<u128> {
+    if bytes.iter().skip(16).any(|&b| b != 0) {
+        return None;
+    }
+    Some(bytes.iter().take(16).rev().fold(0u128, |acc, &b| acc << 8 | b as u128))
+}
+
+fn double(bytes: &[u8]) -> Vec<u8> {
+    let mut carry = 0u8;
+    let mut doubled: Vec<u8> = bytes
+        .iter()
+        .map(|&b| {
+            let out = b << 1 | carry;
+            carry = b >> 7;
+            out
+        })
+        .collect();
+    doubled.push(carry);
+    doubled
+}
+
+impl FieldBytes {
+    /// The centered representative of this element of F_p, reduced mod
+    /// `modulus`, or `None` if it is not below p or does not fit in
+    /// (-q/2, q/2). For p = q the value is taken as it is.
+    pub fn to_zq(&self, prime: &FieldBytes, modulus: u64) -> Option<u64> {
+        if self.0.len() != prime.0.len() || compare(&self.0, &prime.0) != Ordering::Less {
+            return None;
+        }
+        if to_u128(&prime.0) == Some(modulus as u128) {
+            return to_u128(&self.0).map(|value| value as u64);
+        }
+        let mut prime_padded = prime.0.clone();
+        prime_padded.push(0);
+        let negative = compare(&double(&self.0), &prime_padded) != Ordering::Less;
+        let magnitude = if negative { to_u128(&subtract(&prime.0, &self.0))? } else { to_u128(&self.0)? };
+        if magnitude > (modulus as u128 - 1) / 2 {
+            return None;
+        }
+        let magnitude = magnitude as u64;
+        Some(if negative && magnitude != 0 { modulus - magnitude } else { magnitude })
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], CircomError> {
+        if self.bytes.len() < len {
+            return Err(CircomError::Truncated);
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32, CircomError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, CircomError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn field(&mut self, n8: usize) -> Result<FieldBytes, CircomError> {
+        Ok(FieldBytes(self.take(n8)?.to_vec()))
+    }
+}
+
+/// Checks magic and version and returns the sections by type; of repeated
+/// types the first one wins.
+fn sections<'a>(
+    bytes: &'a [u8],
+    magic: &[u8; 4],
+    name: &'static str,
+    max_version: u32,
+) -> Result<Vec<(u32, &'a [u8])>, CircomError> {
+    let mut reader = Reader { bytes };
+    if reader.take(4).map_err(|_| CircomError::BadMagic { expected: name })? != magic {
+        return Err(CircomError::BadMagic { expected: name });
+    }
+    let version = reader.u32()?;
+    if version == 0 || version > max_version {
+        return Err(CircomError::UnsupportedVersion(version));
+    }
+    let count = reader.u32()?;
+    // Every section header takes 12 bytes, so a corrupt count cannot
+    // reserve more than the input could hold.
+    let mut sections = Vec::with_capacity((count as usize).min(reader.bytes.len() / 12));
+    for _ in 0..count {
+        let kind = reader.u32()?;
+        let len = usize::try_from(reader.u64()?).map_err(|_| CircomError::Truncated)?;
+        sections.push((kind, reader.take(len)?));
+    }
+    Ok(sections)
+}
+
+fn section<'a>(sections: &[(u32, &'a [u8])], kind: u32) -> Result<Reader<'a>, CircomError> {
+    sections
+        .iter()
+        .find(|&&(k, _)| k == kind)
+        .map(|&(_, bytes)| Reader { bytes })
+        .ok_or(CircomError::MissingSection(kind))
+}
+
+/// Field size and prime at the start of both header sections.
+fn field_header(reader: &mut Reader) -> Result<(usize, FieldBytes), CircomError> {
+    let n8 = reader.u32()?;
+    if n8 == 0 || n8 % 8 != 0 {
+        return Err(CircomError::BadFieldSize(n8));
+    }
+    let prime = reader.field(n8 as usize)?;
+    Ok((n8 as usize, prime))
+}
+
+/// Σ coefficient·z[wire].
+pub type LinearCombination = Vec<(usize, FieldBytes)>;
+
+/// A circom constraint system: A·z ∘ B·z = C·z over F_p.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CircomR1cs {
+    pub prime: FieldBytes,
+    pub num_wires: usize,
+    pub num_public_outputs: usize,
+    pub num_public_inputs: usize,
+    pub num_private_inputs: usize,
+    pub constraints: Vec<[LinearCombination; 3]>,
+}
+
+pub fn parse_r1cs(bytes: &[u8]) -> Result<CircomR1cs, CircomError> {
+    let sections = sections(bytes, R1CS_MAGIC, "r1cs", 1)?;
+    let mut header = section(&sections, R1CS_HEADER)?;
+    let (n8, prime) = field_header(&mut header)?;
+    let num_wires = header.u32()? as usize;
+    let num_public_outputs = header.u32()? as usize;
+    let num_public_inputs = header.u32()? as usize;
+    let num_private_inputs = header.u32()? as usize;
+    let _num_labels = header.u64()?;
+    let num_constraints = header.u32()? as usize;
+
+    let mut body = section(&sections, R1CS_CONSTRAINTS)?;
+    // Three term counts of 4 bytes each at least per constraint.
+    let mut constraints = Vec::with_capacity(num_constraints.min(body.bytes.len() / 12));
+    for _ in 0..num_constraints {
+        let mut read_lc = || -> Result<LinearCombination, CircomError> {
+            let terms = body.u32()?;
+            (0..terms).map(|_| Ok((body.u32()? as usize, body.field(n8)?))).collect()
+        };
+        constraints.push([read_lc()?, read_lc()?, read_lc()?]);
+    }
+    let circuit =
+        CircomR1cs { prime, num_wires, num_public_outputs, num_public_inputs, num_private_inputs, constraints };
+    circuit.check_wires()?;
+    Ok(circuit)
+}
+
+pub fn read_r1cs(path: impl AsRef<Path>) -> Result<CircomR1cs, CircomError> {
+    parse_r1cs(&std::fs::read(path)?)
+}
+
+/// The values of all wires, wire 0 first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CircomWitness {
+    pub prime: FieldBytes,
+    pub values: Vec<FieldBytes>,
+}
+
+pub fn parse_wtns(bytes: &[u8]) -> Result<CircomWitness, CircomError> {
+    let sections = sections(bytes, WTNS_MAGIC, "wtns", 2)?;
+    let mut header = section(&sections, WTNS_HEADER)?;
+    let (n8, prime) = field_header(&mut header)?;
+    let count = header.u32()?;
+    let mut body = section(&sections, WTNS_VALUES)?;
+    let values = (0..count).map(|_| body.field(n8)).collect::<Result<_, _>>()?;
+    Ok(CircomWitness { prime, values })
+}
+
+pub fn read_wtns(path: impl AsRef<Path>) -> Result<CircomWitness, CircomError> {
+    parse_wtns(&std::fs::read(path)?)
+}
+
+impl CircomR1cs {
+    pub fn num_public(&self) -> usize {
+        self.num_public_outputs + self.num_public_inputs
+    }
+
+    /// Checks that the wires hold the constant one and every input, and
+    /// that every constraint refers to existing wires.
+    fn check_wires(&self) -> Result<(), CircomError> {
+        let num_inputs = [self.num_public_outputs, self.num_public_inputs, self.num_private_inputs]
+            .into_iter()
+            .try_fold(0usize, usize::checked_add);
+        match num_inputs {
+            Some(num_inputs) if num_inputs < self.num_wires => {}
+            num_inputs => {
+                let num_inputs = num_inputs.unwrap_or(usize::MAX);
+                return Err(CircomError::InconsistentWires { num_wires: self.num_wires, num_inputs });
+            }
+        }
+        for (constraint, lcs) in self.constraints.iter().enumerate() {
+            if let Some(&(wire, _)) = lcs.iter().flatten().find(|&&(wire, _)| wire >= self.num_wires) {
+                return Err(CircomError::WireOutOfRange { constraint, wire });
+            }
+        }
+        Ok(())
+    }
+
+    /// The constraints over Z_q, coefficients as constant ring elements.
+    pub fn to_r1cs<const MOD_Q: u64, const N: usize>(&self) -> Result<R1cs<MOD_Q, N>, CircomError> {
+        self.check_wires()?;
+        let rows = self.constraints.len();
+        let mut matrices = [(); 3].map(|_| SparseMatrix::new(rows, self.num_wires));
+        for (row, constraint) in self.constraints.iter().enumerate() {
+            for (matrix, lc) in matrices.iter_mut().zip(constraint) {
+                for (wire, coefficient) in lc {
+                    let value = coefficient
+                        .to_zq(&self.prime, MOD_Q)
+                        .ok_or(CircomError::CoefficientOutOfRange { constraint: row, modulus: MOD_Q })?;
+                    matrix.push_scalar(row, *wire, value);
+                }
+            }
+        }
+        let [a, b, c] = matrices;
+        Ok(R1cs { num_public: self.num_public(), a, b, c })
+    }
+
+    /// Checks `witness` against the circuit and re-encodes its values over
+    /// Z_q, wire 0 dropped.
+    fn witness_to_zq(&self, witness: &CircomWitness, modulus: u64) -> Result<Vec<u64>, CircomError> {
+        self.check_wires()?;
+        if witness.prime != self.prime {
+            return Err(CircomError::PrimeMismatch);
+        }
+        if witness.values.len() != self.num_wires {
+            return Err(CircomError::WitnessLength { expected: self.num_wires, actual: witness.values.len() });
+        }
+        let values = witness
+            .values
+            .iter()
+            .enumerate()
+            .map(|(wire, value)| value.to_zq(&self.prime, modulus).ok_or(CircomError::WitnessOutOfRange { wire, modulus }))
+            .collect::<Result<Vec<_>, _>>()?;
+        match values.split_first() {
+            Some((1, rest)) => Ok(rest.to_vec()),
+            _ => Err(CircomError::ConstantWireNotOne),
+        }
+    }
+
+    /// (public, witness) for [`R1cs::check`], every value a constant ring
+    /// element.
+    #[allow(clippy::type_complexity)]
+    pub fn ring_witness<const MOD_Q: u64, const N: usize>(
+        &self,
+        witness: &CircomWitness,
+    ) -> Result<(Vec<CyclotomicRing<MOD_Q, N>>, Vec<CyclotomicRing<MOD_Q, N>>), CircomError> {
+        let values = self.witness_to_zq(witness, MOD_Q)?;
+        let mut rings: Vec<_> = values.iter().map(|&v| CyclotomicRing::constant(v)).collect();
+        let private = rings.split_off(self.num_public());
+        Ok((rings, private))
+    }
+
+    /// Up to N witnesses of the same circuit packed into the NTT slots:
+    /// slot t of every wire holds witness t (zero-padded with copies of the
+    /// first). The coefficients are constants, which act on every slot
+    /// alike, so the packed witness satisfies the imported R1CS iff each
+    /// witness does. Needs q ≡ 1 mod 2N.
+    #[allow(clippy::type_complexity)]
+    pub fn packed_ring_witness<const MOD_Q: u64, const N: usize>(
+        &self,
+        witnesses: &[CircomWitness],
+    ) -> Result<(Vec<CyclotomicRing<MOD_Q, N>>, Vec<CyclotomicRing<MOD_Q, N>>), CircomError> {
+        assert!(!witnesses.is_empty() && witnesses.len() <= N, "between 1 and {N} witnesses fit in the slots");
+        let values = witnesses.iter().map(|w| self.witness_to_zq(w, MOD_Q)).collect::<Result<Vec<_>, _>>()?;
+        let mut rings: Vec<CyclotomicRing<MOD_Q, N>> = (0..self.num_wires - 1)
+            .map(|wire| {
+                let slots: Vec<u64> = (0..N).map(|t| values.get(t).unwrap_or(&values[0])[wire]).collect();
+                encode_ntt_slots(&slots)
+            })
+            .collect();
+        let private = rings.split_off(self.num_public());
+        Ok((rings, private))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ccs::CcsError;
+    use crate::slots::decode_ntt_slots;
+
+    const MOD_Q: u64 = 1125899904679937;
+    const N: usize = 64;
+
+    fn testdata(name: &str) -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join(name)
+    }
+
+    #[test]
+    fn test_multiplier_circuit() {
+        let circuit = read_r1cs(testdata("multiplier.r1cs")).unwrap();
+        assert_eq!((circuit.num_wires, circuit.num_public(), circuit.constraints.len()), (4, 1, 1));
+        let r1cs = circuit.to_r1cs::<MOD_Q, N>().unwrap();
+
+        for file in ["multiplier.wtns", "multiplier_negative.wtns"] {
+            let witness = read_wtns(testdata(file)).unwrap();
+            let (public, private) = circuit.ring_witness::<MOD_Q, N>(&witness).unwrap();
+            assert_eq!(r1cs.check(&public, &private), Ok(()));
+        }
+        let (public, _) = circuit.ring_witness::<MOD_Q, N>(&read_wtns(testdata("multiplier_negative.wtns")).unwrap()).unwrap();
+        assert_eq!(public[0].centered_coefficients()[0], -33);
+
+        // Both witnesses in the NTT slots at once.
+        let witnesses = [read_wtns(testdata("multiplier.wtns")).unwrap(), read_wtns(testdata("multiplier_negative.wtns")).unwrap()];
+        let (public, private) = circuit.packed_ring_witness::<MOD_Q, N>(&witnesses).unwrap();
+        assert_eq!(r1cs.check(&public, &private), Ok(()));
+        assert_eq!(decode_ntt_slots(&public[0])[..2], [33, MOD_Q - 33]);
+
+        // A wrong output breaks the constraint.
+        let mut wrong = witnesses[0].clone();
+        wrong.values[1] = wrong.values[2].clone();
+        let (public, private) = circuit.ring_witness::<MOD_Q, N>(&wrong).unwrap();
+        assert_eq!(r1cs.check(&public, &private), Err(CcsError::Unsatisfied { row: 0 }));
+    }
+
+    #[test]
+    fn test_import_errors() {
+        let r1cs_bytes = std::fs::read(testdata("multiplier.r1cs")).unwrap();
+        let wtns_bytes = std::fs::read(testdata("multiplier.wtns")).unwrap();
+        let circuit = parse_r1cs(&r1cs_bytes).unwrap();
+
+        assert!(matches!(parse_r1cs(&wtns_bytes), Err(CircomError::BadMagic { expected: "r1cs" })));
+        assert!(matches!(parse_r1cs(&r1cs_bytes[..100]), Err(CircomError::Truncated)));
+
+        // Counts far beyond the input end in Truncated, not in a huge
+        // allocation: the section count at byte 8, the constraint count at
+        // byte 84.
+        for offset in [8, 84] {
+            let mut huge_count = r1cs_bytes.clone();
+            huge_count[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+            assert!(matches!(parse_r1cs(&huge_count), Err(CircomError::Truncated)));
+        }
+
+        // The first wire of the first constraint is at byte 104.
+        let mut bad_wire = r1cs_bytes.clone();
+        bad_wire[104..108].copy_from_slice(&7u32.to_le_bytes());
+        assert!(matches!(parse_r1cs(&bad_wire), Err(CircomError::WireOutOfRange { constraint: 0, wire: 7 })));
+        let mut edited = circuit.clone();
+        edited.constraints[0][2][0].0 = 4;
+        assert!(matches!(edited.to_r1cs::<MOD_Q, N>(), Err(CircomError::WireOutOfRange { constraint: 0, wire: 4 })));
+
+        // No wires at all: not even the constant one.
+        let mut no_wires = r1cs_bytes.clone();
+        no_wires[60..64].copy_from_slice(&0u32.to_le_bytes());
+        assert!(matches!(parse_r1cs(&no_wires), Err(CircomError::InconsistentWires { num_wires: 0, num_inputs: 3 })));
+        let empty = CircomR1cs { num_wires: 0, constraints: Vec::new(), ..circuit.clone() };
+        let witness = CircomWitness { prime: circuit.prime.clone(), values: Vec::new() };
+        assert!(matches!(empty.ring_witness::<MOD_Q, N>(&witness), Err(CircomError::InconsistentWires { .. })));
+        assert!(matches!(
+            empty.packed_ring_witness::<MOD_Q, N>(&[witness]),
+            Err(CircomError::InconsistentWires { .. })
+        ));
+
+        // The witness prime is the header's first field element, at byte 28.
+        let mut other_prime = wtns_bytes.clone();
+        other_prime[28] ^= 2;
+        let witness = parse_wtns(&other_prime).unwrap();
+        assert!(matches!(circuit.ring_witness::<MOD_Q, N>(&witness), Err(CircomError::PrimeMismatch)));
+
+        // 2^100 has no representative in (-q/2, q/2).
+        let mut witness = parse_wtns(&wtns_bytes).unwrap();
+        witness.values[3].0[12] = 1;
+        assert!(matches!(
+            circuit.ring_witness::<MOD_Q, N>(&witness),
+            Err(CircomError::WitnessOutOfRange { wire: 3, modulus: MOD_Q })
+        ));
+        // p itself is not a field element.
+        witness.values[3] = circuit.prime.clone();
+        assert!(matches!(
+            circuit.ring_witness::<MOD_Q, N>(&witness),
+            Err(CircomError::WitnessOutOfRange { wire: 3, modulus: MOD_Q })
+        ));
+        witness.values.pop();
+        assert!(matches!(
+            circuit.ring_witness::<MOD_Q, N>(&witness),
+            Err(CircomError::WitnessLength { expected: 4, actual: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_centered_reencoding() {
+        let p = FieldBytes(vec![251, 0, 0, 0, 0, 0, 0, 0]);
+        let value = |v: u8| FieldBytes(vec![v, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(value(5).to_zq(&p, 97), Some(5));
+        assert_eq!(value(246).to_zq(&p, 97), Some(92)); // -5
+        assert_eq!(value(48).to_zq(&p, 97), Some(48));
+        assert_eq!(value(49).to_zq(&p, 97), None);
+        assert_eq!(value(203).to_zq(&p, 97), Some(49)); // -48
+        // p = q: taken as is.
+        assert_eq!(value(200).to_zq(&p, 251), Some(200));
+        // Not below p.
+        assert_eq!(value(251).to_zq(&p, 97), None);
+        assert_eq!(value(255).to_zq(&p, 251), None);
+    }
+}
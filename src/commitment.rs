@@ -0,0 +1,321 @@
+//! Ajtai commitments over R_q: cm = A·w for a uniform A ∈ R_q^(κ×n).
+//!
+//! Binding rests on Module-SIS: two openings w ≠ w' of the same
+//! commitment give a solution A·(w - w') = 0, which is short as long as
+//! both openings are. So a commitment only binds together with a norm
+//! bound on its opening, and the folding schemes track that bound.
+//!
+//! The map is R_q-linear, which is what folding uses: Σ ρ_i·cm_i is a
+//! commitment to Σ ρ_i·w_i, and Σ b^k·cm_k to the recomposition of gadget
+//! digits w_k.
+//!
+//! A double commitment (LatticeFold+) commits to a matrix in two layers:
+//! each column is committed to under the inner key, the coefficients of
+//! those commitments are split into short gadget digits, and the digits
+//! are committed to again under the outer key. The result has κ elements
+//! however many columns there are, and binds like a single commitment
+//! since both openings are short. With one key for both layers the
+//! zero-padded digits have the shape of a column, so LatticeFold+ can fold
+//! them like any other witness instead of sending the inner commitments.
+
+use crate::algebra::{PolynomialRing, RingElement};
+use crate::cyclotomic_ring::CyclotomicRing;
+use crate::decomposition::balanced_digit_bound;
+use crate::transcript::Transcript;
+
+/// The public matrix A, in multiplication form.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AjtaiKey<const MOD_Q: u64, const N: usize> {
+    pub rows: Vec<Vec<CyclotomicRing<MOD_Q, N>>>,
+}
+
+impl<const MOD_Q: u64, const N: usize> AjtaiKey<MOD_Q, N> {
+    /// A κ × `len` matrix expanded from `seed` with SHAKE128, so a key is
+    /// published as its seed.
+    pub fn setup(kappa: usize, len: usize, seed: &[u8]) -> Self {
+        let mut transcript = Transcript::new(b"ajtai commitment key");
+        transcript.append_u64(b"kappa", kappa as u64);
+        transcript.append_u64(b"len", len as u64);
+        transcript.append_bytes(b"seed", seed);
+        let mut rng = transcript.challenge_rng(b"matrix");
+        let rows = (0..kappa)
+            .map(|_| {
+                (0..len)
+                    .map(|_| {
+                        let mut entry = CyclotomicRing::new();
+                        entry.data.iter_mut().for_each(|c| *c = rng.uniform_below(MOD_Q));
+                        entry.prepare();
+                        entry
+                    })
+                    .collect()
+            })
+            .collect();
+        Self { rows }
+    }
+
+    /// κ, the number of ring elements in a commitment.
+    pub fn kappa(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Length of the committed vectors.
+    pub fn witness_len(&self) -> usize {
+        self.rows.first().map_or(0, Vec::len)
+    }
+
+    /// A·w, in multiplication form.
+    pub fn commit(&self, witness: &[CyclotomicRing<MOD_Q, N>]) -> Vec<CyclotomicRing<MOD_Q, N>> {
+        assert_eq!(witness.len(), self.witness_len(), "witness length does not match the key");
+        let mut prepared = witness.to_vec();
+        prepared.iter_mut().for_each(RingElement::prepare);
+        self.rows
+            .iter()
+            .map(|row| row.iter().zip(&prepared).fold(RingElement::zero(), |sum, (&a, &w)| sum + a * w))
+            .collect()
+    }
+
+    /// Whether `witness` opens `commitment` with every coefficient of
+    /// absolute value below `norm_bound`.
+    pub fn verify_opening(
+        &self,
+        commitment: &[CyclotomicRing<MOD_Q, N>],
+        witness: &[CyclotomicRing<MOD_Q, N>],
+        norm_bound: u64,
+    ) -> bool {
+        witness.len() == self.witness_len()
+            && witness.iter().all(|w| w.infinity_norm() < norm_bound)
+            && self.commit(witness).as_slice() == commitment
+    }
+}
+
+/// Keys for committing to `columns` column vectors at once.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DoubleCommitmentKey<const MOD_Q: u64, const N: usize> {
+    /// Commits to each column.
+    pub inner: AjtaiKey<MOD_Q, N>,
+    /// Commits to the gadget digits of all inner commitments.
+    pub outer: AjtaiKey<MOD_Q, N>,
+    pub gadget_base: u64,
+    pub gadget_digits: usize,
+    columns: usize,
+    /// The inner key in coefficient form, for monomial columns.
+    inner_coefficients: Vec<Vec<[u64; N]>>,
+}
+
+impl<const MOD_Q: u64, const N: usize> DoubleCommitmentKey<MOD_Q, N> {
+    /// `gadget_digits` balanced base-`gadget_base` digits must represent
+    /// every element of Z_q, or this panics. The outer key has the inner
+    /// key's κ.
+    pub fn new(inner: AjtaiKey<MOD_Q, N>, columns: usize, gadget_base: u64, gadget_digits: usize, seed: &[u8]) -> Self {
+        let kappa = inner.kappa();
+        let outer = AjtaiKey::setup(kappa, kappa * columns * gadget_digits, seed);
+        Self::with_outer(inner, outer, columns, gadget_base, gadget_digits)
+    }
+
+    /// Both layers under `key`, whose length must hold the digits of all
+    /// inner commitments.
+    pub fn sharing_key(key: AjtaiKey<MOD_Q, N>, columns: usize, gadget_base: u64, gadget_digits: usize) -> Self {
+        assert!(
+            key.witness_len() >= key.kappa() * columns * gadget_digits,
+            "the key is too short for the digits of {columns} inner commitments"
+        );
+        Self::with_outer(key.clone(), key, columns, gadget_base, gadget_digits)
+    }
+
+    fn with_outer(
+        inner: AjtaiKey<MOD_Q, N>,
+        outer: AjtaiKey<MOD_Q, N>,
+        columns: usize,
+        gadget_base: u64,
+        gadget_digits: usize,
+    ) -> Self {
+        assert!(
+            balanced_digit_bound(gadget_base, gadget_digits) >= (MOD_Q / 2) as u128,
+            "{gadget_digits} base-{gadget_base} digits cannot represent Z_{MOD_Q}"
+        );
+        let inner_coefficients =
+            inner.rows.iter().map(|row| row.iter().map(CyclotomicRing::canonical_coefficients).collect()).collect();
+        Self { inner, outer, gadget_base, gadget_digits, columns, inner_coefficients }
+    }
+
+    /// Number of columns of the committed matrices.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// The inner commitment to a column of monomials X^e, `None` standing
+    /// for 0. Multiplying by X^e is a negacyclic rotation, so this takes
+    /// additions only.
+    pub fn commit_monomials(&self, column: &[Option<usize>]) -> Vec<CyclotomicRing<MOD_Q, N>> {
+        assert_eq!(column.len(), self.inner.witness_len(), "column length does not match the key");
+        self.inner_coefficients
+            .iter()
+            .map(|row| {
+                let mut sum = [0u64; N];
+                for (a, e) in row.iter().zip(column) {
+                    let Some(e) = *e else { continue };
+                    // a[t] moves to t + e, and wraps around negated. Both
+                    // sides are canonical, so one conditional subtraction
+                    // reduces.
+                    for (s, &x) in sum[e..].iter_mut().zip(&a[..N - e]) {
+                        let t = *s + x;
+                        *s = if t >= MOD_Q { t - MOD_Q } else { t };
+                    }
+                    for (s, &x) in sum[..e].iter_mut().zip(&a[N - e..]) {
+                        *s = if *s >= x { *s - x } else { *s + MOD_Q - x };
+                    }
+                }
+                let mut result = CyclotomicRing::new();
+                result.data = sum;
+                result
+            })
+            .collect()
+    }
+
+    /// The gadget digits of the inner commitments, digit t of row i of
+    /// column j at (j·κ + i)·d + t, zero-padded to the outer key's length.
+    pub fn opening(&self, inner: &[Vec<CyclotomicRing<MOD_Q, N>>]) -> Vec<CyclotomicRing<MOD_Q, N>> {
+        assert_eq!(inner.len(), self.columns(), "one inner commitment per column");
+        let mut digits: Vec<_> =
+            inner.iter().flatten().flat_map(|c| c.decompose(self.gadget_base, self.gadget_digits)).collect();
+        digits.resize(self.outer.witness_len(), RingElement::zero());
+        digits
+    }
+
+    /// The outer commitment to the gadget digits of the inner commitments.
+    pub fn commit_inner(&self, inner: &[Vec<CyclotomicRing<MOD_Q, N>>]) -> Vec<CyclotomicRing<MOD_Q, N>> {
+        self.outer.commit(&self.opening(inner))
+    }
+
+    /// The double commitment to `columns` and its inner commitments, which
+    /// open it.
+    #[allow(clippy::type_complexity)]
+    pub fn commit(
+        &self,
+        columns: &[Vec<CyclotomicRing<MOD_Q, N>>],
+    ) -> (Vec<CyclotomicRing<MOD_Q, N>>, Vec<Vec<CyclotomicRing<MOD_Q, N>>>) {
+        let inner: Vec<_> = columns.iter().map(|column| self.inner.commit(column)).collect();
+        (self.commit_inner(&inner), inner)
+    }
+
+    /// Whether `inner` opens `commitment` in the outer layer and every
+    /// column opens its inner commitment with norm below `norm_bound`.
+    pub fn verify_opening(
+        &self,
+        commitment: &[CyclotomicRing<MOD_Q, N>],
+        inner: &[Vec<CyclotomicRing<MOD_Q, N>>],
+        columns: &[Vec<CyclotomicRing<MOD_Q, N>>],
+        norm_bound: u64,
+    ) -> bool {
+        inner.len() == self.columns()
+            && columns.len() == inner.len()
+            && inner.iter().all(|c| c.len() == self.inner.kappa())
+            && self.commit_inner(inner).as_slice() == commitment
+            && inner.iter().zip(columns).all(|(c, column)| self.inner.verify_opening(c, column, norm_bound))
+    }
+}
+
+/// Σ scalars[i]·vectors[i] for vectors of equal length, e.g. commitments or
+/// witnesses folded with challenges.
+pub fn linear_combination<const MOD_Q: u64, const N: usize>(
+    vectors: &[&[CyclotomicRing<MOD_Q, N>]],
+    scalars: &[CyclotomicRing<MOD_Q, N>],
+) -> Vec<CyclotomicRing<MOD_Q, N>> {
+    assert_eq!(vectors.len(), scalars.len(), "one scalar per vector");
+    let len = vectors.first().map_or(0, |v| v.len());
+    assert!(vectors.iter().all(|v| v.len() == len), "vectors of different lengths");
+    let mut result = vec![RingElement::zero(); len];
+    for (vector, &scalar) in vectors.iter().zip(scalars) {
+        let mut scalar = scalar;
+        scalar.prepare();
+        for (sum, &value) in result.iter_mut().zip(vector.iter()) {
+            let mut value = value;
+            value.prepare();
+            *sum = *sum + scalar * value;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOD_Q: u64 = 1125899904679937;
+    const N: usize = 64;
+
+    #[test]
+    fn test_commitments_are_linear() {
+        let key = AjtaiKey::<MOD_Q, N>::setup(3, 8, b"test");
+        assert_eq!(key, AjtaiKey::setup(3, 8, b"test"));
+        assert_ne!(key, AjtaiKey::setup(3, 8, b"other"));
+
+        let mut a: Vec<_> = (0..8).map(|_| CyclotomicRing::random_bounded(4)).collect();
+        a[0] = CyclotomicRing::constant(MOD_Q - 3);
+        let b: Vec<_> = (0..8).map(|_| CyclotomicRing::random_bounded(4)).collect();
+        let scalars = [CyclotomicRing::random_bounded(2), CyclotomicRing::random_bounded(2)];
+        let folded = linear_combination(&[&a, &b], &scalars);
+        assert_eq!(key.commit(&folded), linear_combination(&[&key.commit(&a), &key.commit(&b)], &scalars));
+
+        let commitment = key.commit(&a);
+        assert!(key.verify_opening(&commitment, &a, 4));
+        assert!(!key.verify_opening(&commitment, &a, 3));
+        assert!(!key.verify_opening(&commitment, &b, 4));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot represent")]
+    fn test_double_commitment_needs_enough_digits() {
+        DoubleCommitmentKey::<MOD_Q, N>::new(AjtaiKey::setup(2, 6, b"test"), 3, 1 << 13, 3, b"test outer");
+    }
+
+    #[test]
+    fn test_double_commitment() {
+        let key = DoubleCommitmentKey::<MOD_Q, N>::new(AjtaiKey::setup(2, 6, b"test"), 3, 1 << 13, 4, b"test outer");
+        assert_eq!(key.columns(), 3);
+
+        let exponents = [Some(3), None, Some(63), Some(0), None, Some(40)];
+        let monomials: Vec<_> = exponents
+            .iter()
+            .map(|e| {
+                let mut m = CyclotomicRing::new();
+                if let Some(e) = *e {
+                    m.data[e] = 1;
+                }
+                m
+            })
+            .collect();
+        assert_eq!(key.commit_monomials(&exponents), key.inner.commit(&monomials));
+
+        let columns: Vec<Vec<_>> = (0..3).map(|_| (0..6).map(|_| CyclotomicRing::random_bounded(2)).collect()).collect();
+        let (commitment, inner) = key.commit(&columns);
+        assert_eq!(commitment.len(), 2);
+        assert!(key.verify_opening(&commitment, &inner, &columns, 2));
+        assert!(!key.verify_opening(&commitment, &inner, &columns, 1));
+        assert!(!key.verify_opening(&commitment, &inner, &columns[..2], 2));
+
+        let mut other = inner.clone();
+        other[1][0] = other[1][0] + CyclotomicRing::constant(1);
+        assert!(!key.verify_opening(&commitment, &other, &columns, 2));
+        let mut swapped = columns.clone();
+        swapped.swap(0, 2);
+        assert!(!key.verify_opening(&commitment, &inner, &swapped, 2));
+
+        // One key for both layers: the padded digits recompose to the inner
+        // commitments.
+        let shared = DoubleCommitmentKey::sharing_key(AjtaiKey::setup(2, 30, b"test"), 3, 1 << 13, 4);
+        let inner: Vec<_> = columns
+            .iter()
+            .map(|column| {
+                let mut padded = column.clone();
+                padded.resize(30, RingElement::zero());
+                shared.inner.commit(&padded)
+            })
+            .collect();
+        let opening = shared.opening(&inner);
+        assert_eq!(opening.len(), 30);
+        assert!(opening[24..].iter().all(|d| *d == CyclotomicRing::new()));
+        assert_eq!(CyclotomicRing::recompose(&opening[4..8], 1 << 13), inner[0][1]);
+        assert_eq!(shared.commit_inner(&inner), shared.inner.commit(&opening));
+    }
+}
@@ -1,6 +1,14 @@
-use crate::hexl::bindings::{eltwise_add_mod, eltwise_mult_mod, eltwise_reduce_mod, eltwise_sub_mod, multiply_mod, ntt_forward_in_place, ntt_inverse_in_place, power_mod};
+#[cfg(test)]
+use crate::algebra::PolynomialRing;
+use crate::hexl::safe::{eltwise_add_mod, eltwise_fma_mod_assign, eltwise_mult_acc_lazy, eltwise_mult_add_mod_assign, eltwise_mult_mod_lazy, eltwise_reduce_mod_any_assign, eltwise_reduce_mod_assign, eltwise_reduce_mod_lazy_assign, eltwise_sub_mod, ntt_forward, ntt_forward_lazy, ntt_inverse_lazy};
+use crate::modular::centered;
+use crate::params::{RingError, RingParams};
+use crate::polymul::karatsuba_multiply;
+use crate::splitting::{fast_strategy, select_strategy, MultiplicationStrategy, StrategyError};
 use rand::Rng;
 use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::sync::{Mutex, OnceLock, RwLock};
 use std::ops::{Add,Mul,Sub};
 use std::iter::Sum;
@@ -13,11 +21,94 @@ pub enum Representation {
     IncompleteNTT,
 }
 
+/// Bound on the stored values. HEXL's NTT can skip its final reduction and
+/// leave outputs in `[0, 4q)` when the next kernel (e.g. multiplication)
+/// accepts such inputs anyway.
+#[derive(Clone, Debug, PartialEq, Eq, Copy, PartialOrd, Ord)]
+pub enum CoefficientRange {
+    Reduced,
+    LessThan2Q,
+    LessThan4Q,
+}
+
+impl CoefficientRange {
+    /// The factor `k` such that every value lies in `[0, k * q)`.
+    pub fn mod_factor(self) -> u64 {
+        match self {
+            CoefficientRange::Reduced => 1,
+            CoefficientRange::LessThan2Q => 2,
+            CoefficientRange::LessThan4Q => 4,
+        }
+    }
+}
+
 
-#[derive(Clone, Debug, Copy, PartialEq)]
+/// Equality, hashing and printing all go through the reduced coefficient
+/// form, so the same polynomial compares equal in every representation.
+#[derive(Clone, Copy)]
 pub struct CyclotomicRing<const MOD_Q: u64, const N: usize> {
     pub data: [u64; N],
     pub representation: Representation,
+    pub range: CoefficientRange,
+}
+
+impl<const MOD_Q: u64, const N: usize> PartialEq for CyclotomicRing<MOD_Q, N> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.representation == other.representation && self.range == CoefficientRange::Reduced
+            && other.range == CoefficientRange::Reduced
+        {
+            return self.data == other.data;
+        }
+        self.canonical_coefficients() == other.canonical_coefficients()
+    }
+}
+
+impl<const MOD_Q: u64, const N: usize> Eq for CyclotomicRing<MOD_Q, N> {}
+
+impl<const MOD_Q: u64, const N: usize> Hash for CyclotomicRing<MOD_Q, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical_coefficients().hash(state);
+    }
+}
+
+/// Prints the centered polynomial, lowest degree first: `3 + 4X − X^2`.
+impl<const MOD_Q: u64, const N: usize> fmt::Display for CyclotomicRing<MOD_Q, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (degree, c) in self.centered_coefficients().into_iter().enumerate() {
+            if c == 0 {
+                continue;
+            }
+            match (first, c < 0) {
+                (true, true) => write!(f, "−")?,
+                (true, false) => {}
+                (false, true) => write!(f, " − ")?,
+                (false, false) => write!(f, " + ")?,
+            }
+            first = false;
+            let magnitude = c.unsigned_abs();
+            match degree {
+                0 => write!(f, "{magnitude}")?,
+                _ if magnitude != 1 => write!(f, "{magnitude}")?,
+                _ => {}
+            }
+            match degree {
+                0 => {}
+                1 => write!(f, "X")?,
+                _ => write!(f, "X^{degree}")?,
+            }
+        }
+        if first {
+            write!(f, "0")?;
+        }
+        Ok(())
+    }
+}
+
+impl<const MOD_Q: u64, const N: usize> fmt::Debug for CyclotomicRing<MOD_Q, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self} (mod {MOD_Q}, stored as {:?})", self.representation)
+    }
 }
 
 impl <const MOD_Q: u64, const N: usize> Add<&CyclotomicRing<MOD_Q, N>> for &mut CyclotomicRing<MOD_Q, N> {
@@ -25,16 +116,16 @@ impl <const MOD_Q: u64, const N: usize> Add<&CyclotomicRing<MOD_Q, N>> for &mut
 
     fn add(self, other: &CyclotomicRing<MOD_Q, N>) -> Self::Output {
         self.adjust_representation(other.representation);
+        self.reduce();
+        let reduced_other;
+        let other = if other.range == CoefficientRange::Reduced {
+            other
+        } else {
+            reduced_other = other.reduced();
+            &reduced_other
+        };
         let mut result = CyclotomicRing::<MOD_Q, N>::new();
-        unsafe { 
-            eltwise_add_mod(
-                result.data.as_mut_ptr(),
-                self.data.as_ptr(),
-                other.data.as_ptr(),
-                N as u64,
-                MOD_Q
-            )
-        } 
+        eltwise_add_mod(&mut result.data, &self.data, &other.data, MOD_Q).expect("ring addition");
         result.representation = self.representation.clone();
         result
     }   
@@ -55,16 +146,16 @@ impl <const MOD_Q: u64, const N: usize> Sub<&CyclotomicRing<MOD_Q, N>> for &mut
 
     fn sub(self, other: &CyclotomicRing<MOD_Q, N>) -> Self::Output {
         self.adjust_representation(other.representation);
+        self.reduce();
+        let reduced_other;
+        let other = if other.range == CoefficientRange::Reduced {
+            other
+        } else {
+            reduced_other = other.reduced();
+            &reduced_other
+        };
         let mut result = CyclotomicRing::<MOD_Q, N>::new();
-        unsafe { 
-            eltwise_sub_mod(
-                result.data.as_mut_ptr(),
-                self.data.as_ptr(),
-                other.data.as_ptr(),
-                N as u64,
-                MOD_Q
-            )
-        } 
+        eltwise_sub_mod(&mut result.data, &self.data, &other.data, MOD_Q).expect("ring subtraction");
         result.representation = self.representation.clone();
         result
     }   
@@ -97,7 +188,7 @@ impl <const MOD_Q: u64, const N: usize> Mul for &mut CyclotomicRing<MOD_Q, N> {
     type Output = CyclotomicRing<MOD_Q, N>;
 
     fn mul(self, other: Self) -> Self::Output {
-        incomplete_ntt_multiplication::<MOD_Q, N>(self, other, true)
+        multiply::<MOD_Q, N>(self, other)
     }
 }
 
@@ -153,21 +244,190 @@ fn test_multiplication_same_representation() {
     assert_eq!(c.data, [0, 3, 4, 3]);
 }
 
+#[test]
+fn test_lazy_ntt_chain() {
+    const MOD_Q: u64 = 17;
+    const N: usize = 4;
+    let mut a = CyclotomicRing::<MOD_Q, N>::new();
+    let mut b = CyclotomicRing::<MOD_Q, N>::new();
+    let mut c = CyclotomicRing::<MOD_Q, N>::new();
+    a.data = [1, 2, 1, 0];
+    b.data = [1, 1, 1, 0];
+    c.data = [16, 0, 0, 1];
+    let mut expected = naive_multiply(&mut a.clone(), &mut b.clone());
+    expected = &mut expected + &c;
+
+    a.to_ntt_representation_lazy();
+    b.to_ntt_representation_lazy();
+    c.to_ntt_representation_lazy();
+    assert_eq!(a.range, CoefficientRange::LessThan4Q);
+
+    let product = fully_splitting_ntt_multiplication(&mut a, &mut b);
+    assert_eq!(product.range, CoefficientRange::Reduced);
+    assert_eq!(product + c, expected);
+}
+
+#[test]
+fn test_multiply_selects_working_strategy() {
+    fn check<const MOD_Q: u64, const N: usize>(strategy: MultiplicationStrategy, output: Representation) {
+        assert_eq!(CyclotomicRing::<MOD_Q, N>::MULTIPLICATION_STRATEGY, strategy);
+        let a = CyclotomicRing::<MOD_Q, N>::random();
+        let b = CyclotomicRing::<MOD_Q, N>::random();
+        let expected = naive_multiply(&mut a.clone(), &mut b.clone());
+        let mut product = a * b;
+        assert_eq!(product.representation, output);
+        product.to_coeff_representation();
+        assert_eq!(product, expected);
+    }
+
+    check::<17, 8>(MultiplicationStrategy::FullySplitting, Representation::NTT);
+    check::<41, 8>(MultiplicationStrategy::IncompleteNtt { degree: 2 }, Representation::IncompleteNTT);
+    check::<97, 64>(MultiplicationStrategy::IncompleteNtt { degree: 4 }, Representation::Coefficient);
+    check::<7681, 1024>(MultiplicationStrategy::IncompleteNtt { degree: 4 }, Representation::Coefficient);
+    check::<31, 16>(MultiplicationStrategy::NonNtt, Representation::Coefficient);
+
+    let mut a = CyclotomicRing::<17, 8>::random();
+    let mut b = CyclotomicRing::<17, 8>::random();
+    a.to_incomplete_ntt_representation();
+    b.to_incomplete_ntt_representation();
+    assert_eq!((a * b).representation, Representation::IncompleteNTT);
+    assert_eq!(
+        CyclotomicRing::<31, 16>::fast_multiplication_strategy(),
+        Err(StrategyError::NoFastPath { modulus: 31, n: 16 })
+    );
+}
+
+#[test]
+fn test_norms_and_decomposition() {
+    const MOD_Q: u64 = 1125899904679937;
+    const N: usize = 64;
+    let mut a = CyclotomicRing::<MOD_Q, N>::new();
+    a.data[0] = 3;
+    a.data[1] = MOD_Q - 4;
+    assert_eq!(a.infinity_norm(), 4);
+    assert_eq!(a.l2_norm_squared(), 25);
+
+    let x = CyclotomicRing::<MOD_Q, N>::random();
+    let parts = x.decompose(1 << 11, 5);
+    assert!(parts.iter().all(|part| part.infinity_norm() <= 1 << 10));
+    assert_eq!(CyclotomicRing::recompose(&parts, 1 << 11), x);
+}
+
+#[test]
+fn test_parameter_validation() {
+    assert!(CyclotomicRing::<1125899904679937, 64>::try_new().is_ok());
+    assert_eq!(
+        CyclotomicRing::<{ 1 << 32 }, 64>::try_new().unwrap_err(),
+        RingError::NotPrime(1 << 32)
+    );
+    // 41 ≡ 1 mod 8 only: the 2-way incomplete NTT works, the full one does not.
+    let mut a = CyclotomicRing::<41, 8>::random();
+    assert_eq!(
+        a.clone().try_to_ntt_representation(),
+        Err(RingError::NotNttFriendly { modulus: 41, n: 8, degree: 1 })
+    );
+    assert_eq!(a.try_to_incomplete_ntt_representation(), Ok(()));
+}
+
+#[test]
+fn test_semantic_equality_and_display() {
+    use std::collections::hash_map::DefaultHasher;
+
+    const MOD_Q: u64 = 1125899904679937;
+    let mut a = CyclotomicRing::<MOD_Q, 8>::new();
+    a.data[0] = 3;
+    a.data[1] = 4;
+    a.data[2] = MOD_Q - 1;
+    assert_eq!(a.to_string(), "3 + 4X − X^2");
+    assert_eq!((CyclotomicRing::new() - a).to_string(), "−3 − 4X + X^2");
+    assert_eq!(CyclotomicRing::<MOD_Q, 8>::new().to_string(), "0");
+
+    let hash = |x: &CyclotomicRing<MOD_Q, 8>| {
+        let mut hasher = DefaultHasher::new();
+        x.hash(&mut hasher);
+        hasher.finish()
+    };
+    let mut ntt = a;
+    ntt.to_ntt_representation_lazy();
+    let mut incomplete = a;
+    incomplete.to_incomplete_ntt_representation();
+    assert_eq!(ntt, a);
+    assert_eq!(incomplete, ntt);
+    assert_eq!(hash(&ntt), hash(&a));
+    assert_eq!(hash(&incomplete), hash(&a));
+    assert_ne!(ntt, CyclotomicRing::one());
+}
+
+#[test]
+fn test_inner_products_match_naive() {
+    // A 61-bit modulus leaves room for only 7 lazy additions, so the
+    // accumulators are reduced several times along the way.
+    const MOD_Q: u64 = 2305843009213693921;
+    const N: usize = 16;
+    let mut left: Vec<_> = (0..20).map(|_| CyclotomicRing::<MOD_Q, N>::random()).collect();
+    let mut right: Vec<_> = (0..20).map(|_| CyclotomicRing::<MOD_Q, N>::random()).collect();
+    let mut expected = CyclotomicRing::<MOD_Q, N>::new();
+    for (l, r) in left.iter().zip(right.iter()) {
+        expected = expected + naive_multiply(&mut l.clone(), &mut r.clone());
+    }
+
+    let ntt = ntt_inner_product(&mut left.clone(), &mut right.clone());
+    let incomplete = incomplete_ntt_inner_product(&mut left, &mut right);
+
+    assert_eq!(ntt, expected);
+    assert_eq!(incomplete, expected);
+}
+
 static NORMALIZE_INCOMPLETE_NTT_FACTORS_CACHE: OnceLock<Mutex<HashMap<usize, Vec<u64>>>> = OnceLock::new();
 static NORMALIZE_INCOMPLETE_NTT_FACTORS_INVERSE_CACHE: OnceLock<Mutex<HashMap<usize, Vec<u64>>>> = OnceLock::new();
 
 impl<const MOD_Q: u64, const N: usize> CyclotomicRing<MOD_Q, N> {
+    /// Multiplication strategy chosen from how X^N + 1 splits modulo `MOD_Q`.
+    pub const MULTIPLICATION_STRATEGY: MultiplicationStrategy = select_strategy(MOD_Q, N);
+
+    pub const PARAMS: RingParams = RingParams::new(MOD_Q, N);
+
+    /// Referenced by every constructor, so an invalid N or q, or an NTT
+    /// strategy the parameters cannot support, fails the build.
+    const PARAMS_CHECK: () = match Self::PARAMS.check() {
+        Ok(_) => (),
+        Err(RingError::DegreeNotPowerOfTwo(_)) => panic!("CyclotomicRing needs N a power of two ≥ 2"),
+        Err(RingError::ModulusTooSmall(_)) => panic!("CyclotomicRing needs q ≥ 2"),
+        Err(_) => panic!("CyclotomicRing: q does not support the NTT strategy selected for it"),
+    };
+    const FULL_NTT_SUPPORT: Result<(), RingError> = Self::PARAMS.check_ntt_degree(1);
+    const INCOMPLETE_NTT_SUPPORT: Result<(), RingError> = Self::PARAMS.check_ntt_degree(2);
+
+    /// The NTT-based strategy for these parameters, or why there is none.
+    pub fn fast_multiplication_strategy() -> Result<MultiplicationStrategy, StrategyError> {
+        fast_strategy(MOD_Q, N)
+    }
+
+    /// Checks that q and N support NTT-based multiplication; see
+    /// [`RingParams::validate`].
+    pub fn validate() -> Result<MultiplicationStrategy, RingError> {
+        Self::PARAMS.validate()
+    }
+
+    /// Like [`CyclotomicRing::new`], but fails for parameters without an NTT.
+    pub fn try_new() -> Result<Self, RingError> {
+        Self::validate()?;
+        Ok(Self::new())
+    }
+
     pub fn new() -> Self {
-        Self { data: [0u64; N], representation: Representation::Coefficient }
+        let () = Self::PARAMS_CHECK;
+        Self { data: [0u64; N], representation: Representation::Coefficient, range: CoefficientRange::Reduced }
     }
 
     pub fn random() -> Self {
+        let () = Self::PARAMS_CHECK;
         let mut rng = rand::rng();
         let mut data = [0u64; N];
         for i in 0..N {
             data[i] = rng.random_range(0..MOD_Q);
         }
-        let mut  t = Self { data, representation: Representation::Coefficient };
+        let mut  t = Self { data, representation: Representation::Coefficient, range: CoefficientRange::Reduced };
         t.to_coeff_representation();
         t
     }
@@ -180,7 +440,12 @@ impl<const MOD_Q: u64, const N: usize> CyclotomicRing<MOD_Q, N> {
     }
 
     pub fn random_bounded(bound: u64) -> Self {
-        let mut rng = rand::rng();
+        Self::random_bounded_with(&mut rand::rng(), bound)
+    }
+
+    /// [`CyclotomicRing::random_bounded`] with the given randomness.
+    pub fn random_bounded_with<R: Rng + ?Sized>(rng: &mut R, bound: u64) -> Self {
+        let () = Self::PARAMS_CHECK;
         let mut data = [0u64; N];
         for i in 0..N {
             data[i] = rng.random_range(0..bound);
@@ -188,35 +453,42 @@ impl<const MOD_Q: u64, const N: usize> CyclotomicRing<MOD_Q, N> {
                 data[i] = MOD_Q - data[i]; // Randomly negate the value
             }
         }
-        unsafe {
-            eltwise_reduce_mod(data.as_mut_ptr(), data.as_mut_ptr(), data.len() as u64, MOD_Q);
-        }
+        eltwise_reduce_mod_assign(&mut data, MOD_Q).expect("reduce bounded sample");
 
         // TODO 
-        let mut t = Self { data, representation: Representation::Coefficient };
+        let mut t = Self { data, representation: Representation::Coefficient, range: CoefficientRange::Reduced };
         t.to_coeff_representation();
         t
         // t + t.conjugate()
     }
 
     pub fn constant(value: u64) -> Self {
+        let () = Self::PARAMS_CHECK;
         let mut data = [0u64; N];
         data[0] = value;
-        Self { data, representation: Representation::Coefficient }
+        Self { data, representation: Representation::Coefficient, range: CoefficientRange::Reduced }
     }
 
     pub fn one() -> Self {
+        let () = Self::PARAMS_CHECK;
         let mut data = [0u64; N];
         data[0] = 1;
-        Self { data, representation: Representation::Coefficient }
+        Self { data, representation: Representation::Coefficient, range: CoefficientRange::Reduced }
     }
 
-    fn forward_ntt(&mut self) {
-        unsafe { ntt_forward_in_place(self.data.as_mut_ptr(), self.data.len(), MOD_Q) };
+    /// Brings every stored value into `[0, q)`.
+    pub fn reduce(&mut self) {
+        if self.range == CoefficientRange::Reduced {
+            return;
+        }
+        eltwise_reduce_mod_lazy_assign(&mut self.data, MOD_Q, self.range.mod_factor(), 1).expect("reduce");
+        self.range = CoefficientRange::Reduced;
     }
 
-    fn inverse_ntt(&mut self) {
-        unsafe { ntt_inverse_in_place(self.data.as_mut_ptr(), self.data.len(), MOD_Q) };
+    pub fn reduced(&self) -> Self {
+        let mut reduced = *self;
+        reduced.reduce();
+        reduced
     }
 
     pub fn conjugate(&self) -> Self {
@@ -235,7 +507,50 @@ impl<const MOD_Q: u64, const N: usize> CyclotomicRing<MOD_Q, N> {
         conjugated
     }
 
-    fn adjust_representation(&mut self, new_representation: Representation) {
+    /// The automorphism X ↦ X^g for odd `g`; `conjugate` is g = 2N - 1.
+    pub fn automorphism(&self, g: usize) -> Self {
+        assert!(g % 2 == 1, "X ↦ X^{g} is not an automorphism for even g");
+        let mut source = *self;
+        source.to_coeff_representation();
+        let mut result = Self::new();
+        for i in 0..N {
+            // X^(i·g) = ±X^(i·g mod N), negated when i·g mod 2N ≥ N.
+            let exponent = i * g % (2 * N);
+            let value = source.data[i];
+            if exponent < N {
+                result.data[exponent] = value;
+            } else {
+                result.data[exponent - N] = if value == 0 { 0 } else { MOD_Q - value };
+            }
+        }
+        result.adjust_representation(self.representation);
+        result
+    }
+
+    /// `c · self` for a scalar `c`; works in every representation.
+    pub fn scalar_mul(&self, scalar: u64) -> Self {
+        let mut result = *self;
+        eltwise_fma_mod_assign(&mut result.data, scalar % MOD_Q, None, MOD_Q, self.range.mod_factor())
+            .expect("scalar multiplication");
+        result.range = CoefficientRange::Reduced;
+        result
+    }
+
+    /// Coefficients in `[0, q)`, whatever the stored representation.
+    pub fn canonical_coefficients(&self) -> [u64; N] {
+        let mut coefficients = *self;
+        coefficients.to_coeff_representation();
+        coefficients.data
+    }
+
+    /// Coefficients as centered representatives in `(-q/2, q/2]`.
+    pub fn centered_coefficients(&self) -> [i64; N] {
+        let mut coefficients = self.clone();
+        coefficients.to_coeff_representation();
+        coefficients.data.map(|c| centered(c, MOD_Q))
+    }
+
+    pub(crate) fn adjust_representation(&mut self, new_representation: Representation) {
         if self.representation == new_representation {
             return; // already in the desired representation
         }
@@ -247,10 +562,21 @@ impl<const MOD_Q: u64, const N: usize> CyclotomicRing<MOD_Q, N> {
         }
     }
 
+    /// Converts to the 2-way incomplete NTT form with fully reduced values.
     pub fn to_incomplete_ntt_representation(&mut self) {
+        self.to_incomplete_ntt_representation_lazy();
+        self.reduce();
+    }
+
+    /// Converts to the 2-way incomplete NTT form, leaving values in `[0, 4q)`
+    /// if a transform was needed.
+    pub fn to_incomplete_ntt_representation_lazy(&mut self) {
         if self.representation == Representation::IncompleteNTT {
             return; // already in NTT form
         }
+        if let Err(error) = Self::INCOMPLETE_NTT_SUPPORT {
+            panic!("cannot convert to incomplete NTT form: {error}");
+        }
         if self.representation == Representation::NTT {
             self.to_coeff_representation();
         }
@@ -264,54 +590,68 @@ impl<const MOD_Q: u64, const N: usize> CyclotomicRing<MOD_Q, N> {
         }
 
         // Perform NTT on both halves in-place
-        unsafe {
-            ntt_forward_in_place(even_odd.as_mut_ptr(), N / 2, MOD_Q);           // even part
-            ntt_forward_in_place(even_odd.as_mut_ptr().add(N / 2), N / 2, MOD_Q); // odd part
-        }
-
-        // Assign back to even and odd for further use
-        let even = &even_odd[..N / 2];
-        let odd = &even_odd[N / 2..];
+        let (even, odd) = even_odd.split_at_mut(N / 2);
+        forward_ntt_lazy::<MOD_Q>(even, self.range);
+        forward_ntt_lazy::<MOD_Q>(odd, self.range);
 
-        for i in 0..N / 2 {
-            self.data[i] = even[i];
-            self.data[i + N / 2] = odd[i];
-        }
+        self.data = even_odd;
         self.representation = Representation::IncompleteNTT;
+        self.range = CoefficientRange::LessThan4Q;
+    }
+
+    /// Fallible form of [`CyclotomicRing::to_incomplete_ntt_representation`].
+    pub fn try_to_incomplete_ntt_representation(&mut self) -> Result<(), RingError> {
+        Self::INCOMPLETE_NTT_SUPPORT?;
+        self.to_incomplete_ntt_representation();
+        Ok(())
+    }
+
+    /// Fallible form of [`CyclotomicRing::to_ntt_representation`].
+    pub fn try_to_ntt_representation(&mut self) -> Result<(), RingError> {
+        Self::FULL_NTT_SUPPORT?;
+        self.to_ntt_representation();
+        Ok(())
     }
 
+    /// Converts to the fully splitting NTT form with fully reduced values.
     pub fn to_ntt_representation(&mut self) {
+        self.to_ntt_representation_lazy();
+        self.reduce();
+    }
+
+    /// Converts to the fully splitting NTT form, leaving values in `[0, 4q)`
+    /// if a transform was needed.
+    pub fn to_ntt_representation_lazy(&mut self) {
         if self.representation == Representation::NTT {
             return; // already in NTT form
         }
+        if let Err(error) = Self::FULL_NTT_SUPPORT {
+            panic!("cannot convert to NTT form: {error}");
+        }
 
         if self.representation == Representation::IncompleteNTT {
             self.to_coeff_representation();
         }
 
-        unsafe { ntt_forward_in_place(self.data.as_mut_ptr(), self.data.len(), MOD_Q) };
+        forward_ntt_lazy::<MOD_Q>(&mut self.data, self.range);
         self.representation = Representation::NTT;
+        self.range = CoefficientRange::LessThan4Q;
     }
 
 
     pub fn to_coeff_representation(&mut self) {
         if self.representation == Representation::Coefficient {
+            self.reduce();
             return; // already in coefficient form
         }
 
         if self.representation == Representation::IncompleteNTT {
             // Use a single array to hold both even and odd parts
-            let mut even_odd = [0u64; N];
-            // Copy even and odd parts from self.data
-            for i in 0..N / 2 {
-                even_odd[i] = self.data[i];           // even part
-                even_odd[i + N / 2] = self.data[i + N / 2]; // odd part
-            }
+            let mut even_odd = self.data;
 
-            unsafe {
-                ntt_inverse_in_place(even_odd.as_mut_ptr(), N / 2, MOD_Q);           // inverse NTT on even part
-                ntt_inverse_in_place(even_odd.as_mut_ptr().add(N / 2), N / 2, MOD_Q); // inverse NTT on odd part
-            }
+            let (even, odd) = even_odd.split_at_mut(N / 2);
+            inverse_ntt_lazy::<MOD_Q>(even, self.range);
+            inverse_ntt_lazy::<MOD_Q>(odd, self.range);
 
             // Interleave even and odd back into self.data
             for i in 0..N / 2 {
@@ -322,38 +662,60 @@ impl<const MOD_Q: u64, const N: usize> CyclotomicRing<MOD_Q, N> {
         } 
 
         if self.representation == Representation::NTT {
-            unsafe { ntt_inverse_in_place(self.data.as_mut_ptr(), self.data.len(), MOD_Q) };
+            inverse_ntt_lazy::<MOD_Q>(&mut self.data, self.range);
         }
 
         self.representation = Representation::Coefficient;
+        self.range = CoefficientRange::Reduced;
     }
     
 }
 
-fn get_shift_factors<const MOD_Q: u64, const N: usize>() -> Vec<u64> {
-        let mut factors = vec![0u64; N / 2];
-        factors[1] = 1;
-        unsafe { ntt_forward_in_place(factors.as_mut_ptr(), factors.len(), MOD_Q) };
-        factors
-    }
-
-
-    
+/// Forward NTT of `data` taking values in `input` and leaving them in `[0, 4q)`.
+fn forward_ntt_lazy<const MOD_Q: u64>(data: &mut [u64], input: CoefficientRange) {
+    ntt_forward_lazy(data, MOD_Q, input.mod_factor(), 4).expect("forward NTT");
+}
 
+/// Inverse NTT of `data` taking values in `input`; the output is fully reduced.
+/// HEXL's inverse accepts at most `[0, 2q)`, so `[0, 4q)` inputs are halved first.
+fn inverse_ntt_lazy<const MOD_Q: u64>(data: &mut [u64], input: CoefficientRange) {
+    let mut factor = input.mod_factor();
+    if factor == 4 {
+        eltwise_reduce_mod_lazy_assign(data, MOD_Q, 4, 2).expect("reduce before inverse NTT");
+        factor = 2;
+    }
+    ntt_inverse_lazy(data, MOD_Q, factor, 1).expect("inverse NTT");
+}
 
+/// Evaluation points of the slots of a size-`size` negacyclic NTT, i.e. the
+/// NTT of the polynomial `Y`. These are the factors that multiply the
+/// wrapped-around terms of an incomplete NTT product.
+fn get_shift_factors(modulus: u64, size: usize) -> Vec<u64> {
+    let mut factors = vec![0u64; size];
+    factors[1] = 1;
+    ntt_forward(&mut factors, modulus).expect("forward NTT of shift factors");
+    factors
+}
 
-static SHIFT_FACTORS_CACHE: OnceLock<Vec<u64>> = OnceLock::new();
+/// One slot per (modulus, size) in use, filled on first use. Lookups after
+/// that are a scan of initialised `OnceLock`s, without locking or copying.
+type ShiftFactorsSlot = OnceLock<((u64, usize), &'static [u64])>;
 
+static SHIFT_FACTORS: [ShiftFactorsSlot; 64] = [const { OnceLock::new() }; 64];
 
-fn get_shift_factors_cached<const MOD_Q: u64, const N: usize>() -> Vec<u64> {
-    // Ensure the cache is initialized
-    // Safe to access without locking since OnceLock + HashMap is read-only after init
-    if cfg!(test) {
-        return  get_shift_factors::<MOD_Q, N>();
+pub(crate) fn get_shift_factors_cached_for(modulus: u64, size: usize) -> &'static [u64] {
+    for slot in &SHIFT_FACTORS {
+        let &(key, factors) =
+            slot.get_or_init(|| ((modulus, size), Box::leak(get_shift_factors(modulus, size).into_boxed_slice())));
+        if key == (modulus, size) {
+            return factors;
+        }
     }
-    SHIFT_FACTORS_CACHE
-        .get_or_init(get_shift_factors::<MOD_Q, N>)
-        .clone()
+    panic!("more than {} (modulus, size) pairs need shift factors", SHIFT_FACTORS.len())
+}
+
+fn get_shift_factors_cached<const MOD_Q: u64, const N: usize>() -> &'static [u64] {
+    get_shift_factors_cached_for(MOD_Q, N / 2)
 }
 
 pub fn incomplete_ntt_multiplication<const MOD_Q: u64, const N: usize>(
@@ -361,132 +723,238 @@ pub fn incomplete_ntt_multiplication<const MOD_Q: u64, const N: usize>(
     operand2: &mut CyclotomicRing<MOD_Q, N>,
     use_shift_factors: bool,
 ) -> CyclotomicRing<MOD_Q, N> {
-    // Static local cache (thread-safe, mutable via Mutex)
+    let unshifted;
+    let shift_factors = if use_shift_factors {
+        get_shift_factors_cached::<MOD_Q, N>()
+    } else {
+        unshifted = vec![get_shift_factors_cached::<MOD_Q, N>()[0]; N / 2];
+        &unshifted
+    };
+    let mut result = CyclotomicRing::<MOD_Q, N>::new();
 
-    // Initialize or get the cache
-    // let cache = SHIFT_FACTORS_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
-    // let mut cache_guard = cache.lock().unwrap();
+    operand1.to_incomplete_ntt_representation_lazy();
+    operand2.to_incomplete_ntt_representation_lazy();
+    let input_mod_factor = operand1.range.max(operand2.range).mod_factor();
 
-    // Get or compute shift factors
-    // init_shift_factors_cached::<MOD_Q, N>();
-    let shift_factors = get_shift_factors_cached::<MOD_Q, N>();
+    let (result_even, result_odd) = result.data.split_at_mut(N / 2);
+    let (op1_even, op1_odd) = operand1.data.split_at(N / 2);
+    let (op2_even, op2_odd) = operand2.data.split_at(N / 2);
+
+    // result_odd = op1_odd * op2_odd, used as scratch for the shifted term
+    eltwise_mult_mod_lazy(result_odd, op1_odd, op2_odd, MOD_Q, input_mod_factor).expect("odd * odd");
+    // result_even = op1_even * op2_even
+    eltwise_mult_mod_lazy(result_even, op1_even, op2_even, MOD_Q, input_mod_factor).expect("even * even");
+    // result_even += result_odd * shift_factors
+    eltwise_mult_add_mod_assign(result_even, result_odd, shift_factors, MOD_Q, 1).expect("shift");
+    // result_odd = op1_even * op2_odd + op1_odd * op2_even
+    eltwise_mult_mod_lazy(result_odd, op1_even, op2_odd, MOD_Q, input_mod_factor).expect("even * odd");
+    eltwise_mult_add_mod_assign(result_odd, op1_odd, op2_even, MOD_Q, input_mod_factor).expect("odd * even");
 
-    // if !use_shift_factors { 
-    //     for i in 1..N / 2 {
-    //         shift_factors[i] = shift_factors[0]; // Use identity for multiplication
-    //     }
-    // }
+    result.representation = Representation::IncompleteNTT;
+    result
+}
+
+pub fn fully_splitting_ntt_multiplication<const MOD_Q: u64, const N: usize>(
+    operand1: &mut CyclotomicRing<MOD_Q, N>,
+    operand2: &mut CyclotomicRing<MOD_Q, N>,
+) -> CyclotomicRing<MOD_Q, N> {
+    operand1.to_ntt_representation_lazy();
+    operand2.to_ntt_representation_lazy();
+    let input_mod_factor = operand1.range.max(operand2.range).mod_factor();
 
-    // Use 3 arrays: result, op1, op2 (each N elements, even in first N/2, odd in second N/2)
-    let mut result_parts = [0u64; N];
-    let mut op1_parts = [0u64; N];
-    let mut op2_parts = [0u64; N];
     let mut result = CyclotomicRing::<MOD_Q, N>::new();
 
-    operand1.to_incomplete_ntt_representation();
-    operand2.to_incomplete_ntt_representation();
+    eltwise_mult_mod_lazy(&mut result.data, &operand1.data, &operand2.data, MOD_Q, input_mod_factor)
+        .expect("slot-wise multiplication");
 
-    op1_parts.copy_from_slice(&operand1.data);
-    op2_parts.copy_from_slice(&operand2.data);
+    result.representation = Representation::NTT;
+    result
+}
 
-    // result_even = op1_even * op2_even
-    unsafe {
-        eltwise_mult_mod(
-            result_parts.as_mut_ptr(),
-            op1_parts.as_ptr(),
-            op2_parts.as_ptr(),
-            (N / 2) as u64,
-            MOD_Q,
-        );
-        // result_odd = op1_odd * op2_even
-        eltwise_mult_mod(
-            result_parts.as_mut_ptr().add(N / 2),
-            op1_parts.as_ptr().add(N / 2),
-            op2_parts.as_ptr(),
-            (N / 2) as u64,
-            MOD_Q,
-        );
-        // tmp1 = op1_odd * op2_odd
-        eltwise_mult_mod(
-            op1_parts.as_mut_ptr().add(N / 2),
-            op1_parts.as_ptr().add(N / 2),
-            op2_parts.as_ptr().add(N / 2),
-            (N / 2) as u64,
-            MOD_Q,
-        );
-        // tmp1 = tmp1 * shift_factors
-        if use_shift_factors {
-            eltwise_mult_mod(
-                op1_parts.as_mut_ptr().add(N / 2),
-                op1_parts.as_ptr().add(N / 2),
-                shift_factors.as_ptr(),
-                (N / 2) as u64,
-                MOD_Q
-            ) 
-        } else {
-            // If not using shift factors, just copy the odd part to tmp1
-            eltwise_mult_mod(
-                op1_parts.as_mut_ptr().add(N / 2),
-                op1_parts.as_ptr().add(N / 2),
-                vec![shift_factors[0]; N/2].as_ptr(),
-                (N / 2) as u64,
-                MOD_Q
-            ) 
+/// Multiplication through a d-way incomplete NTT, for moduli with
+/// q ≡ 1 mod 2N/d but not q ≡ 1 mod N. Writing a = Σ_j X^j a_j(X^d), each
+/// a_j is transformed with a size-N/d NTT in Y = X^d, and every slot holds
+/// a degree-(d-1) polynomial reduced modulo X^d - ω. Operands are converted
+/// to coefficient form and so is the result.
+pub fn d_way_incomplete_ntt_multiplication<const MOD_Q: u64, const N: usize>(
+    operand1: &mut CyclotomicRing<MOD_Q, N>,
+    operand2: &mut CyclotomicRing<MOD_Q, N>,
+    degree: usize,
+) -> CyclotomicRing<MOD_Q, N> {
+    assert!(
+        degree.is_power_of_two() && degree <= N / 2,
+        "incomplete NTT degree {degree} must be a power of two of at most N/2"
+    );
+    let size = N / degree;
+    let shift_factors = get_shift_factors_cached_for(MOD_Q, size);
+    operand1.to_coeff_representation();
+    operand2.to_coeff_representation();
+
+    let parts1 = d_way_forward::<MOD_Q, N>(&operand1.data, degree);
+    let parts2 = d_way_forward::<MOD_Q, N>(&operand2.data, degree);
+
+    // low[k] collects the products with j + l = k, high[k] those with
+    // j + l = k + d, which wrap around as X^d = ω.
+    let mut low = [0u64; N];
+    let mut high = [0u64; N];
+    for j in 0..degree {
+        for l in 0..degree {
+            let (target, k) = if j + l < degree { (&mut low, j + l) } else { (&mut high, j + l - degree) };
+            eltwise_mult_add_mod_assign(
+                &mut target[k * size..(k + 1) * size],
+                &parts1[j * size..(j + 1) * size],
+                &parts2[l * size..(l + 1) * size],
+                MOD_Q,
+                CoefficientRange::LessThan4Q.mod_factor(),
+            )
+            .expect("slot product");
         }
-        // tmp2 = op1_even * op2_odd
-        eltwise_mult_mod(
-            op1_parts.as_mut_ptr(),
-            op1_parts.as_ptr(),
-            op2_parts.as_ptr().add(N / 2),
-            (N / 2) as u64,
-            MOD_Q,
-        );
-        // result_even += tmp1
-        eltwise_add_mod(
-            result_parts.as_mut_ptr(),
-            result_parts.as_ptr(),
-            op1_parts.as_ptr().add(N / 2),
-            (N / 2) as u64,
-            MOD_Q,
-        );
-        // result_odd += tmp2
-        eltwise_add_mod(
-            result_parts.as_mut_ptr().add(N / 2),
-            result_parts.as_ptr().add(N / 2),
-            op1_parts.as_ptr(),
-            (N / 2) as u64,
-            MOD_Q,
-        );
     }
 
-    result.representation = Representation::IncompleteNTT;
-    result.data.copy_from_slice(&result_parts);
+    for (low_part, high_part) in low.chunks_mut(size).zip(high.chunks(size)) {
+        eltwise_mult_add_mod_assign(low_part, high_part, shift_factors, MOD_Q, 1).expect("shift");
+    }
+    let mut result = CyclotomicRing::<MOD_Q, N>::new();
+    result.data = d_way_inverse::<MOD_Q, N>(&mut low, degree);
     result
 }
 
-pub fn fully_splitting_ntt_multiplication<const MOD_Q: u64, const N: usize>(
+/// Forward d-way incomplete NTT of reduced coefficients: coefficient j of
+/// slot k ends up at `j * N/d + k`, in `[0, 4q)`. For d = 2 this is the
+/// layout of `Representation::IncompleteNTT`, for d = 1 that of
+/// `Representation::NTT`.
+pub(crate) fn d_way_forward<const MOD_Q: u64, const N: usize>(data: &[u64; N], degree: usize) -> [u64; N] {
+    let size = N / degree;
+    let mut parts = [0u64; N];
+    for k in 0..size {
+        for j in 0..degree {
+            parts[j * size + k] = data[k * degree + j];
+        }
+    }
+    for part in parts.chunks_mut(size) {
+        forward_ntt_lazy::<MOD_Q>(part, CoefficientRange::Reduced);
+    }
+    parts
+}
+
+/// Inverse of [`d_way_forward`] for reduced slot values; `parts` is used
+/// as scratch.
+pub(crate) fn d_way_inverse<const MOD_Q: u64, const N: usize>(parts: &mut [u64; N], degree: usize) -> [u64; N] {
+    let size = N / degree;
+    let mut data = [0u64; N];
+    for (j, part) in parts.chunks_mut(size).enumerate() {
+        inverse_ntt_lazy::<MOD_Q>(part, CoefficientRange::Reduced);
+        for (k, &value) in part.iter().enumerate() {
+            data[k * degree + j] = value;
+        }
+    }
+    data
+}
+
+/// Multiplies with the cheapest strategy the parameters allow (see
+/// [`crate::splitting`]). The product is left in the form that strategy
+/// computes in: NTT for a fully splitting modulus, 2-way incomplete NTT for
+/// degree-2 moduli and for fully splitting ones when both operands are
+/// already incomplete, and coefficients for d-way and Karatsuba. Callers
+/// that compare or inspect the data convert it first.
+pub fn multiply<const MOD_Q: u64, const N: usize>(
     operand1: &mut CyclotomicRing<MOD_Q, N>,
     operand2: &mut CyclotomicRing<MOD_Q, N>,
 ) -> CyclotomicRing<MOD_Q, N> {
-    operand1.to_ntt_representation();
-    operand2.to_ntt_representation();
+    let both_incomplete = operand1.representation == Representation::IncompleteNTT
+        && operand2.representation == Representation::IncompleteNTT;
+    match CyclotomicRing::<MOD_Q, N>::MULTIPLICATION_STRATEGY {
+        MultiplicationStrategy::FullySplitting if !both_incomplete => {
+            fully_splitting_ntt_multiplication(operand1, operand2)
+        }
+        MultiplicationStrategy::FullySplitting | MultiplicationStrategy::IncompleteNtt { degree: 2 } => {
+            incomplete_ntt_multiplication(operand1, operand2, true)
+        }
+        MultiplicationStrategy::IncompleteNtt { degree } => {
+            d_way_incomplete_ntt_multiplication(operand1, operand2, degree)
+        }
+        MultiplicationStrategy::NonNtt => karatsuba_multiply(operand1, operand2),
+    }
+}
 
-    let mut result = CyclotomicRing::<MOD_Q, N>::new();
+/// Number of values below `q` that can be added onto a reduced accumulator
+/// before it may overflow 64 bits.
+fn lazy_accumulation_budget<const MOD_Q: u64>() -> usize {
+    (u64::MAX / MOD_Q - 1) as usize
+}
 
-    unsafe {
-        eltwise_mult_mod(
-            result.data.as_mut_ptr(),
-            operand1.data.as_ptr(),
-            operand2.data.as_ptr(),
-            result.data.len() as u64,
-            MOD_Q,
-        ) 
-    };
+/// Σ leftᵢ·rightᵢ in the fully splitting NTT form. Products are accumulated
+/// without reduction and the accumulator is only reduced when it could
+/// otherwise overflow, so a length-k inner product costs k multiplications
+/// and about k·q / 2^64 reductions.
+pub fn ntt_inner_product<const MOD_Q: u64, const N: usize>(
+    left: &mut [CyclotomicRing<MOD_Q, N>],
+    right: &mut [CyclotomicRing<MOD_Q, N>],
+) -> CyclotomicRing<MOD_Q, N> {
+    assert_eq!(left.len(), right.len(), "inner product of vectors with different lengths");
+    let budget = lazy_accumulation_budget::<MOD_Q>();
+    let mut result = CyclotomicRing::<MOD_Q, N>::new();
+    let mut pending = 0;
+
+    for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+        l.to_ntt_representation_lazy();
+        r.to_ntt_representation_lazy();
+        if pending == budget {
+            eltwise_reduce_mod_any_assign(&mut result.data, MOD_Q).expect("reduce accumulator");
+            pending = 0;
+        }
+        let input_mod_factor = l.range.max(r.range).mod_factor();
+        eltwise_mult_acc_lazy(&mut result.data, &l.data, &r.data, MOD_Q, input_mod_factor).expect("accumulate");
+        pending += 1;
+    }
 
+    eltwise_reduce_mod_any_assign(&mut result.data, MOD_Q).expect("reduce accumulator");
     result.representation = Representation::NTT;
     result
 }
 
+/// Σ leftᵢ·rightᵢ in the 2-way incomplete NTT form. The even, odd and
+/// shifted (odd·odd) parts are accumulated lazily and the shift factors are
+/// applied once at the end instead of once per product.
+pub fn incomplete_ntt_inner_product<const MOD_Q: u64, const N: usize>(
+    left: &mut [CyclotomicRing<MOD_Q, N>],
+    right: &mut [CyclotomicRing<MOD_Q, N>],
+) -> CyclotomicRing<MOD_Q, N> {
+    assert_eq!(left.len(), right.len(), "inner product of vectors with different lengths");
+    let shift_factors = get_shift_factors_cached::<MOD_Q, N>();
+    let budget = lazy_accumulation_budget::<MOD_Q>();
+    let mut result = CyclotomicRing::<MOD_Q, N>::new();
+    let mut shifted = vec![0u64; N / 2];
+    let mut pending = 0;
+
+    for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+        l.to_incomplete_ntt_representation_lazy();
+        r.to_incomplete_ntt_representation_lazy();
+        // The odd accumulator receives two products per term.
+        if pending + 2 > budget {
+            eltwise_reduce_mod_any_assign(&mut result.data, MOD_Q).expect("reduce accumulator");
+            eltwise_reduce_mod_any_assign(&mut shifted, MOD_Q).expect("reduce accumulator");
+            pending = 0;
+        }
+        let input_mod_factor = l.range.max(r.range).mod_factor();
+        let (even, odd) = result.data.split_at_mut(N / 2);
+        let (l_even, l_odd) = l.data.split_at(N / 2);
+        let (r_even, r_odd) = r.data.split_at(N / 2);
+        eltwise_mult_acc_lazy(even, l_even, r_even, MOD_Q, input_mod_factor).expect("even * even");
+        eltwise_mult_acc_lazy(odd, l_even, r_odd, MOD_Q, input_mod_factor).expect("even * odd");
+        eltwise_mult_acc_lazy(odd, l_odd, r_even, MOD_Q, input_mod_factor).expect("odd * even");
+        eltwise_mult_acc_lazy(&mut shifted, l_odd, r_odd, MOD_Q, input_mod_factor).expect("odd * odd");
+        pending += 2;
+    }
+
+    eltwise_reduce_mod_any_assign(&mut result.data, MOD_Q).expect("reduce accumulator");
+    eltwise_reduce_mod_any_assign(&mut shifted, MOD_Q).expect("reduce accumulator");
+    let (even, _) = result.data.split_at_mut(N / 2);
+    eltwise_mult_add_mod_assign(even, &shifted, shift_factors, MOD_Q, 1).expect("shift");
+    result.representation = Representation::IncompleteNTT;
+    result
+}
+
 pub fn naive_multiply<const MOD_Q: u64, const N: usize>(
     operand1: &mut CyclotomicRing<MOD_Q, N>,
     operand2: &mut CyclotomicRing<MOD_Q, N>,
@@ -0,0 +1,229 @@
+//! Π_dec: keeps witness norms bounded across folds.
+//!
+//! Folding 2k witnesses of norm β with challenges of ℓ1 norm ‖ρ‖_1 gives a
+//! witness of norm up to 2k·‖ρ‖_1·β, so a bound only survives repeated
+//! folding if every folded witness is split again before the next fold. A
+//! witness w of norm up to (b^k - 1)/(b - 1)·⌊(b-1)/2⌋ has k balanced
+//! base-b digit vectors w = Σ b^i·w_i with ‖w_i‖∞ ≤ b/2. The prover commits
+//! to each w_i and claims its evaluations; the verifier needs no witness,
+//! since commitments and evaluations are linear:
+//!
+//!   Σ b^i·cm_i = cm,  Σ b^i·v_i = v.
+//!
+//! [`NormBound`] follows the bound through a step: b/2 after Π_dec,
+//! ‖ρ‖_1 times the sum of the inputs' bounds after Π_fold, and a fold is
+//! only sound to repeat if that stays within [`Decomposition::bound`].
+
+use crate::algebra::{PolynomialRing, RingElement};
+use crate::challenge::ChallengeSet;
+use crate::commitment::{linear_combination, AjtaiKey};
+use crate::cyclotomic_ring::CyclotomicRing;
+use crate::latticefold::{challenge_l1_norm, FoldingError};
+use crate::modular::pow_mod;
+
+/// Largest a such that every integer in [-a, a] has `digits` balanced
+/// base-`base` digits in (-base/2, base/2].
+pub(crate) fn balanced_digit_bound(base: u64, digits: usize) -> u128 {
+    let b = base as u128;
+    ((b - 1) / 2) * ((b.pow(digits as u32) - 1) / (b - 1))
+}
+
+pub fn max_norm<const MOD_Q: u64, const N: usize>(witness: &[CyclotomicRing<MOD_Q, N>]) -> u64 {
+    witness.iter().map(CyclotomicRing::infinity_norm).max().unwrap_or(0)
+}
+
+/// An upper bound on ‖w‖∞.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NormBound(pub u64);
+
+impl NormBound {
+    /// The bound of Σ ρ_i·w_i for witnesses w_i of the given bounds and
+    /// challenges ρ_i from `challenge_set`.
+    pub fn fold(bounds: impl IntoIterator<Item = NormBound>, challenge_set: &ChallengeSet) -> NormBound {
+        NormBound(challenge_l1_norm(challenge_set) * bounds.into_iter().map(|b| b.0).sum::<u64>())
+    }
+
+    pub fn check<const MOD_Q: u64, const N: usize>(self, witness: &[CyclotomicRing<MOD_Q, N>]) -> Result<(), FoldingError> {
+        let norm = max_norm(witness);
+        if norm > self.0 {
+            return Err(FoldingError::NormTooLarge { bound: self.0, actual: norm });
+        }
+        Ok(())
+    }
+}
+
+/// Commitments and evaluation claims of the k digit vectors.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecompositionProof<const MOD_Q: u64, const N: usize> {
+    pub commitments: Vec<Vec<CyclotomicRing<MOD_Q, N>>>,
+    pub evaluations: Vec<Vec<CyclotomicRing<MOD_Q, N>>>,
+}
+
+/// Splitting into `digits` balanced base-`base` digit vectors.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Decomposition {
+    pub base: u64,
+    pub digits: usize,
+}
+
+impl Decomposition {
+    /// Witnesses up to this bound can be decomposed.
+    pub fn bound(&self) -> NormBound {
+        NormBound(balanced_digit_bound(self.base, self.digits).min(u64::MAX as u128) as u64)
+    }
+
+    /// The bound of every digit vector.
+    pub fn digit_bound(&self) -> NormBound {
+        NormBound(self.base / 2)
+    }
+
+    /// The k digit vectors of `witness`.
+    pub fn split<const MOD_Q: u64, const N: usize>(
+        &self,
+        witness: &[CyclotomicRing<MOD_Q, N>],
+    ) -> Result<Vec<Vec<CyclotomicRing<MOD_Q, N>>>, FoldingError> {
+        self.bound().check(witness)?;
+        let mut digit_witnesses = vec![Vec::with_capacity(witness.len()); self.digits];
+        for w in witness {
+            for (digits, digit) in digit_witnesses.iter_mut().zip(w.decompose(self.base, self.digits)) {
+                digits.push(digit);
+            }
+        }
+        Ok(digit_witnesses)
+    }
+
+    /// Σ b^i·parts[i], for digit vectors, commitments or evaluations alike.
+    pub fn recombine<const MOD_Q: u64, const N: usize>(
+        &self,
+        parts: &[Vec<CyclotomicRing<MOD_Q, N>>],
+    ) -> Vec<CyclotomicRing<MOD_Q, N>> {
+        let powers: Vec<CyclotomicRing<MOD_Q, N>> =
+            (0..parts.len()).map(|i| RingElement::from_u64(pow_mod(self.base, i as u64, MOD_Q))).collect();
+        let parts: Vec<&[_]> = parts.iter().map(Vec::as_slice).collect();
+        linear_combination(&parts, &powers)
+    }
+
+    /// The digit vectors of `witness` and their commitments.
+    #[allow(clippy::type_complexity)]
+    pub fn commit<const MOD_Q: u64, const N: usize>(
+        &self,
+        key: &AjtaiKey<MOD_Q, N>,
+        witness: &[CyclotomicRing<MOD_Q, N>],
+    ) -> Result<(Vec<Vec<CyclotomicRing<MOD_Q, N>>>, Vec<Vec<CyclotomicRing<MOD_Q, N>>>), FoldingError> {
+        let digit_witnesses = self.split(witness)?;
+        let commitments = digit_witnesses.iter().map(|w| key.commit(w)).collect();
+        Ok((digit_witnesses, commitments))
+    }
+
+    /// Whether `proof` has one digit commitment and one evaluation vector
+    /// per digit, shaped like `commitment` and `evaluations`.
+    pub fn check_shape<const MOD_Q: u64, const N: usize>(
+        &self,
+        commitment: &[CyclotomicRing<MOD_Q, N>],
+        evaluations: &[CyclotomicRing<MOD_Q, N>],
+        proof: &DecompositionProof<MOD_Q, N>,
+    ) -> Result<(), FoldingError> {
+        let well_formed = proof.commitments.len() == self.digits
+            && proof.evaluations.len() == self.digits
+            && proof.commitments.iter().all(|cm| cm.len() == commitment.len())
+            && proof.evaluations.iter().all(|v| v.len() == evaluations.len());
+        if !well_formed {
+            return Err(FoldingError::MalformedProof);
+        }
+        Ok(())
+    }
+
+    /// Whether the digit commitments and evaluations of `proof` recombine
+    /// to `commitment` and `evaluations`.
+    pub fn verify<const MOD_Q: u64, const N: usize>(
+        &self,
+        commitment: &[CyclotomicRing<MOD_Q, N>],
+        evaluations: &[CyclotomicRing<MOD_Q, N>],
+        proof: &DecompositionProof<MOD_Q, N>,
+    ) -> Result<(), FoldingError> {
+        self.check_shape(commitment, evaluations, proof)?;
+        if self.recombine(&proof.commitments) != commitment || self.recombine(&proof.evaluations) != evaluations {
+            return Err(FoldingError::DecompositionMismatch);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mle::MultilinearExtension;
+
+    const MOD_Q: u64 = 1125899904679937;
+    const N: usize = 64;
+
+    type Ring = CyclotomicRing<MOD_Q, N>;
+
+    fn evaluate(witness: &[Ring], point: &[u64]) -> Vec<Ring> {
+        vec![MultilinearExtension::new(witness.to_vec()).evaluate_scalar(point)]
+    }
+
+    #[test]
+    fn test_repeated_folds_stay_bounded() {
+        let decomposition = Decomposition { base: 4, digits: 6 };
+        let challenge_set = ChallengeSet::FixedWeightTernary { weight: 20 };
+        let key = AjtaiKey::<MOD_Q, N>::setup(2, 16, b"test");
+        let point = [3, 1 << 40, 7, 12345];
+        let arity = 2 * decomposition.digits;
+        let folded_bound = NormBound::fold(vec![decomposition.digit_bound(); arity], &challenge_set);
+        assert_eq!(folded_bound, NormBound(480));
+        assert!(folded_bound <= decomposition.bound());
+
+        let bound = decomposition.bound().0;
+        let fresh: Vec<Ring> = (0..16).map(|_| Ring::random_bounded(2)).collect();
+        let mut accumulator: Vec<Ring> = (0..16).map(|_| Ring::random_bounded(bound + 1)).collect();
+        let mut accumulator_commitment = key.commit(&accumulator);
+        for _ in 0..8 {
+            let mut digits = Vec::new();
+            let mut commitments = Vec::new();
+            for (witness, commitment) in [(&accumulator, &accumulator_commitment), (&fresh, &key.commit(&fresh))] {
+                let (digit_witnesses, digit_commitments) = decomposition.commit(&key, witness).unwrap();
+                let evaluations = digit_witnesses.iter().map(|w| evaluate(w, &point)).collect();
+                let proof = DecompositionProof { commitments: digit_commitments.clone(), evaluations };
+                assert_eq!(decomposition.verify(commitment, &evaluate(witness, &point), &proof), Ok(()));
+                assert_eq!(decomposition.recombine(&digit_witnesses), *witness);
+                for w in &digit_witnesses {
+                    assert_eq!(decomposition.digit_bound().check(w), Ok(()));
+                }
+                digits.extend(digit_witnesses);
+                commitments.extend(digit_commitments);
+            }
+
+            let rho: Vec<Ring> = (0..arity).map(|_| challenge_set.sample()).collect();
+            let digits: Vec<&[_]> = digits.iter().map(Vec::as_slice).collect();
+            let commitments: Vec<&[_]> = commitments.iter().map(Vec::as_slice).collect();
+            accumulator = linear_combination(&digits, &rho);
+            accumulator_commitment = linear_combination(&commitments, &rho);
+            assert_eq!(key.commit(&accumulator), accumulator_commitment);
+            assert_eq!(folded_bound.check(&accumulator), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_rejects_bad_decompositions() {
+        let decomposition = Decomposition { base: 4, digits: 3 };
+        let key = AjtaiKey::<MOD_Q, N>::setup(2, 4, b"test");
+        let mut witness: Vec<Ring> = (0..4).map(|_| Ring::random_bounded(3)).collect();
+        let (digit_witnesses, commitments) = decomposition.commit(&key, &witness).unwrap();
+        let evaluations: Vec<_> = digit_witnesses.iter().map(|w| evaluate(w, &[5, 9])).collect();
+        let proof = DecompositionProof { commitments, evaluations };
+        let commitment = key.commit(&witness);
+        let claim = evaluate(&witness, &[5, 9]);
+        assert_eq!(decomposition.verify(&commitment, &claim, &proof), Ok(()));
+
+        let mut tampered = proof.clone();
+        tampered.commitments[2][1] = tampered.commitments[2][1] + Ring::one();
+        assert_eq!(decomposition.verify(&commitment, &claim, &tampered), Err(FoldingError::DecompositionMismatch));
+        let mut tampered = proof.clone();
+        tampered.evaluations.pop();
+        assert_eq!(decomposition.verify(&commitment, &claim, &tampered), Err(FoldingError::MalformedProof));
+
+        witness[3] = Ring::constant(22);
+        assert_eq!(decomposition.split(&witness), Err(FoldingError::NormTooLarge { bound: 21, actual: 22 }));
+    }
+}
@@ -0,0 +1,441 @@
+//! Extension fields F_{q^K} = F_q[Y]/(f(Y)) for K ∈ {1, 2, 4}.
+//!
+//! Sumcheck over a small q draws its challenges from an extension of F_q
+//! for soundness. The defining polynomial f is chosen at compile time: the
+//! binomial Y^K - r, with r the smallest non-residue, whenever it is
+//! irreducible (K = 2, or K = 4 and q ≡ 1 mod 4), and otherwise the first
+//! irreducible Y^4 - c1·Y - c0 in a fixed search order. For binomials the
+//! Frobenius y ↦ y^q only twists coefficients, since Y^q = r^((q-1)/K)·Y.
+//!
+//! If q ≡ 1 mod N but not mod 2N, the slots F_q[X]/(X^2 - s_t) of the 2-way
+//! incomplete NTT are fields of size q^2. `embed_in_incomplete_slots` and
+//! `extract_from_incomplete_slots` identify them with `ExtField<MOD_Q, 2>`
+//! via Y ↦ λ_t·X, λ_t^2 = r / s_t, so slot-wise ring arithmetic is
+//! extension-field arithmetic.
+
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use rand::Rng;
+
+use crate::cyclotomic_ring::CyclotomicRing;
+use crate::modular::{add_mod, inv_mod, is_prime, mul_mod, pow_mod, smallest_non_residue, sqrt_mod};
+use crate::slots::{decode_incomplete_slots, encode_incomplete_slots, slot_layout};
+
+/// Largest supported extension degree.
+pub const MAX_EXTENSION_DEGREE: usize = 4;
+
+/// `a · b` in F_q[Y]/(Y^K - Σ rule[i]·Y^i). Needs q < 2^62 so that K
+/// products fit a u128 accumulator.
+const fn mul_reduce<const K: usize>(a: &[u64; K], b: &[u64; K], rule: &[u64; K], q: u64) -> [u64; K] {
+    let mut wide = [0u64; 2 * MAX_EXTENSION_DEGREE - 1];
+    let mut d = 0;
+    while d < 2 * K - 1 {
+        let mut acc = 0u128;
+        let mut i = if d >= K { d + 1 - K } else { 0 };
+        while i < K && i <= d {
+            acc += a[i] as u128 * b[d - i] as u128;
+            i += 1;
+        }
+        wide[d] = (acc % q as u128) as u64;
+        d += 1;
+    }
+    // Replace Y^d = Y^(d-K)·Y^K by Y^(d-K)·Σ rule[i]·Y^i, top down.
+    let mut d = 2 * K - 1;
+    while d > K {
+        d -= 1;
+        let mut i = 0;
+        while i < K {
+            if rule[i] != 0 {
+                wide[d - K + i] = add_mod(wide[d - K + i], mul_mod(wide[d], rule[i], q), q);
+            }
+            i += 1;
+        }
+    }
+    let mut result = [0u64; K];
+    let mut i = 0;
+    while i < K {
+        result[i] = wide[i];
+        i += 1;
+    }
+    result
+}
+
+const fn pow_reduce<const K: usize>(base: &[u64; K], mut exponent: u64, rule: &[u64; K], q: u64) -> [u64; K] {
+    let mut result = [0u64; K];
+    result[0] = 1;
+    let mut base = *base;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_reduce(&result, &base, rule, q);
+        }
+        base = mul_reduce(&base, &base, rule, q);
+        exponent >>= 1;
+    }
+    result
+}
+
+const fn equal<const K: usize>(a: &[u64; K], b: &[u64; K]) -> bool {
+    let mut i = 0;
+    while i < K {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Rabin's test for a power-of-two degree K ≥ 2: f is irreducible iff
+/// Y^(q^K) ≡ Y and Y^(q^(K/2)) ≢ Y mod f. The first condition makes f
+/// square-free with factors of degree dividing K, the second rules out
+/// factors of degree dividing K/2, i.e. all proper ones.
+pub const fn is_irreducible<const K: usize>(rule: &[u64; K], q: u64) -> bool {
+    let mut y = [0u64; K];
+    y[1] = 1;
+    let mut power = y;
+    let mut half = y;
+    let mut i = 0;
+    while i < K {
+        power = pow_reduce(&power, q, rule, q);
+        i += 1;
+        if i == K / 2 {
+            half = power;
+        }
+    }
+    equal(&power, &y) && !equal(&half, &y)
+}
+
+/// The reduction rule Y^K = Σ rule[i]·Y^i of the defining polynomial of
+/// F_{q^K}; see the module documentation for the search order.
+pub const fn irreducible_rule<const K: usize>(q: u64) -> [u64; K] {
+    assert!(K == 1 || K == 2 || K == 4, "extension degree must be 1, 2 or 4");
+    assert!(q > 2 && q < 1 << 62 && is_prime(q), "extension fields need an odd prime modulus below 2^62");
+    let mut rule = [0u64; K];
+    if K == 1 {
+        return rule;
+    }
+    if K == 2 || q % 4 == 1 {
+        rule[0] = smallest_non_residue(q);
+        return rule;
+    }
+    // q ≡ 3 mod 4: -1 is a non-square, so Y^4 - r always factors.
+    let mut c0 = 1;
+    loop {
+        let mut c1 = 1;
+        while c1 <= 16 {
+            rule[0] = c0;
+            rule[1] = c1;
+            if is_irreducible(&rule, q) {
+                return rule;
+            }
+            c1 += 1;
+        }
+        c0 += 1;
+    }
+}
+
+/// An element Σ coefficients[i]·Y^i of F_{q^K}, coefficients reduced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ExtField<const MOD_Q: u64, const K: usize> {
+    pub coefficients: [u64; K],
+}
+
+impl<const MOD_Q: u64, const K: usize> ExtField<MOD_Q, K> {
+    /// Y^K = Σ RULE[i]·Y^i.
+    pub const RULE: [u64; K] = irreducible_rule::<K>(MOD_Q);
+
+    /// Whether the defining polynomial is Y^K - RULE[0].
+    pub const IS_BINOMIAL: bool = {
+        let mut i = 1;
+        while i < K && Self::RULE[i] == 0 {
+            i += 1;
+        }
+        i == K
+    };
+
+    /// Y^q = FROBENIUS_TWIST·Y for binomials.
+    const FROBENIUS_TWIST: u64 = if Self::IS_BINOMIAL && K > 1 {
+        pow_mod(Self::RULE[0], (MOD_Q - 1) / K as u64, MOD_Q)
+    } else {
+        1
+    };
+
+    pub fn new(coefficients: [u64; K]) -> Self {
+        Self { coefficients: coefficients.map(|c| c % MOD_Q) }
+    }
+
+    pub fn zero() -> Self {
+        Self { coefficients: [0; K] }
+    }
+
+    pub fn one() -> Self {
+        Self::from_base(1)
+    }
+
+    /// The image of `value` ∈ F_q.
+    pub fn from_base(value: u64) -> Self {
+        let mut coefficients = [0; K];
+        coefficients[0] = value % MOD_Q;
+        Self { coefficients }
+    }
+
+    /// The generator Y of the extension.
+    pub fn generator() -> Self {
+        let mut coefficients = [0; K];
+        coefficients[1 % K] = 1;
+        Self { coefficients }
+    }
+
+    pub fn random() -> Self {
+        Self::random_with(&mut rand::rng())
+    }
+
+    pub fn random_with<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self { coefficients: std::array::from_fn(|_| rng.random_range(0..MOD_Q)) }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.iter().all(|&c| c == 0)
+    }
+
+    /// The value in F_q, if the element lies in the base field.
+    pub fn to_base(&self) -> Option<u64> {
+        self.coefficients[1..].iter().all(|&c| c == 0).then_some(self.coefficients[0])
+    }
+
+    pub fn scalar_mul(&self, scalar: u64) -> Self {
+        let scalar = scalar % MOD_Q;
+        Self { coefficients: self.coefficients.map(|c| mul_mod(c, scalar, MOD_Q)) }
+    }
+
+    pub fn square(&self) -> Self {
+        *self * *self
+    }
+
+    pub fn pow(&self, exponent: u64) -> Self {
+        Self { coefficients: pow_reduce(&self.coefficients, exponent, &Self::RULE, MOD_Q) }
+    }
+
+    /// y ↦ y^q, the generator of Gal(F_{q^K}/F_q).
+    pub fn frobenius(&self) -> Self {
+        if !Self::IS_BINOMIAL {
+            return self.pow(MOD_Q);
+        }
+        let mut twist = 1;
+        let mut coefficients = self.coefficients;
+        for c in coefficients.iter_mut().skip(1) {
+            twist = mul_mod(twist, Self::FROBENIUS_TWIST, MOD_Q);
+            *c = mul_mod(*c, twist, MOD_Q);
+        }
+        Self { coefficients }
+    }
+
+    /// y ↦ y^(q^power).
+    pub fn frobenius_power(&self, power: usize) -> Self {
+        (0..power % K).fold(*self, |y, _| y.frobenius())
+    }
+
+    /// Product of the Galois conjugates y^(q^i), i ∈ 1..K, so that
+    /// y · conjugate_product(y) is the norm.
+    fn conjugate_product(&self) -> Self {
+        let mut conjugate = *self;
+        let mut product = Self::one();
+        for _ in 1..K {
+            conjugate = conjugate.frobenius();
+            product *= conjugate;
+        }
+        product
+    }
+
+    /// N(y) = Π y^(q^i) ∈ F_q.
+    pub fn norm(&self) -> u64 {
+        let norm = *self * self.conjugate_product();
+        debug_assert!(norm.to_base().is_some());
+        norm.coefficients[0]
+    }
+
+    /// y^(-1) = conjugate_product(y) / N(y), or `None` for zero.
+    pub fn inverse(&self) -> Option<Self> {
+        let conjugates = self.conjugate_product();
+        let norm = (*self * conjugates).coefficients[0];
+        Some(conjugates.scalar_mul(inv_mod(norm, MOD_Q)?))
+    }
+}
+
+impl<const MOD_Q: u64, const K: usize> Default for ExtField<MOD_Q, K> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<const MOD_Q: u64, const K: usize> Add for ExtField<MOD_Q, K> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self::Output {
+        self += other;
+        self
+    }
+}
+
+impl<const MOD_Q: u64, const K: usize> AddAssign for ExtField<MOD_Q, K> {
+    fn add_assign(&mut self, other: Self) {
+        for (a, b) in self.coefficients.iter_mut().zip(other.coefficients) {
+            let sum = *a + b;
+            *a = if sum >= MOD_Q { sum - MOD_Q } else { sum };
+        }
+    }
+}
+
+impl<const MOD_Q: u64, const K: usize> Sub for ExtField<MOD_Q, K> {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self::Output {
+        self -= other;
+        self
+    }
+}
+
+impl<const MOD_Q: u64, const K: usize> SubAssign for ExtField<MOD_Q, K> {
+    fn sub_assign(&mut self, other: Self) {
+        for (a, b) in self.coefficients.iter_mut().zip(other.coefficients) {
+            *a = if *a >= b { *a - b } else { *a + MOD_Q - b };
+        }
+    }
+}
+
+impl<const MOD_Q: u64, const K: usize> Neg for ExtField<MOD_Q, K> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::zero() - self
+    }
+}
+
+impl<const MOD_Q: u64, const K: usize> Mul for ExtField<MOD_Q, K> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        Self { coefficients: mul_reduce(&self.coefficients, &other.coefficients, &Self::RULE, MOD_Q) }
+    }
+}
+
+impl<const MOD_Q: u64, const K: usize> MulAssign for ExtField<MOD_Q, K> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+/// λ_t with λ_t^2 = r / s_t for every canonical incomplete-NTT slot t.
+fn slot_isomorphisms<const MOD_Q: u64, const N: usize>() -> Vec<u64> {
+    let layout = slot_layout::<MOD_Q, N>();
+    let r = ExtField::<MOD_Q, 2>::RULE[0];
+    layout
+        .incomplete_positions
+        .iter()
+        .enumerate()
+        .map(|(t, &position)| {
+            let s = layout.incomplete_roots[position];
+            let ratio = mul_mod(r, inv_mod(s, MOD_Q).expect("slot roots are units"), MOD_Q);
+            sqrt_mod(ratio, MOD_Q).unwrap_or_else(|| {
+                panic!("incomplete-NTT slot {t} of q = {MOD_Q}, N = {N} is not a field (q ≡ 1 mod 2N)")
+            })
+        })
+        .collect()
+}
+
+/// The element whose canonical incomplete-NTT slot t holds `values[t]`.
+/// Needs q ≡ 1 mod N but not mod 2N, so that every slot is a field.
+pub fn embed_in_incomplete_slots<const MOD_Q: u64, const N: usize>(
+    values: &[ExtField<MOD_Q, 2>],
+) -> CyclotomicRing<MOD_Q, N> {
+    let slots: Vec<[u64; 2]> = values
+        .iter()
+        .zip(slot_isomorphisms::<MOD_Q, N>())
+        .map(|(value, lambda)| [value.coefficients[0], mul_mod(value.coefficients[1], lambda, MOD_Q)])
+        .collect();
+    encode_incomplete_slots(&slots)
+}
+
+/// The extension-field value of every canonical incomplete-NTT slot.
+pub fn extract_from_incomplete_slots<const MOD_Q: u64, const N: usize>(
+    element: &CyclotomicRing<MOD_Q, N>,
+) -> Vec<ExtField<MOD_Q, 2>> {
+    decode_incomplete_slots(element)
+        .into_iter()
+        .zip(slot_isomorphisms::<MOD_Q, N>())
+        .map(|([c0, c1], lambda)| {
+            let lambda_inverse = inv_mod(lambda, MOD_Q).expect("λ is a unit");
+            ExtField { coefficients: [c0, mul_mod(c1, lambda_inverse, MOD_Q)] }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cyclotomic_ring::multiply;
+
+    const MOD_Q: u64 = 1125899904679937;
+    // q ≡ 65 mod 128: the 2-way incomplete NTT slots are fields for N = 64.
+    const MOD_Q_INCOMPLETE: u64 = 1125899906842817;
+    // q ≡ 3 mod 4: no irreducible binomial of degree 4.
+    const MOD_Q_3_MOD_4: u64 = 1125899906842679;
+    const N: usize = 64;
+
+    fn check_field<const MOD_Q: u64, const K: usize>() {
+        assert!(K == 1 || is_irreducible(&ExtField::<MOD_Q, K>::RULE, MOD_Q));
+        let one = ExtField::<MOD_Q, K>::one();
+        for _ in 0..8 {
+            let (a, b, c) = (ExtField::<MOD_Q, K>::random(), ExtField::random(), ExtField::random());
+            assert_eq!(a * (b + c), a * b + a * c);
+            assert_eq!((a * b) * c, a * (b * c));
+            assert_eq!(a - b + b, a);
+            assert_eq!(a * a.inverse().unwrap(), one);
+
+            assert_eq!(a.frobenius(), a.pow(MOD_Q));
+            assert_eq!((a * b).frobenius(), a.frobenius() * b.frobenius());
+            assert_eq!(a.frobenius_power(K), a);
+            assert_eq!(a.norm(), mul_mod((a * b).norm(), inv_mod(b.norm(), MOD_Q).unwrap(), MOD_Q));
+        }
+        assert_eq!(ExtField::<MOD_Q, K>::zero().inverse(), None);
+        assert_eq!(ExtField::<MOD_Q, K>::from_base(5).frobenius(), ExtField::from_base(5));
+    }
+
+    #[test]
+    fn test_field_arithmetic() {
+        check_field::<MOD_Q, 1>();
+        check_field::<MOD_Q, 2>();
+        check_field::<MOD_Q, 4>();
+        check_field::<MOD_Q_3_MOD_4, 2>();
+        check_field::<MOD_Q_3_MOD_4, 4>();
+        const { assert!(ExtField::<MOD_Q, 4>::IS_BINOMIAL) };
+        const { assert!(!ExtField::<MOD_Q_3_MOD_4, 4>::IS_BINOMIAL) };
+    }
+
+    #[test]
+    fn test_defining_polynomials() {
+        // Y^2 - r for the smallest non-residue r; Y^2 + 1 factors when q ≡ 1 mod 4.
+        assert_eq!(ExtField::<MOD_Q, 2>::RULE, [smallest_non_residue(MOD_Q), 0]);
+        assert!(!is_irreducible(&[MOD_Q - 1, 0], MOD_Q));
+        assert!(!is_irreducible(&[MOD_Q - 1, 0, 0, 0], MOD_Q_3_MOD_4));
+        // Y^4 - 1 = (Y - 1)(Y + 1)(Y^2 + 1).
+        assert!(!is_irreducible(&[1, 0, 0, 0], MOD_Q));
+    }
+
+    #[test]
+    fn test_incomplete_slot_embedding() {
+        type F = ExtField<MOD_Q_INCOMPLETE, 2>;
+        let u: Vec<F> = (0..N / 2).map(|_| F::random()).collect();
+        let v: Vec<F> = (0..N / 2).map(|_| F::random()).collect();
+        let a = embed_in_incomplete_slots::<MOD_Q_INCOMPLETE, N>(&u);
+        let b = embed_in_incomplete_slots::<MOD_Q_INCOMPLETE, N>(&v);
+        assert_eq!(extract_from_incomplete_slots(&a), u);
+
+        let sum = extract_from_incomplete_slots(&(a + b));
+        let product = extract_from_incomplete_slots(&multiply(&mut a.clone(), &mut b.clone()));
+        for t in 0..N / 2 {
+            assert_eq!(sum[t], u[t] + v[t]);
+            assert_eq!(product[t], u[t] * v[t]);
+        }
+    }
+}
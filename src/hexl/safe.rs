@@ -0,0 +1,637 @@
+//! Safe wrappers around the raw HEXL bindings.
+//!
+//! Every function here validates its arguments before crossing the FFI
+//! boundary: slice lengths must agree, NTT sizes must be powers of two and
+//! the modulus must be one HEXL accepts (below 2^62, and a prime q ≡ 1 mod
+//! 2n for the NTT). Aliasing is ruled out by the borrow checker: outputs
+//! are taken as `&mut` and inputs as `&`, and the only in-place form HEXL
+//! supports (output equal to the first operand) is exposed through the
+//! `*_assign` variants.
+//!
+//! Operand ranges are checked in every build, since HEXL's kernels give
+//! wrong results or read out of bounds on values above their input bound.
+//! The checks are O(n) and cheap next to the kernels they guard.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::bindings;
+use crate::modular::is_prime;
+
+/// HEXL's NTT and element-wise kernels require moduli below 2^62.
+pub const MAX_MODULUS_BITS: u32 = 62;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HexlError {
+    LengthMismatch { expected: usize, found: usize },
+    NotPowerOfTwo(usize),
+    ModulusOutOfRange(u64),
+    NotNttFriendly { n: usize, modulus: u64 },
+    NotPrime(u64),
+    OperandOutOfRange { index: usize, value: u64, bound: u64 },
+    InvalidModFactor(u64),
+}
+
+impl fmt::Display for HexlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HexlError::LengthMismatch { expected, found } => {
+                write!(f, "slice length mismatch: expected {expected}, found {found}")
+            }
+            HexlError::NotPowerOfTwo(n) => write!(f, "NTT size {n} is not a power of two"),
+            HexlError::ModulusOutOfRange(q) => {
+                write!(f, "modulus {q} is outside [2, 2^{MAX_MODULUS_BITS})")
+            }
+            HexlError::NotNttFriendly { n, modulus } => {
+                write!(f, "modulus {modulus} is not 1 mod 2n for NTT size n = {n}")
+            }
+            HexlError::NotPrime(q) => write!(f, "NTT modulus {q} is not prime"),
+            HexlError::OperandOutOfRange { index, value, bound } => {
+                write!(f, "operand {value} at index {index} is not below {bound}")
+            }
+            HexlError::InvalidModFactor(factor) => {
+                write!(f, "modulus factor {factor} is not supported by this kernel")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HexlError {}
+
+pub type Result<T> = std::result::Result<T, HexlError>;
+
+pub fn check_modulus(modulus: u64) -> Result<()> {
+    if !(2..1 << MAX_MODULUS_BITS).contains(&modulus) {
+        return Err(HexlError::ModulusOutOfRange(modulus));
+    }
+    Ok(())
+}
+
+pub fn check_ntt_params(n: usize, modulus: u64) -> Result<()> {
+    if n < 2 || !n.is_power_of_two() {
+        return Err(HexlError::NotPowerOfTwo(n));
+    }
+    check_modulus(modulus)?;
+    if modulus % (2 * n as u64) != 1 {
+        return Err(HexlError::NotNttFriendly { n, modulus });
+    }
+    check_prime(modulus)
+}
+
+/// The last modulus found to be prime. Callers nearly always reuse one
+/// modulus, so the primality test runs about once per modulus rather than
+/// once per NTT.
+static LAST_PRIME: AtomicU64 = AtomicU64::new(0);
+
+/// HEXL aborts when it finds no root of unity, so composite NTT moduli are
+/// rejected here.
+fn check_prime(modulus: u64) -> Result<()> {
+    if LAST_PRIME.load(Ordering::Relaxed) == modulus {
+        return Ok(());
+    }
+    if !is_prime(modulus) {
+        return Err(HexlError::NotPrime(modulus));
+    }
+    LAST_PRIME.store(modulus, Ordering::Relaxed);
+    Ok(())
+}
+
+fn check_len(expected: usize, found: usize) -> Result<()> {
+    if expected != found {
+        return Err(HexlError::LengthMismatch { expected, found });
+    }
+    Ok(())
+}
+
+fn check_reduced(operand: &[u64], bound: u64) -> Result<()> {
+    if let Some((index, &value)) = operand.iter().enumerate().find(|(_, &v)| v >= bound) {
+        return Err(HexlError::OperandOutOfRange { index, value, bound });
+    }
+    Ok(())
+}
+
+fn check_factor(factor: u64, allowed: &[u64]) -> Result<()> {
+    if !allowed.contains(&factor) {
+        return Err(HexlError::InvalidModFactor(factor));
+    }
+    Ok(())
+}
+
+fn check_scalar(value: u64, modulus: u64) -> Result<()> {
+    check_modulus(modulus)?;
+    if value >= modulus {
+        return Err(HexlError::OperandOutOfRange { index: 0, value, bound: modulus });
+    }
+    Ok(())
+}
+
+pub fn multiply_mod(a: u64, b: u64, modulus: u64) -> Result<u64> {
+    check_scalar(a, modulus)?;
+    check_scalar(b, modulus)?;
+    Ok(unsafe { bindings::multiply_mod(a, b, modulus) })
+}
+
+pub fn power_mod(a: u64, exponent: u64, modulus: u64) -> Result<u64> {
+    check_scalar(a, modulus)?;
+    Ok(unsafe { bindings::power_mod(a, exponent, modulus) })
+}
+
+pub fn add_mod(a: u64, b: u64, modulus: u64) -> Result<u64> {
+    check_scalar(a, modulus)?;
+    check_scalar(b, modulus)?;
+    Ok(unsafe { bindings::add_mod(a, b, modulus) })
+}
+
+pub fn sub_mod(a: u64, b: u64, modulus: u64) -> Result<u64> {
+    check_scalar(a, modulus)?;
+    check_scalar(b, modulus)?;
+    Ok(unsafe { bindings::sub_mod(a, b, modulus) })
+}
+
+fn check_binary(result: &[u64], a: &[u64], b: &[u64], modulus: u64) -> Result<()> {
+    check_modulus(modulus)?;
+    check_len(result.len(), a.len())?;
+    check_len(result.len(), b.len())?;
+    check_reduced(a, modulus)?;
+    check_reduced(b, modulus)
+}
+
+/// `result[i] = a[i] * b[i] mod modulus`.
+pub fn eltwise_mult_mod(result: &mut [u64], a: &[u64], b: &[u64], modulus: u64) -> Result<()> {
+    check_binary(result, a, b, modulus)?;
+    unsafe { bindings::eltwise_mult_mod(result.as_mut_ptr(), a.as_ptr(), b.as_ptr(), a.len(), modulus) };
+    Ok(())
+}
+
+/// `a[i] = a[i] * b[i] mod modulus`.
+pub fn eltwise_mult_mod_assign(a: &mut [u64], b: &[u64], modulus: u64) -> Result<()> {
+    check_binary(a, a, b, modulus)?;
+    unsafe { bindings::eltwise_mult_mod(a.as_mut_ptr(), a.as_ptr(), b.as_ptr(), a.len(), modulus) };
+    Ok(())
+}
+
+/// `result[i] = a[i] + b[i] mod modulus`.
+pub fn eltwise_add_mod(result: &mut [u64], a: &[u64], b: &[u64], modulus: u64) -> Result<()> {
+    check_binary(result, a, b, modulus)?;
+    unsafe { bindings::eltwise_add_mod(result.as_mut_ptr(), a.as_ptr(), b.as_ptr(), a.len(), modulus) };
+    Ok(())
+}
+
+/// `a[i] = a[i] + b[i] mod modulus`.
+pub fn eltwise_add_mod_assign(a: &mut [u64], b: &[u64], modulus: u64) -> Result<()> {
+    check_binary(a, a, b, modulus)?;
+    unsafe { bindings::eltwise_add_mod(a.as_mut_ptr(), a.as_ptr(), b.as_ptr(), a.len(), modulus) };
+    Ok(())
+}
+
+/// `result[i] = a[i] - b[i] mod modulus`.
+pub fn eltwise_sub_mod(result: &mut [u64], a: &[u64], b: &[u64], modulus: u64) -> Result<()> {
+    check_binary(result, a, b, modulus)?;
+    unsafe { bindings::eltwise_sub_mod(result.as_mut_ptr(), a.as_ptr(), b.as_ptr(), a.len(), modulus) };
+    Ok(())
+}
+
+/// `a[i] = a[i] - b[i] mod modulus`.
+pub fn eltwise_sub_mod_assign(a: &mut [u64], b: &[u64], modulus: u64) -> Result<()> {
+    check_binary(a, a, b, modulus)?;
+    unsafe { bindings::eltwise_sub_mod(a.as_mut_ptr(), a.as_ptr(), b.as_ptr(), a.len(), modulus) };
+    Ok(())
+}
+
+/// Reduces values in `[0, 4 * modulus)` into `[0, modulus)`.
+pub fn eltwise_reduce_mod(result: &mut [u64], operand: &[u64], modulus: u64) -> Result<()> {
+    check_modulus(modulus)?;
+    check_len(result.len(), operand.len())?;
+    check_reduced(operand, 4 * modulus)?;
+    unsafe { bindings::eltwise_reduce_mod(result.as_mut_ptr(), operand.as_ptr(), operand.len(), modulus) };
+    Ok(())
+}
+
+/// In-place variant of [`eltwise_reduce_mod`].
+pub fn eltwise_reduce_mod_assign(operand: &mut [u64], modulus: u64) -> Result<()> {
+    check_modulus(modulus)?;
+    check_reduced(operand, 4 * modulus)?;
+    unsafe { bindings::eltwise_reduce_mod(operand.as_mut_ptr(), operand.as_ptr(), operand.len(), modulus) };
+    Ok(())
+}
+
+/// Negacyclic forward NTT of `operand` in place; the output is fully reduced.
+pub fn ntt_forward(operand: &mut [u64], modulus: u64) -> Result<()> {
+    check_ntt_params(operand.len(), modulus)?;
+    check_reduced(operand, 4 * modulus)?;
+    unsafe { bindings::ntt_forward_in_place(operand.as_mut_ptr(), operand.len(), modulus) };
+    Ok(())
+}
+
+/// Negacyclic inverse NTT of `operand` in place, for inputs below 2q (the
+/// largest input factor HEXL's inverse NTT accepts); the output is fully
+/// reduced.
+pub fn ntt_inverse(operand: &mut [u64], modulus: u64) -> Result<()> {
+    check_ntt_params(operand.len(), modulus)?;
+    check_reduced(operand, 2 * modulus)?;
+    unsafe { bindings::ntt_inverse_in_place(operand.as_mut_ptr(), operand.len(), modulus) };
+    Ok(())
+}
+
+/// Reduces arbitrary 64-bit values into `[0, modulus)`, e.g. the
+/// accumulators filled by [`eltwise_mult_acc_lazy`].
+pub fn eltwise_reduce_mod_any_assign(operand: &mut [u64], modulus: u64) -> Result<()> {
+    check_modulus(modulus)?;
+    // HEXL reads an input factor equal to the modulus as "any 64-bit value".
+    unsafe {
+        bindings::eltwise_reduce_mod_lazy(operand.as_mut_ptr(), operand.as_ptr(), operand.len(), modulus, modulus, 1)
+    };
+    Ok(())
+}
+
+/// Forward NTT taking inputs in `[0, input_mod_factor * q)` and leaving
+/// outputs in `[0, output_mod_factor * q)`. HEXL accepts input factors
+/// 1, 2 and 4 and output factors 1 and 4.
+pub fn ntt_forward_lazy(operand: &mut [u64], modulus: u64, input_mod_factor: u64, output_mod_factor: u64) -> Result<()> {
+    check_ntt_params(operand.len(), modulus)?;
+    check_factor(input_mod_factor, &[1, 2, 4])?;
+    check_factor(output_mod_factor, &[1, 4])?;
+    check_reduced(operand, input_mod_factor * modulus)?;
+    unsafe {
+        bindings::ntt_forward_in_place_lazy(operand.as_mut_ptr(), operand.len(), modulus, input_mod_factor, output_mod_factor)
+    };
+    Ok(())
+}
+
+/// Inverse NTT with explicit modulus factors; HEXL accepts 1 or 2 for both.
+pub fn ntt_inverse_lazy(operand: &mut [u64], modulus: u64, input_mod_factor: u64, output_mod_factor: u64) -> Result<()> {
+    check_ntt_params(operand.len(), modulus)?;
+    check_factor(input_mod_factor, &[1, 2])?;
+    check_factor(output_mod_factor, &[1, 2])?;
+    check_reduced(operand, input_mod_factor * modulus)?;
+    unsafe {
+        bindings::ntt_inverse_in_place_lazy(operand.as_mut_ptr(), operand.len(), modulus, input_mod_factor, output_mod_factor)
+    };
+    Ok(())
+}
+
+fn check_binary_lazy(result: &[u64], a: &[u64], b: &[u64], modulus: u64, input_mod_factor: u64) -> Result<()> {
+    check_modulus(modulus)?;
+    check_factor(input_mod_factor, &[1, 2, 4])?;
+    check_len(result.len(), a.len())?;
+    check_len(result.len(), b.len())?;
+    check_reduced(a, input_mod_factor * modulus)?;
+    check_reduced(b, input_mod_factor * modulus)
+}
+
+/// `result[i] = a[i] * b[i] mod modulus` for operands in
+/// `[0, input_mod_factor * q)`; the output is fully reduced.
+pub fn eltwise_mult_mod_lazy(result: &mut [u64], a: &[u64], b: &[u64], modulus: u64, input_mod_factor: u64) -> Result<()> {
+    check_binary_lazy(result, a, b, modulus, input_mod_factor)?;
+    unsafe {
+        bindings::eltwise_mult_mod_lazy(result.as_mut_ptr(), a.as_ptr(), b.as_ptr(), a.len(), modulus, input_mod_factor)
+    };
+    Ok(())
+}
+
+/// In-place variant of [`eltwise_mult_mod_lazy`].
+pub fn eltwise_mult_mod_lazy_assign(a: &mut [u64], b: &[u64], modulus: u64, input_mod_factor: u64) -> Result<()> {
+    check_binary_lazy(a, a, b, modulus, input_mod_factor)?;
+    unsafe {
+        bindings::eltwise_mult_mod_lazy(a.as_mut_ptr(), a.as_ptr(), b.as_ptr(), a.len(), modulus, input_mod_factor)
+    };
+    Ok(())
+}
+
+/// Maps values in `[0, input_mod_factor * q)` into `[0, output_mod_factor * q)`.
+/// HEXL accepts input factors 2 and 4 and output factors 1 and 2.
+pub fn eltwise_reduce_mod_lazy_assign(operand: &mut [u64], modulus: u64, input_mod_factor: u64, output_mod_factor: u64) -> Result<()> {
+    check_modulus(modulus)?;
+    check_factor(input_mod_factor, &[2, 4])?;
+    check_factor(output_mod_factor, &[1, 2])?;
+    check_reduced(operand, input_mod_factor * modulus)?;
+    unsafe {
+        bindings::eltwise_reduce_mod_lazy(
+            operand.as_mut_ptr(),
+            operand.as_ptr(),
+            operand.len(),
+            modulus,
+            input_mod_factor,
+            output_mod_factor,
+        )
+    };
+    Ok(())
+}
+
+/// `result[i] = a[i] * scalar + addend[i] mod modulus` for `a` in
+/// `[0, input_mod_factor * q)`; a missing addend is treated as zero.
+pub fn eltwise_fma_mod(
+    result: &mut [u64],
+    a: &[u64],
+    scalar: u64,
+    addend: Option<&[u64]>,
+    modulus: u64,
+    input_mod_factor: u64,
+) -> Result<()> {
+    check_fma(result, a, scalar, addend, modulus, input_mod_factor)?;
+    let addend = addend.map_or(std::ptr::null(), |addend| addend.as_ptr());
+    unsafe {
+        bindings::eltwise_fma_mod(result.as_mut_ptr(), a.as_ptr(), scalar, addend, a.len(), modulus, input_mod_factor)
+    };
+    Ok(())
+}
+
+/// `a[i] = a[i] * scalar + addend[i] mod modulus`.
+pub fn eltwise_fma_mod_assign(
+    a: &mut [u64],
+    scalar: u64,
+    addend: Option<&[u64]>,
+    modulus: u64,
+    input_mod_factor: u64,
+) -> Result<()> {
+    check_fma(a, a, scalar, addend, modulus, input_mod_factor)?;
+    let addend = addend.map_or(std::ptr::null(), |addend| addend.as_ptr());
+    unsafe {
+        bindings::eltwise_fma_mod(a.as_mut_ptr(), a.as_ptr(), scalar, addend, a.len(), modulus, input_mod_factor)
+    };
+    Ok(())
+}
+
+fn check_fma(
+    result: &[u64],
+    a: &[u64],
+    scalar: u64,
+    addend: Option<&[u64]>,
+    modulus: u64,
+    input_mod_factor: u64,
+) -> Result<()> {
+    check_scalar(scalar, modulus)?;
+    check_factor(input_mod_factor, &[1, 2, 4, 8])?;
+    // HEXL only takes inputs below 8q when 8q still fits in 64 bits.
+    if input_mod_factor == 8 && modulus >= 1 << 61 {
+        return Err(HexlError::InvalidModFactor(input_mod_factor));
+    }
+    let bound = input_mod_factor.checked_mul(modulus).ok_or(HexlError::InvalidModFactor(input_mod_factor))?;
+    check_len(result.len(), a.len())?;
+    check_reduced(a, bound)?;
+    if let Some(addend) = addend {
+        check_len(result.len(), addend.len())?;
+        check_reduced(addend, modulus)?;
+    }
+    Ok(())
+}
+
+/// `result[i] = a[i] * b[i] + addend[i] mod modulus` for `a`, `b` in
+/// `[0, input_mod_factor * q)` and a reduced addend.
+pub fn eltwise_mult_add_mod(
+    result: &mut [u64],
+    a: &[u64],
+    b: &[u64],
+    addend: &[u64],
+    modulus: u64,
+    input_mod_factor: u64,
+) -> Result<()> {
+    check_binary_lazy(result, a, b, modulus, input_mod_factor)?;
+    check_len(result.len(), addend.len())?;
+    check_reduced(addend, modulus)?;
+    unsafe {
+        bindings::eltwise_mult_add_mod(
+            result.as_mut_ptr(),
+            a.as_ptr(),
+            b.as_ptr(),
+            addend.as_ptr(),
+            a.len(),
+            modulus,
+            input_mod_factor,
+        )
+    };
+    Ok(())
+}
+
+/// `accumulator[i] = a[i] * b[i] + accumulator[i] mod modulus`.
+pub fn eltwise_mult_add_mod_assign(
+    accumulator: &mut [u64],
+    a: &[u64],
+    b: &[u64],
+    modulus: u64,
+    input_mod_factor: u64,
+) -> Result<()> {
+    check_binary_lazy(accumulator, a, b, modulus, input_mod_factor)?;
+    check_reduced(accumulator, modulus)?;
+    unsafe {
+        bindings::eltwise_mult_add_mod(
+            accumulator.as_mut_ptr(),
+            a.as_ptr(),
+            b.as_ptr(),
+            accumulator.as_ptr(),
+            a.len(),
+            modulus,
+            input_mod_factor,
+        )
+    };
+    Ok(())
+}
+
+/// `accumulator[i] += a[i] * b[i] mod modulus` without reducing the sum.
+/// Every call adds less than `q`, so the caller must reduce (e.g. with
+/// [`eltwise_reduce_mod_any_assign`]) before the accumulator can overflow.
+pub fn eltwise_mult_acc_lazy(
+    accumulator: &mut [u64],
+    a: &[u64],
+    b: &[u64],
+    modulus: u64,
+    input_mod_factor: u64,
+) -> Result<()> {
+    check_binary_lazy(accumulator, a, b, modulus, input_mod_factor)?;
+    check_reduced(accumulator, u64::MAX - (modulus - 2))?;
+    unsafe {
+        bindings::eltwise_mult_acc_lazy(
+            accumulator.as_mut_ptr(),
+            a.as_ptr(),
+            b.as_ptr(),
+            a.len(),
+            modulus,
+            input_mod_factor,
+        )
+    };
+    Ok(())
+}
+
+/// Product of `a` and `b` in `Z_q[X]/(X^n + 1)` through HEXL's NTT.
+pub fn multiply_poly(result: &mut [u64], a: &[u64], b: &[u64], modulus: u64) -> Result<()> {
+    check_binary(result, a, b, modulus)?;
+    check_ntt_params(a.len(), modulus)?;
+    unsafe { bindings::multiply_poly(result.as_mut_ptr(), a.as_ptr(), b.as_ptr(), a.len(), modulus) };
+    Ok(())
+}
+
+/// Same product as [`multiply_poly`], reduced through the wrapper's explicit
+/// cyclotomic folding step.
+pub fn polynomial_multiply_cyclotomic_mod(result: &mut [u64], a: &[u64], b: &[u64], modulus: u64) -> Result<()> {
+    check_binary(result, a, b, modulus)?;
+    check_ntt_params(a.len(), modulus)?;
+    unsafe {
+        bindings::polynomial_multiply_cyclotomic_mod(result.as_mut_ptr(), a.as_ptr(), b.as_ptr(), a.len(), modulus)
+    };
+    Ok(())
+}
+
+/// Copy of the root-of-unity powers HEXL uses for the size-`n` NTT.
+pub fn root_of_unity_powers(n: usize, modulus: u64) -> Result<Vec<u64>> {
+    check_ntt_params(n, modulus)?;
+    let roots = unsafe { std::slice::from_raw_parts(bindings::get_roots(n, modulus), n) };
+    Ok(roots.to_vec())
+}
+
+/// Copy of the inverse root-of-unity powers HEXL uses for the size-`n` NTT.
+pub fn inv_root_of_unity_powers(n: usize, modulus: u64) -> Result<Vec<u64>> {
+    check_ntt_params(n, modulus)?;
+    let roots = unsafe { std::slice::from_raw_parts(bindings::get_inv_roots(n, modulus), n) };
+    Ok(roots.to_vec())
+}
+
+#[cfg(target_arch = "x86_64")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multiply_mod() {
+        let a: u64 = 12345;
+        let b: u64 = 67890;
+        let modulus: u64 = 1000000007;
+        let result = multiply_mod(a, b, modulus).unwrap();
+        assert_eq!(result, 838102050); // This is the expected result for (12345 * 67890) % 1000000007
+    }
+
+    #[test]
+    fn test_eltwise_mult_mod() {
+        let a: Vec<u64> = vec![1, 2, 3, 4, 5];
+        let b: Vec<u64> = vec![6, 7, 8, 9, 10];
+        let modulus: u64 = 100;
+        let mut result: Vec<u64> = vec![0; a.len()];
+
+        eltwise_mult_mod(&mut result, &a, &b, modulus).unwrap();
+
+        assert_eq!(result, vec![6, 14, 24, 36, 50]); // Expected results for element-wise multiplication
+    }
+
+    #[test]
+    fn test_multiply_poly() {
+        let n = 8;
+        let modulus = 65537; // Example modulus
+        let operand1: Vec<u64> = vec![1, 2, 3, 1, 0, 0, 0, 0];
+        let operand2: Vec<u64> = vec![8, 7, 6, 1, 0, 0, 0, 0];
+        let mut result = vec![0u64; n];
+
+        // The expected result must be computed using the same polynomial multiplication logic.
+        // For simplicity, we use a direct multiplication method here assuming the input is small.
+        let mut expected_result = vec![0u64; n];
+        for i in 0..n / 2 {
+            for j in 0..n / 2 {
+                expected_result[i + j] = (expected_result[i + j] + operand1[i] * operand2[j]) % modulus;
+            }
+        }
+
+        multiply_poly(&mut result, &operand1, &operand2, modulus).unwrap();
+
+        assert_eq!(result, expected_result);
+    }
+
+    #[test]
+    fn test_rejects_invalid_arguments() {
+        let mut short = vec![0u64; 4];
+        assert_eq!(
+            eltwise_add_mod(&mut short, &[1, 2, 3, 4], &[1, 2, 3], 17),
+            Err(HexlError::LengthMismatch { expected: 4, found: 3 })
+        );
+        assert_eq!(ntt_forward(&mut [0u64; 6], 13), Err(HexlError::NotPowerOfTwo(6)));
+        assert_eq!(ntt_forward(&mut short, 13), Err(HexlError::NotNttFriendly { n: 4, modulus: 13 }));
+        assert_eq!(ntt_forward(&mut short, 1 << 62), Err(HexlError::ModulusOutOfRange(1 << 62)));
+        assert_eq!(ntt_forward(&mut short, 25), Err(HexlError::NotPrime(25)));
+        assert_eq!(
+            multiply_mod(17, 1, 17),
+            Err(HexlError::OperandOutOfRange { index: 0, value: 17, bound: 17 })
+        );
+    }
+
+    #[test]
+    fn test_ntt_round_trip() {
+        let modulus = 17;
+        let original = vec![1u64, 2, 3, 4];
+        let mut data = original.clone();
+        ntt_forward(&mut data, modulus).unwrap();
+        ntt_inverse(&mut data, modulus).unwrap();
+        assert_eq!(data, original);
+
+        let mut unreduced = vec![1u64, 2, 3, 2 * modulus];
+        assert_eq!(
+            ntt_inverse(&mut unreduced, modulus),
+            Err(HexlError::OperandOutOfRange { index: 3, value: 34, bound: 34 })
+        );
+    }
+
+    #[test]
+    fn test_lazy_ntt_then_multiply() {
+        let modulus = 17;
+        let mut a = vec![1u64, 2, 3, 4];
+        let mut b = vec![4u64, 3, 2, 1];
+        let mut expected = vec![0u64; 4];
+        multiply_poly(&mut expected, &a, &b, modulus).unwrap();
+
+        ntt_forward_lazy(&mut a, modulus, 1, 4).unwrap();
+        ntt_forward_lazy(&mut b, modulus, 1, 4).unwrap();
+        eltwise_mult_mod_lazy_assign(&mut a, &b, modulus, 4).unwrap();
+        ntt_inverse(&mut a, modulus).unwrap();
+
+        assert_eq!(a, expected);
+        assert_eq!(ntt_inverse_lazy(&mut a, modulus, 4, 1), Err(HexlError::InvalidModFactor(4)));
+    }
+
+    #[test]
+    fn test_fused_kernels() {
+        let modulus = 97;
+        let a = vec![5u64, 60, 96, 0];
+        let b = vec![50u64, 40, 3, 1];
+        let c = vec![1u64, 2, 3, 4];
+        let expected: Vec<u64> = (0..4).map(|i| (a[i] * b[i] + c[i]) % modulus).collect();
+
+        let mut result = vec![0u64; 4];
+        eltwise_mult_add_mod(&mut result, &a, &b, &c, modulus, 1).unwrap();
+        assert_eq!(result, expected);
+
+        let mut accumulator = c.clone();
+        eltwise_mult_add_mod_assign(&mut accumulator, &a, &b, modulus, 1).unwrap();
+        assert_eq!(accumulator, expected);
+
+        let mut lazy = c.clone();
+        eltwise_mult_acc_lazy(&mut lazy, &a, &b, modulus, 1).unwrap();
+        eltwise_mult_acc_lazy(&mut lazy, &a, &b, modulus, 1).unwrap();
+        eltwise_reduce_mod_any_assign(&mut lazy, modulus).unwrap();
+        let twice: Vec<u64> = (0..4).map(|i| (2 * a[i] * b[i] + c[i]) % modulus).collect();
+        assert_eq!(lazy, twice);
+
+        eltwise_fma_mod(&mut result, &a, 3, Some(&c), modulus, 1).unwrap();
+        let scaled: Vec<u64> = (0..4).map(|i| (a[i] * 3 + c[i]) % modulus).collect();
+        assert_eq!(result, scaled);
+
+        let large = (1 << 61) + 1;
+        assert_eq!(
+            eltwise_fma_mod(&mut result, &a, 3, None, large, 8),
+            Err(HexlError::InvalidModFactor(8))
+        );
+        assert_eq!(
+            eltwise_fma_mod(&mut result, &[8 * modulus, 0, 0, 0], 3, None, modulus, 8),
+            Err(HexlError::OperandOutOfRange { index: 0, value: 8 * modulus, bound: 8 * modulus })
+        );
+    }
+
+    #[test]
+    fn test_assign_variants_match_out_of_place() {
+        let modulus = 97;
+        let a = vec![5u64, 60, 96, 0];
+        let b = vec![50u64, 40, 3, 1];
+        let mut expected = vec![0u64; 4];
+        let mut in_place = a.clone();
+
+        eltwise_sub_mod(&mut expected, &a, &b, modulus).unwrap();
+        eltwise_sub_mod_assign(&mut in_place, &b, modulus).unwrap();
+
+        assert_eq!(in_place, expected);
+    }
+}
@@ -0,0 +1,272 @@
+//! Units of Z_q[X]/(X^N + 1).
+//!
+//! By the CRT, an element is invertible iff it is invertible in every slot
+//! of the splitting of X^N + 1. The inversion works in the d-way
+//! incomplete NTT the multiplication strategy uses: with d = 1 the slots
+//! are scalars, inverted together with one modular inversion, and with
+//! d > 1 each slot is a degree-(d-1) polynomial inverted modulo X^d - ω by
+//! the extended Euclidean algorithm. Without an NTT the whole element is
+//! inverted modulo X^N + 1 the same way.
+
+use crate::cyclotomic_ring::{
+    d_way_forward, d_way_inverse, get_shift_factors_cached_for, multiply, CyclotomicRing, Representation,
+};
+use crate::hexl::safe::eltwise_reduce_mod_lazy_assign;
+use crate::modular::{batch_inv_mod, inv_mod, is_prime, mul_mod};
+use crate::polymul::{CoefficientArith, Modulus};
+use crate::splitting::MultiplicationStrategy;
+
+fn trim(p: &mut Vec<u64>) {
+    while p.last() == Some(&0) {
+        p.pop();
+    }
+}
+
+/// Quotient and remainder of `numerator / denominator` over Z_q, or `None`
+/// if the leading coefficient of `denominator` is not a unit.
+fn div_rem(numerator: &[u64], denominator: &[u64], modulus: u64) -> Option<(Vec<u64>, Vec<u64>)> {
+    let m = Modulus(modulus);
+    let inv_lead = inv_mod(*denominator.last().unwrap(), modulus)?;
+    let mut remainder = numerator.to_vec();
+    let mut quotient = vec![0u64; numerator.len() + 1 - denominator.len()];
+    for i in (0..quotient.len()).rev() {
+        let c = mul_mod(remainder[i + denominator.len() - 1], inv_lead, modulus);
+        quotient[i] = c;
+        for (j, &d) in denominator.iter().enumerate() {
+            remainder[i + j] = m.sub(remainder[i + j], mul_mod(c, d, modulus));
+        }
+    }
+    remainder.truncate(denominator.len() - 1);
+    trim(&mut remainder);
+    Some((quotient, remainder))
+}
+
+/// Inverse of `a` modulo X^d - ω over F_q, with d = `a.len()`, or `None`
+/// if the two share a factor. Extended Euclid keeping only the Bézout
+/// coefficient of `a`.
+pub fn invert_mod_binomial(a: &[u64], omega: u64, modulus: u64) -> Option<Vec<u64>> {
+    let m = Modulus(modulus);
+    let d = a.len();
+    let mut r0 = vec![0u64; d + 1];
+    r0[0] = m.sub(0, omega % modulus);
+    r0[d] = 1;
+    let mut r1: Vec<u64> = a.iter().map(|&c| c % modulus).collect();
+    trim(&mut r1);
+    let (mut s0, mut s1) = (Vec::new(), vec![1 % modulus]);
+    loop {
+        match r1.len() {
+            0 => return None,
+            1 => {
+                let scale = inv_mod(r1[0], modulus)?;
+                let mut inverse: Vec<u64> = s1.iter().map(|&c| mul_mod(c, scale, modulus)).collect();
+                debug_assert!(inverse.len() <= d);
+                inverse.resize(d, 0);
+                return Some(inverse);
+            }
+            _ => {}
+        }
+        let (quotient, remainder) = div_rem(&r0, &r1, modulus)?;
+        let mut product = vec![0u64; quotient.len() + s1.len() - 1];
+        m.schoolbook(&quotient, &s1, &mut product);
+        let mut s2 = vec![0u64; product.len().max(s0.len())];
+        for (i, c) in s2.iter_mut().enumerate() {
+            *c = m.sub(s0.get(i).copied().unwrap_or(0), product.get(i).copied().unwrap_or(0));
+        }
+        trim(&mut s2);
+        (r0, r1) = (r1, remainder);
+        (s0, s1) = (s1, s2);
+    }
+}
+
+impl<const MOD_Q: u64, const N: usize> CyclotomicRing<MOD_Q, N> {
+    /// Degree of the slots inversion works in, `None` without an NTT.
+    fn inversion_slot_degree() -> Option<usize> {
+        match Self::MULTIPLICATION_STRATEGY {
+            MultiplicationStrategy::FullySplitting => Some(1),
+            MultiplicationStrategy::IncompleteNtt { degree } => Some(degree),
+            MultiplicationStrategy::NonNtt => None,
+        }
+    }
+
+    /// Reduced slot values of the d-way incomplete NTT, reusing the stored
+    /// values when they already are in that layout.
+    fn inversion_slots(&self, degree: usize) -> [u64; N] {
+        match (self.representation, degree) {
+            (Representation::NTT, 1) | (Representation::IncompleteNTT, 2) => self.reduced().data,
+            _ => {
+                let mut slots = d_way_forward::<MOD_Q, N>(&self.canonical_coefficients(), degree);
+                eltwise_reduce_mod_lazy_assign(&mut slots, MOD_Q, 4, 1).expect("reduce slots");
+                slots
+            }
+        }
+    }
+
+    /// Whether the element is a unit, i.e. nonzero in every field of the
+    /// CRT decomposition. For q = 2^k it is a unit iff it is one mod 2,
+    /// where X^N + 1 = (X + 1)^N, i.e. iff its coefficient sum is odd.
+    /// Panics for other composite q.
+    pub fn is_invertible(&self) -> bool {
+        if MOD_Q.is_power_of_two() {
+            return self.canonical_coefficients().iter().fold(0, |sum, &c| sum ^ (c & 1)) == 1;
+        }
+        assert!(is_prime(MOD_Q), "inversion needs a prime modulus, got {MOD_Q}");
+        match Self::inversion_slot_degree() {
+            Some(1) => self.inversion_slots(1).iter().all(|&slot| slot != 0),
+            _ => self.inverse().is_some(),
+        }
+    }
+
+    /// The multiplicative inverse, in the same representation as `self`,
+    /// or `None` if the element is not a unit. Panics for composite q.
+    pub fn inverse(&self) -> Option<Self> {
+        assert!(is_prime(MOD_Q), "inversion needs a prime modulus, got {MOD_Q}");
+        let Some(degree) = Self::inversion_slot_degree() else {
+            let mut result = Self::new();
+            result.data.copy_from_slice(&invert_mod_binomial(&self.canonical_coefficients(), MOD_Q - 1, MOD_Q)?);
+            result.adjust_representation(self.representation);
+            return Some(result);
+        };
+
+        let mut slots = self.inversion_slots(degree);
+        if degree == 1 {
+            if !batch_inv_mod(&mut slots, MOD_Q) {
+                return None;
+            }
+        } else {
+            let size = N / degree;
+            let shift_factors = get_shift_factors_cached_for(MOD_Q, size);
+            let mut slot = vec![0u64; degree];
+            for k in 0..size {
+                for j in 0..degree {
+                    slot[j] = slots[j * size + k];
+                }
+                let inverse = invert_mod_binomial(&slot, shift_factors[k], MOD_Q)?;
+                for j in 0..degree {
+                    slots[j * size + k] = inverse[j];
+                }
+            }
+        }
+
+        let mut result = Self::new();
+        match (self.representation, degree) {
+            (Representation::NTT, 1) | (Representation::IncompleteNTT, 2) => {
+                result.data = slots;
+                result.representation = self.representation;
+            }
+            _ => {
+                result.data = d_way_inverse::<MOD_Q, N>(&mut slots, degree);
+                result.adjust_representation(self.representation);
+            }
+        }
+        Some(result)
+    }
+}
+
+/// Inverts every element with Montgomery's trick: one ring inversion and
+/// 3(n - 1) multiplications. Returns `None` if any element is not a unit.
+/// Each inverse is in the representation of its input.
+pub fn batch_inverse<const MOD_Q: u64, const N: usize>(
+    elements: &[CyclotomicRing<MOD_Q, N>],
+) -> Option<Vec<CyclotomicRing<MOD_Q, N>>> {
+    if elements.is_empty() {
+        return Some(Vec::new());
+    }
+    let mut prefix = Vec::with_capacity(elements.len());
+    let mut acc = elements[0];
+    prefix.push(acc);
+    for element in &elements[1..] {
+        acc = multiply(&mut acc, &mut element.clone());
+        prefix.push(acc);
+    }
+
+    let mut inverse = acc.inverse()?;
+    let mut result = vec![CyclotomicRing::new(); elements.len()];
+    for i in (1..elements.len()).rev() {
+        let mut element_inverse = multiply(&mut inverse.clone(), &mut prefix[i - 1].clone());
+        element_inverse.adjust_representation(elements[i].representation);
+        result[i] = element_inverse;
+        inverse = multiply(&mut inverse, &mut elements[i].clone());
+    }
+    inverse.adjust_representation(elements[0].representation);
+    result[0] = inverse;
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_inverse<const MOD_Q: u64, const N: usize>() {
+        let one = CyclotomicRing::<MOD_Q, N>::one();
+        for _ in 0..4 {
+            let a = CyclotomicRing::<MOD_Q, N>::random();
+            // Random elements are units with overwhelming probability for these q.
+            assert!(a.is_invertible());
+            let inverse = a.inverse().unwrap();
+            assert_eq!(multiply(&mut a.clone(), &mut inverse.clone()), one);
+        }
+    }
+
+    #[test]
+    fn test_inverse_for_every_strategy() {
+        check_inverse::<1125899904679937, 64>(); // fully splitting
+        check_inverse::<1125899906842817, 64>(); // q ≡ 65 mod 128: 2-way incomplete
+        check_inverse::<1125899906843233, 64>(); // q ≡ 33 mod 64: 4-way incomplete
+        check_inverse::<1125899906842769, 64>(); // q ≡ 17 mod 32: 8-way incomplete
+        check_inverse::<2305843009213693951, 16>(); // 2^61 - 1: no NTT
+    }
+
+    #[test]
+    fn test_non_units_and_representations() {
+        const MOD_Q: u64 = 1125899904679937;
+        const N: usize = 64;
+        // An element with a zero NTT slot is not a unit.
+        let mut a = CyclotomicRing::<MOD_Q, N>::random();
+        a.to_ntt_representation();
+        a.data[5] = 0;
+        assert!(!a.is_invertible());
+        assert!(a.inverse().is_none());
+
+        let mut b = CyclotomicRing::<MOD_Q, N>::random();
+        b.to_incomplete_ntt_representation();
+        let inverse = b.inverse().unwrap();
+        assert_eq!(inverse.representation, Representation::IncompleteNTT);
+        assert_eq!(multiply(&mut b.clone(), &mut inverse.clone()), CyclotomicRing::one());
+
+        // Mod 2^k the units are the elements with an odd coefficient sum.
+        type PowerOfTwo = CyclotomicRing<{ 1 << 32 }, N>;
+        assert!(PowerOfTwo::one().is_invertible());
+        let mut one_plus_x = PowerOfTwo::one();
+        one_plus_x.data[1] = 1;
+        assert!(!one_plus_x.is_invertible());
+        one_plus_x.data[2] = (1 << 32) - 1;
+        assert!(one_plus_x.is_invertible());
+
+        // X - 3 divides X^2 - 9.
+        assert!(invert_mod_binomial(&[MOD_Q - 3, 1], 9, MOD_Q).is_none());
+        assert!(invert_mod_binomial(&[0, 0], 9, MOD_Q).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "inversion needs a prime modulus")]
+    fn test_composite_modulus() {
+        CyclotomicRing::<{ 3 * 5 * 7 * 11 * 13 }, 64>::one().is_invertible();
+    }
+
+    #[test]
+    fn test_batch_inverse() {
+        const MOD_Q: u64 = 1125899904679937;
+        const N: usize = 64;
+        let mut elements: Vec<_> = (0..10).map(|_| CyclotomicRing::<MOD_Q, N>::random()).collect();
+        elements[3].to_ntt_representation();
+        elements[7].to_incomplete_ntt_representation();
+        let inverses = batch_inverse(&elements).unwrap();
+        for (element, inverse) in elements.iter().zip(&inverses) {
+            assert_eq!(inverse.representation, element.representation);
+            assert_eq!(inverse, &element.inverse().unwrap());
+        }
+
+        elements[4] = CyclotomicRing::new();
+        assert!(batch_inverse(&elements).is_none());
+    }
+}
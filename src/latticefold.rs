@@ -0,0 +1,736 @@
+//! One LatticeFold step (Boneh–Chen, 2024): fold a fresh CCS instance into
+//! an accumulated linearized instance, keeping the witness norm bounded.
+//!
+//! A linearized instance (cm, x, r, v) with witness w claims, for
+//! z = (x, w) and r ∈ Z_q^ℓ,
+//!
+//!   cm = A·w,  v_j = Σ_b eq(r, b)·(M_j·z)_b for every matrix M_j,
+//!   v_t = Σ_b eq(r, b)·w_b,  ‖w‖∞ small.
+//!
+//! These claims are linear in (x, w), so they survive random linear
+//! combinations; the accumulated x starts as (1, public) and its first
+//! entry drifts away from 1 as instances are folded. A step runs
+//!
+//! 1. Π_lin: a sumcheck of Σ_b eq(β, b)·Σ_i c_i·∘_(j ∈ S_i) (M_j·z)_b = 0
+//!    turns the CCS instance into a linearized one at the sumcheck point.
+//! 2. Π_dec ([`crate::decomposition`]): both linearized witnesses are
+//!    split into k balanced base-b digits w = Σ b^i·w_i. The prover commits
+//!    to every digit vector and the verifier checks the commitments and
+//!    evaluations recombine.
+//! 3. Π_fold: one sumcheck moves all 2k evaluation claims to a common
+//!    point and checks every digit lies in (-b/2, b/2]. The 2k instances
+//!    are then combined with short challenges ρ_i, so the folded witness
+//!    has ‖w‖∞ ≤ 2k·‖ρ‖_1·b/2.
+//!
+//! The range check needs coefficient-wise products. Moving the N
+//! coefficients of a ring element into the N NTT slots is Z_q-linear, so
+//! it commutes with multilinear extensions at points of Z_q^ℓ, and ring
+//! products of the images are slot-wise. This needs q ≡ 1 mod 2N.
+//!
+//! All sumcheck challenges are constants from Z_q, drawn from a
+//! [`Transcript`] that prover and verifier feed the same messages.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::algebra::RingElement;
+use crate::ccs::{Ccs, CcsError};
+use crate::challenge::ChallengeSet;
+use crate::commitment::{linear_combination, AjtaiKey};
+use crate::cyclotomic_ring::CyclotomicRing;
+use crate::decomposition::{Decomposition, DecompositionProof, NormBound};
+use crate::mle::{eq, eq_table, MultilinearExtension};
+use crate::slots::encode_ntt_slots;
+use crate::sumcheck::{verify, RoundPolynomial, SumcheckError, SumcheckProof, SumcheckProver};
+use crate::transcript::Transcript;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FoldingParams {
+    /// Ring elements per commitment.
+    pub kappa: usize,
+    /// Decomposition base b; digits lie in (-b/2, b/2].
+    pub base: u64,
+    /// Digits k per decomposed witness.
+    pub digits: usize,
+    pub challenge_set: ChallengeSet,
+}
+
+impl FoldingParams {
+    /// N = 64: b = 4, k = 6, ternary challenges of weight 20 and κ = 4.
+    pub const DEFAULT_64: FoldingParams = FoldingParams {
+        kappa: 4,
+        base: 4,
+        digits: 6,
+        challenge_set: ChallengeSet::FixedWeightTernary { weight: 20 },
+    };
+
+    /// Instances combined in Π_fold: the digits of two witnesses.
+    pub fn arity(&self) -> usize {
+        2 * self.digits
+    }
+
+    /// The digits Π_fold accepts, -⌊(b-1)/2⌋ up to ⌊b/2⌋.
+    pub fn digit_range(&self) -> std::ops::RangeInclusive<i64> {
+        -(((self.base - 1) / 2) as i64)..=(self.base / 2) as i64
+    }
+
+    pub fn decomposition(&self) -> Decomposition {
+        Decomposition { base: self.base, digits: self.digits }
+    }
+
+    /// Every coefficient with absolute value up to this has k digits.
+    pub fn decomposition_bound(&self) -> u64 {
+        self.decomposition().bound().0
+    }
+
+    /// ‖w‖∞ of a folded witness: 2k digit vectors, each multiplied by a
+    /// challenge of ℓ1 norm at most ‖ρ‖_1.
+    pub fn norm_bound(&self) -> u64 {
+        NormBound::fold(vec![self.decomposition().digit_bound(); self.arity()], &self.challenge_set).0
+    }
+
+    /// Whether a folded witness can be decomposed again.
+    pub fn is_valid(&self) -> bool {
+        self.base >= 3 && self.digits >= 1 && self.kappa >= 1 && self.norm_bound() <= self.decomposition_bound()
+    }
+}
+
+/// The claims described in the module documentation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearizedInstance<const MOD_Q: u64, const N: usize> {
+    pub commitment: Vec<CyclotomicRing<MOD_Q, N>>,
+    /// x, the first part of z, starting as (1, public).
+    pub public: Vec<CyclotomicRing<MOD_Q, N>>,
+    pub point: Vec<u64>,
+    /// v_1, ..., v_t for the matrices, then the evaluation of w.
+    pub evaluations: Vec<CyclotomicRing<MOD_Q, N>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearizationProof<const MOD_Q: u64, const N: usize> {
+    pub sumcheck: SumcheckProof<CyclotomicRing<MOD_Q, N>>,
+    pub evaluations: Vec<CyclotomicRing<MOD_Q, N>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FoldingProof<const MOD_Q: u64, const N: usize> {
+    pub linearization: LinearizationProof<MOD_Q, N>,
+    /// Of the accumulator, then of the new instance.
+    pub decompositions: [DecompositionProof<MOD_Q, N>; 2],
+    pub sumcheck: SumcheckProof<CyclotomicRing<MOD_Q, N>>,
+    /// The 2k instances' evaluations at the new point.
+    pub evaluations: Vec<Vec<CyclotomicRing<MOD_Q, N>>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FoldingError {
+    Ccs(CcsError),
+    Sumcheck(SumcheckError),
+    /// A proof or instance has the wrong number of elements.
+    MalformedProof,
+    /// The last Π_lin sumcheck claim does not match the evaluations.
+    LinearizationMismatch,
+    /// The digits do not recombine to the decomposed instance.
+    DecompositionMismatch,
+    /// The last Π_fold sumcheck claim does not match the evaluations.
+    FoldingMismatch,
+    /// A witness coefficient is too large to be decomposed.
+    NormTooLarge { bound: u64, actual: u64 },
+    CommitmentMismatch,
+    EvaluationMismatch,
+    /// LatticeFold+: the committed matrix is not made of monomials.
+    MonomialMismatch,
+    /// LatticeFold+: the monomials do not encode the witness digits.
+    RangeMismatch,
+    /// LatticeFold+: the combined commitments are not those of the columns
+    /// under the double commitment.
+    DoubleCommitmentMismatch,
+    /// LatticeFold+: the combined column does not match the monomials.
+    CombinationMismatch,
+}
+
+impl fmt::Display for FoldingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FoldingError::Ccs(error) => write!(f, "{error}"),
+            FoldingError::Sumcheck(error) => write!(f, "{error}"),
+            FoldingError::MalformedProof => write!(f, "proof has the wrong shape"),
+            FoldingError::LinearizationMismatch => write!(f, "linearization evaluations do not match the sumcheck"),
+            FoldingError::DecompositionMismatch => write!(f, "digits do not recombine to the instance"),
+            FoldingError::FoldingMismatch => write!(f, "folding evaluations do not match the sumcheck"),
+            FoldingError::NormTooLarge { bound, actual } => {
+                write!(f, "witness norm {actual} exceeds the bound {bound}")
+            }
+            FoldingError::CommitmentMismatch => write!(f, "witness does not open the commitment"),
+            FoldingError::EvaluationMismatch => write!(f, "witness does not match the evaluations"),
+            FoldingError::MonomialMismatch => write!(f, "committed matrix is not a monomial matrix"),
+            FoldingError::RangeMismatch => write!(f, "monomials do not encode the witness"),
+            FoldingError::DoubleCommitmentMismatch => write!(f, "combined commitments do not match the double commitment"),
+            FoldingError::CombinationMismatch => write!(f, "combined column does not match the monomials"),
+        }
+    }
+}
+
+impl Error for FoldingError {}
+
+impl From<CcsError> for FoldingError {
+    fn from(error: CcsError) -> Self {
+        FoldingError::Ccs(error)
+    }
+}
+
+impl From<SumcheckError> for FoldingError {
+    fn from(error: SumcheckError) -> Self {
+        FoldingError::Sumcheck(error)
+    }
+}
+
+/// Largest ℓ1 norm of any challenge in `set`, which bounds how much
+/// folding with it can grow the infinity norm.
+pub(crate) fn challenge_l1_norm(set: &ChallengeSet) -> u64 {
+    match *set {
+        ChallengeSet::FixedWeightTernary { weight } => weight as u64,
+        ChallengeSet::Labrador { ones, twos, .. } => (ones + 2 * twos) as u64,
+    }
+}
+
+pub(crate) fn absorb_instance<const MOD_Q: u64, const N: usize>(
+    transcript: &mut Transcript,
+    instance: &LinearizedInstance<MOD_Q, N>,
+) {
+    transcript.append_commitment(b"commitment", &instance.commitment);
+    transcript.append_commitment(b"public", &instance.public);
+    for &r in &instance.point {
+        transcript.append_u64(b"point", r);
+    }
+    transcript.append_commitment(b"evaluations", &instance.evaluations);
+}
+
+pub(crate) fn challenge_scalar<const MOD_Q: u64, const N: usize>(transcript: &mut Transcript, label: &[u8]) -> CyclotomicRing<MOD_Q, N> {
+    RingElement::from_u64(transcript.challenge_u64(label, MOD_Q))
+}
+
+/// Absorbs each round polynomial and answers with a Z_q challenge.
+pub(crate) fn sumcheck_challenges<const MOD_Q: u64, const N: usize>(
+    transcript: &mut Transcript,
+) -> impl FnMut(&RoundPolynomial<CyclotomicRing<MOD_Q, N>>) -> CyclotomicRing<MOD_Q, N> + '_ {
+    move |round| {
+        transcript.append_commitment(b"round polynomial", &round.evaluations);
+        challenge_scalar(transcript, b"sumcheck challenge")
+    }
+}
+
+pub(crate) fn to_scalars<const MOD_Q: u64, const N: usize>(point: &[CyclotomicRing<MOD_Q, N>]) -> Vec<u64> {
+    point.iter().map(|r| r.canonical_coefficients()[0]).collect()
+}
+
+pub(crate) fn to_ring_point<const MOD_Q: u64, const N: usize>(point: &[u64]) -> Vec<CyclotomicRing<MOD_Q, N>> {
+    point.iter().map(|&r| RingElement::from_u64(r)).collect()
+}
+
+/// The element whose NTT slots hold the coefficients of `element`.
+fn coefficients_in_slots<const MOD_Q: u64, const N: usize>(element: &CyclotomicRing<MOD_Q, N>) -> CyclotomicRing<MOD_Q, N> {
+    encode_ntt_slots(&element.canonical_coefficients())
+}
+
+/// Challenges of Π_fold and the polynomial its sumcheck runs on, over the
+/// tables eq(β), eq(r_i) for every instance, the slot-encoded witnesses,
+/// and the t + 1 evaluation tables of every instance.
+struct FoldChallenges<const MOD_Q: u64, const N: usize> {
+    beta: Vec<u64>,
+    alpha: Vec<CyclotomicRing<MOD_Q, N>>,
+    mu: Vec<CyclotomicRing<MOD_Q, N>>,
+    gamma: Vec<CyclotomicRing<MOD_Q, N>>,
+    digits: Vec<CyclotomicRing<MOD_Q, N>>,
+}
+
+impl<const MOD_Q: u64, const N: usize> FoldChallenges<MOD_Q, N> {
+    fn draw(transcript: &mut Transcript, params: &FoldingParams, num_variables: usize, claims: usize) -> Self {
+        let arity = params.arity();
+        Self {
+            beta: (0..num_variables).map(|_| transcript.challenge_u64(b"fold beta", MOD_Q)).collect(),
+            alpha: (0..arity).map(|_| challenge_scalar(transcript, b"fold alpha")).collect(),
+            mu: (0..arity).map(|_| challenge_scalar(transcript, b"fold mu")).collect(),
+            gamma: (0..claims).map(|_| challenge_scalar(transcript, b"fold gamma")).collect(),
+            digits: params
+                .digit_range()
+                .map(|a| RingElement::from_u64(if a < 0 { MOD_Q - a.unsigned_abs() } else { a as u64 }))
+                .collect(),
+        }
+    }
+
+    /// Π_(a ∈ digits) (s - a), zero in every slot holding a digit.
+    fn range(&self, s: CyclotomicRing<MOD_Q, N>) -> CyclotomicRing<MOD_Q, N> {
+        self.digits.iter().fold(RingElement::one(), |product, &a| product * (s - a))
+    }
+
+    fn combine(&self, values: &[CyclotomicRing<MOD_Q, N>]) -> CyclotomicRing<MOD_Q, N> {
+        let arity = self.alpha.len();
+        let claims = self.gamma.len();
+        let (eq_beta, rest) = values.split_first().expect("eq(β) table");
+        let (eq_points, rest) = rest.split_at(arity);
+        let (slots, evaluations) = rest.split_at(arity);
+        (0..arity).fold(RingElement::zero(), |sum, i| {
+            let claim = evaluations[i * claims..(i + 1) * claims]
+                .iter()
+                .zip(&self.gamma)
+                .fold(RingElement::zero(), |sum, (&e, &g)| sum + g * e);
+            sum + self.alpha[i] * *eq_beta * self.range(slots[i]) + self.mu[i] * eq_points[i] * claim
+        })
+    }
+}
+
+/// Proves and verifies folding steps for one CCS.
+#[derive(Clone, Debug)]
+pub struct LatticeFold<const MOD_Q: u64, const N: usize> {
+    pub ccs: Ccs<MOD_Q, N>,
+    pub key: AjtaiKey<MOD_Q, N>,
+    pub params: FoldingParams,
+    /// ℓ: constraints and witness are padded to 2^ℓ.
+    pub num_variables: usize,
+}
+
+impl<const MOD_Q: u64, const N: usize> LatticeFold<MOD_Q, N> {
+    /// Expands the commitment key from `seed`.
+    pub fn new(ccs: Ccs<MOD_Q, N>, params: FoldingParams, seed: &[u8]) -> Self {
+        assert!(params.is_valid(), "folded witnesses of {params:?} cannot be decomposed again");
+        let key = AjtaiKey::setup(params.kappa, ccs.num_witness(), seed);
+        let num_variables = ccs.num_constraints().max(ccs.num_witness()).next_power_of_two().trailing_zeros() as usize;
+        Self { ccs, key, params, num_variables }
+    }
+
+    pub fn commit(&self, witness: &[CyclotomicRing<MOD_Q, N>]) -> Vec<CyclotomicRing<MOD_Q, N>> {
+        self.key.commit(witness)
+    }
+
+    /// The t + 1 tables whose evaluations a linearized instance claims:
+    /// M_j·z for every matrix, then w, padded with zeros.
+    pub(crate) fn evaluation_tables(
+        &self,
+        public: &[CyclotomicRing<MOD_Q, N>],
+        witness: &[CyclotomicRing<MOD_Q, N>],
+    ) -> Vec<Vec<CyclotomicRing<MOD_Q, N>>> {
+        let len = 1 << self.num_variables;
+        let z: Vec<_> = public.iter().chain(witness).copied().collect();
+        let mut tables: Vec<Vec<_>> = self.ccs.matrices.iter().map(|m| m.mul_vector(&z)).collect();
+        tables.push(witness.to_vec());
+        for table in tables.iter_mut() {
+            table.resize(len, RingElement::zero());
+        }
+        tables
+    }
+
+    pub(crate) fn evaluate(
+        &self,
+        public: &[CyclotomicRing<MOD_Q, N>],
+        witness: &[CyclotomicRing<MOD_Q, N>],
+        point: &[u64],
+    ) -> Vec<CyclotomicRing<MOD_Q, N>> {
+        self.evaluation_tables(public, witness)
+            .into_iter()
+            .map(|table| MultilinearExtension::new(table).evaluate_scalar(point))
+            .collect()
+    }
+
+    /// Σ_i c_i·Π_(j ∈ S_i) values[j].
+    fn constraint_polynomial(&self, values: &[CyclotomicRing<MOD_Q, N>]) -> CyclotomicRing<MOD_Q, N> {
+        self.ccs.multisets.iter().zip(&self.ccs.constants).fold(RingElement::zero(), |sum, (multiset, &c)| {
+            let term = multiset.iter().fold(RingElement::one(), |product, &j| product * values[j]);
+            sum + RingElement::scalar_mul(&term, c)
+        })
+    }
+
+    /// Π_lin for a CCS instance with commitment `commitment`.
+    pub fn linearize(
+        &self,
+        transcript: &mut Transcript,
+        commitment: &[CyclotomicRing<MOD_Q, N>],
+        public: &[CyclotomicRing<MOD_Q, N>],
+        witness: &[CyclotomicRing<MOD_Q, N>],
+    ) -> Result<(LinearizedInstance<MOD_Q, N>, LinearizationProof<MOD_Q, N>), FoldingError> {
+        self.ccs.check(public, witness)?;
+        self.params.decomposition().bound().check(witness)?;
+        let z_public: Vec<_> = std::iter::once(RingElement::one()).chain(public.iter().copied()).collect();
+        self.absorb_ccs_instance(transcript, commitment, public);
+        let beta: Vec<u64> = (0..self.num_variables).map(|_| transcript.challenge_u64(b"lin beta", MOD_Q)).collect();
+
+        let mut tables = vec![eq_table(&to_ring_point::<MOD_Q, N>(&beta))];
+        let mut evaluation_tables = self.evaluation_tables(&z_public, witness);
+        let witness_table = evaluation_tables.pop().expect("witness table");
+        tables.extend(evaluation_tables);
+        let degree = self.ccs.degree() + 1;
+        let mut prover = SumcheckProver::new(tables, degree, |f| f[0] * self.constraint_polynomial(&f[1..]));
+        let (sumcheck, point) = prover.prove(sumcheck_challenges(transcript));
+        let point = to_scalars(&point);
+
+        let mut evaluations = prover.final_evaluations().split_off(1);
+        evaluations.push(MultilinearExtension::new(witness_table).evaluate_scalar(&point));
+        transcript.append_commitment(b"lin evaluations", &evaluations);
+        let instance =
+            LinearizedInstance { commitment: commitment.to_vec(), public: z_public, point, evaluations: evaluations.clone() };
+        Ok((instance, LinearizationProof { sumcheck, evaluations }))
+    }
+
+    fn absorb_ccs_instance(
+        &self,
+        transcript: &mut Transcript,
+        commitment: &[CyclotomicRing<MOD_Q, N>],
+        public: &[CyclotomicRing<MOD_Q, N>],
+    ) {
+        transcript.append_commitment(b"ccs commitment", commitment);
+        transcript.append_commitment(b"ccs public", public);
+    }
+
+    pub fn verify_linearization(
+        &self,
+        transcript: &mut Transcript,
+        commitment: &[CyclotomicRing<MOD_Q, N>],
+        public: &[CyclotomicRing<MOD_Q, N>],
+        proof: &LinearizationProof<MOD_Q, N>,
+    ) -> Result<LinearizedInstance<MOD_Q, N>, FoldingError> {
+        let claims = self.ccs.matrices.len() + 1;
+        if public.len() != self.ccs.num_public || commitment.len() != self.key.kappa() || proof.evaluations.len() != claims {
+            return Err(FoldingError::MalformedProof);
+        }
+        self.absorb_ccs_instance(transcript, commitment, public);
+        let beta: Vec<u64> = (0..self.num_variables).map(|_| transcript.challenge_u64(b"lin beta", MOD_Q)).collect();
+        let degree = self.ccs.degree() + 1;
+        let subclaim = verify(RingElement::zero(), &proof.sumcheck, self.num_variables, degree, sumcheck_challenges(transcript))?;
+        let point = to_scalars(&subclaim.point);
+        let eq_beta = eq(&to_ring_point::<MOD_Q, N>(&beta), &subclaim.point);
+        if eq_beta * self.constraint_polynomial(&proof.evaluations[..claims - 1]) != subclaim.value {
+            return Err(FoldingError::LinearizationMismatch);
+        }
+        transcript.append_commitment(b"lin evaluations", &proof.evaluations);
+        let z_public = std::iter::once(RingElement::one()).chain(public.iter().copied()).collect();
+        Ok(LinearizedInstance { commitment: commitment.to_vec(), public: z_public, point, evaluations: proof.evaluations.clone() })
+    }
+
+    /// Π_dec, prover side: the k digit instances and witnesses.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn decompose(
+        &self,
+        transcript: &mut Transcript,
+        instance: &LinearizedInstance<MOD_Q, N>,
+        witness: &[CyclotomicRing<MOD_Q, N>],
+    ) -> Result<(Vec<LinearizedInstance<MOD_Q, N>>, Vec<Vec<CyclotomicRing<MOD_Q, N>>>, DecompositionProof<MOD_Q, N>), FoldingError>
+    {
+        let (digit_witnesses, commitments) = self.params.decomposition().commit(&self.key, witness)?;
+        let evaluations: Vec<_> = digit_witnesses
+            .iter()
+            .enumerate()
+            .map(|(i, w)| self.evaluate(&self.digit_public(&instance.public, i), w, &instance.point))
+            .collect();
+        let proof = DecompositionProof { commitments, evaluations };
+        let instances = self.verify_decomposition(transcript, instance, &proof)?;
+        Ok((instances, digit_witnesses, proof))
+    }
+
+    /// x goes with the first digit vector, so that Σ b^i·z_i = z.
+    fn digit_public(&self, public: &[CyclotomicRing<MOD_Q, N>], digit: usize) -> Vec<CyclotomicRing<MOD_Q, N>> {
+        if digit == 0 {
+            public.to_vec()
+        } else {
+            vec![RingElement::zero(); public.len()]
+        }
+    }
+
+    /// Π_dec, verifier side: checks Σ b^i·cm_i = cm and Σ b^i·v_i = v.
+    pub(crate) fn verify_decomposition(
+        &self,
+        transcript: &mut Transcript,
+        instance: &LinearizedInstance<MOD_Q, N>,
+        proof: &DecompositionProof<MOD_Q, N>,
+    ) -> Result<Vec<LinearizedInstance<MOD_Q, N>>, FoldingError> {
+        let decomposition = self.params.decomposition();
+        decomposition.check_shape(&instance.commitment, &instance.evaluations, proof)?;
+        for (commitment, evaluations) in proof.commitments.iter().zip(&proof.evaluations) {
+            transcript.append_commitment(b"digit commitment", commitment);
+            transcript.append_commitment(b"digit evaluations", evaluations);
+        }
+        decomposition.verify(&instance.commitment, &instance.evaluations, proof)?;
+        Ok((0..decomposition.digits)
+            .map(|i| LinearizedInstance {
+                commitment: proof.commitments[i].clone(),
+                public: self.digit_public(&instance.public, i),
+                point: instance.point.clone(),
+                evaluations: proof.evaluations[i].clone(),
+            })
+            .collect())
+    }
+
+    pub(crate) fn check_shape(&self, instance: &LinearizedInstance<MOD_Q, N>) -> Result<(), FoldingError> {
+        if instance.evaluations.len() != self.ccs.matrices.len() + 1
+            || instance.point.len() != self.num_variables
+            || instance.public.len() != 1 + self.ccs.num_public
+            || instance.commitment.len() != self.key.kappa()
+        {
+            return Err(FoldingError::MalformedProof);
+        }
+        Ok(())
+    }
+
+    /// Folds the instances with short challenges.
+    pub(crate) fn combine_instances(
+        &self,
+        transcript: &mut Transcript,
+        instances: &[LinearizedInstance<MOD_Q, N>],
+        point: Vec<u64>,
+        evaluations: &[Vec<CyclotomicRing<MOD_Q, N>>],
+    ) -> (LinearizedInstance<MOD_Q, N>, Vec<CyclotomicRing<MOD_Q, N>>) {
+        let rho: Vec<CyclotomicRing<MOD_Q, N>> =
+            instances.iter().map(|_| transcript.challenge_short_ring(b"fold rho", &self.params.challenge_set)).collect();
+        let commitments: Vec<&[_]> = instances.iter().map(|u| u.commitment.as_slice()).collect();
+        let publics: Vec<&[_]> = instances.iter().map(|u| u.public.as_slice()).collect();
+        let evaluations: Vec<&[_]> = evaluations.iter().map(Vec::as_slice).collect();
+        let folded = LinearizedInstance {
+            commitment: linear_combination(&commitments, &rho),
+            public: linear_combination(&publics, &rho),
+            point,
+            evaluations: linear_combination(&evaluations, &rho),
+        };
+        transcript.append_commitment(b"folded commitment", &folded.commitment);
+        (folded, rho)
+    }
+
+    /// Folds the CCS instance (`commitment`, `public`) with `witness` into
+    /// the accumulator. Returns the new accumulator, its witness and the
+    /// proof.
+    #[allow(clippy::type_complexity, clippy::too_many_arguments)]
+    pub fn prove(
+        &self,
+        transcript: &mut Transcript,
+        accumulator: &LinearizedInstance<MOD_Q, N>,
+        accumulator_witness: &[CyclotomicRing<MOD_Q, N>],
+        commitment: &[CyclotomicRing<MOD_Q, N>],
+        public: &[CyclotomicRing<MOD_Q, N>],
+        witness: &[CyclotomicRing<MOD_Q, N>],
+    ) -> Result<(LinearizedInstance<MOD_Q, N>, Vec<CyclotomicRing<MOD_Q, N>>, FoldingProof<MOD_Q, N>), FoldingError> {
+        absorb_instance(transcript, accumulator);
+        let (fresh, linearization) = self.linearize(transcript, commitment, public, witness)?;
+        let (mut instances, mut witnesses, accumulator_digits) =
+            self.decompose(transcript, accumulator, accumulator_witness)?;
+        let (fresh_instances, fresh_witnesses, fresh_digits) = self.decompose(transcript, &fresh, witness)?;
+        instances.extend(fresh_instances);
+        witnesses.extend(fresh_witnesses);
+
+        // Π_fold
+        let claims = accumulator.evaluations.len();
+        let challenges = FoldChallenges::draw(transcript, &self.params, self.num_variables, claims);
+        let mut tables = vec![eq_table(&to_ring_point::<MOD_Q, N>(&challenges.beta))];
+        tables.extend(instances.iter().map(|u| eq_table(&to_ring_point::<MOD_Q, N>(&u.point))));
+        for w in &witnesses {
+            let mut slots: Vec<_> = w.iter().map(coefficients_in_slots).collect();
+            slots.resize(1 << self.num_variables, RingElement::zero());
+            tables.push(slots);
+        }
+        for (u, w) in instances.iter().zip(&witnesses) {
+            tables.extend(self.evaluation_tables(&u.public, w));
+        }
+        let degree = self.params.digit_range().count() + 1;
+        let mut prover = SumcheckProver::new(tables, degree, |f| challenges.combine(f));
+        let (sumcheck, point) = prover.prove(sumcheck_challenges(transcript));
+        let offset = 1 + 2 * instances.len();
+        let evaluations: Vec<Vec<_>> = prover.final_evaluations()[offset..].chunks(claims).map(<[_]>::to_vec).collect();
+        for e in &evaluations {
+            transcript.append_commitment(b"fold evaluations", e);
+        }
+
+        let (folded, rho) = self.combine_instances(transcript, &instances, to_scalars(&point), &evaluations);
+        let witness_refs: Vec<&[_]> = witnesses.iter().map(Vec::as_slice).collect();
+        let folded_witness = linear_combination(&witness_refs, &rho);
+        let proof = FoldingProof {
+            linearization,
+            decompositions: [accumulator_digits, fresh_digits],
+            sumcheck,
+            evaluations,
+        };
+        Ok((folded, folded_witness, proof))
+    }
+
+    /// Replays [`LatticeFold::prove`] and returns the new accumulator.
+    pub fn verify(
+        &self,
+        transcript: &mut Transcript,
+        accumulator: &LinearizedInstance<MOD_Q, N>,
+        commitment: &[CyclotomicRing<MOD_Q, N>],
+        public: &[CyclotomicRing<MOD_Q, N>],
+        proof: &FoldingProof<MOD_Q, N>,
+    ) -> Result<LinearizedInstance<MOD_Q, N>, FoldingError> {
+        let claims = self.ccs.matrices.len() + 1;
+        self.check_shape(accumulator)?;
+        absorb_instance(transcript, accumulator);
+        let fresh = self.verify_linearization(transcript, commitment, public, &proof.linearization)?;
+        let mut instances = self.verify_decomposition(transcript, accumulator, &proof.decompositions[0])?;
+        instances.extend(self.verify_decomposition(transcript, &fresh, &proof.decompositions[1])?);
+
+        // Π_fold
+        let challenges = FoldChallenges::draw(transcript, &self.params, self.num_variables, claims);
+        if proof.evaluations.len() != instances.len() || proof.evaluations.iter().any(|e| e.len() != claims) {
+            return Err(FoldingError::MalformedProof);
+        }
+        let claimed_sum = instances.iter().zip(&challenges.mu).fold(RingElement::zero(), |sum, (u, &mu)| {
+            let claim =
+                u.evaluations.iter().zip(&challenges.gamma).fold(RingElement::zero(), |sum, (&v, &g)| sum + g * v);
+            sum + mu * claim
+        });
+        let degree = self.params.digit_range().count() + 1;
+        let subclaim = verify(claimed_sum, &proof.sumcheck, self.num_variables, degree, sumcheck_challenges(transcript))?;
+
+        let mut values = vec![eq(&to_ring_point(&challenges.beta), &subclaim.point)];
+        values.extend(instances.iter().map(|u| eq(&to_ring_point(&u.point), &subclaim.point)));
+        values.extend(proof.evaluations.iter().map(|e| coefficients_in_slots(&e[claims - 1])));
+        values.extend(proof.evaluations.iter().flatten().copied());
+        if challenges.combine(&values) != subclaim.value {
+            return Err(FoldingError::FoldingMismatch);
+        }
+        for e in &proof.evaluations {
+            transcript.append_commitment(b"fold evaluations", e);
+        }
+        let (folded, _) = self.combine_instances(transcript, &instances, to_scalars(&subclaim.point), &proof.evaluations);
+        Ok(folded)
+    }
+
+    /// Checks the linearized relation directly: the commitment, every
+    /// evaluation claim and that `witness` can be decomposed again.
+    pub fn check(&self, instance: &LinearizedInstance<MOD_Q, N>, witness: &[CyclotomicRing<MOD_Q, N>]) -> Result<(), FoldingError> {
+        if witness.len() != self.ccs.num_witness() || instance.point.len() != self.num_variables {
+            return Err(FoldingError::MalformedProof);
+        }
+        self.params.decomposition().bound().check(witness)?;
+        if self.commit(witness) != instance.commitment {
+            return Err(FoldingError::CommitmentMismatch);
+        }
+        if self.evaluate(&instance.public, witness, &instance.point) != instance.evaluations {
+            return Err(FoldingError::EvaluationMismatch);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ccs::{R1cs, SparseMatrix};
+    use crate::challenge::operator_norm_bound;
+    use crate::decomposition::max_norm;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    const MOD_Q: u64 = 1125899904679937;
+    const N: usize = 64;
+    const PAIRS: usize = 5;
+
+    type Ring = CyclotomicRing<MOD_Q, N>;
+
+    /// w_(2i)·w_(2i+1) = y_i for i < PAIRS, and the public x = y_0, over
+    /// z = (1, x, w_0, ..., w_(2·PAIRS-1), y_0, ...).
+    fn pairs_ccs() -> Ccs<MOD_Q, N> {
+        let cols = 2 + 3 * PAIRS;
+        let (mut a, mut b, mut c) =
+            (SparseMatrix::new(PAIRS + 1, cols), SparseMatrix::new(PAIRS + 1, cols), SparseMatrix::new(PAIRS + 1, cols));
+        for i in 0..PAIRS {
+            a.push_scalar(i, 2 + 2 * i, 1);
+            b.push_scalar(i, 3 + 2 * i, 1);
+            c.push_scalar(i, 2 + 2 * PAIRS + i, 1);
+        }
+        a.push_scalar(PAIRS, 0, 1);
+        b.push_scalar(PAIRS, 1, 1);
+        c.push_scalar(PAIRS, 2 + 2 * PAIRS, 1);
+        R1cs { num_public: 1, a, b, c }.into()
+    }
+
+    fn assignment(rng: &mut StdRng) -> (Vec<Ring>, Vec<Ring>) {
+        let mut witness: Vec<Ring> = (0..2 * PAIRS)
+            .map(|_| {
+                let mut w = Ring::new();
+                w.data.iter_mut().for_each(|c| *c = [MOD_Q - 1, 0, 1][rng.random_range(0..3)]);
+                w
+            })
+            .collect();
+        let products: Vec<Ring> = witness.chunks(2).map(|pair| pair[0] * pair[1]).collect();
+        witness.extend(products);
+        (vec![witness[2 * PAIRS]], witness)
+    }
+
+    #[test]
+    fn test_challenge_l1_norm() {
+        for set in [ChallengeSet::FixedWeightTernary { weight: 20 }, ChallengeSet::LABRADOR_64] {
+            for _ in 0..20 {
+                let c = set.sample::<MOD_Q, N>();
+                assert!(operator_norm_bound(&c) <= challenge_l1_norm(&set));
+            }
+        }
+    }
+
+    #[test]
+    fn test_fold_two_ccs_instances() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let lf = LatticeFold::new(pairs_ccs(), FoldingParams::DEFAULT_64, b"test key");
+        let mut prover_transcript = Transcript::new(b"latticefold test");
+        let mut verifier_transcript = Transcript::new(b"latticefold test");
+
+        let (public, witness) = assignment(&mut rng);
+        let commitment = lf.commit(&witness);
+        let (mut accumulator, proof) = lf.linearize(&mut prover_transcript, &commitment, &public, &witness).unwrap();
+        let verified = lf.verify_linearization(&mut verifier_transcript, &commitment, &public, &proof).unwrap();
+        assert_eq!(verified, accumulator);
+        assert_eq!(lf.check(&accumulator, &witness), Ok(()));
+
+        let mut accumulator_witness = witness;
+        for _ in 0..2 {
+            let (public, witness) = assignment(&mut rng);
+            assert_eq!(lf.ccs.check(&public, &witness), Ok(()));
+            let commitment = lf.commit(&witness);
+            let (folded, folded_witness, proof) = lf
+                .prove(&mut prover_transcript, &accumulator, &accumulator_witness, &commitment, &public, &witness)
+                .unwrap();
+            let verified = lf.verify(&mut verifier_transcript, &accumulator, &commitment, &public, &proof).unwrap();
+            assert_eq!(verified, folded);
+            assert_eq!(lf.check(&folded, &folded_witness), Ok(()));
+            assert!(max_norm(&folded_witness) <= lf.params.norm_bound());
+            accumulator = folded;
+            accumulator_witness = folded_witness;
+        }
+    }
+
+    #[test]
+    fn test_rejects_bad_folds() {
+        let mut rng = StdRng::seed_from_u64(6);
+        let lf = LatticeFold::new(pairs_ccs(), FoldingParams::DEFAULT_64, b"test key");
+        let (public, accumulator_witness) = assignment(&mut rng);
+        let commitment = lf.commit(&accumulator_witness);
+        let (accumulator, _) = lf.linearize(&mut Transcript::new(b"t"), &commitment, &public, &accumulator_witness).unwrap();
+
+        let (public, mut witness) = assignment(&mut rng);
+        let commitment = lf.commit(&witness);
+        let (_, _, proof) = lf
+            .prove(&mut Transcript::new(b"t"), &accumulator, &accumulator_witness, &commitment, &public, &witness)
+            .unwrap();
+        let verify =
+            |proof: &FoldingProof<MOD_Q, N>| lf.verify(&mut Transcript::new(b"t"), &accumulator, &commitment, &public, proof);
+        assert!(verify(&proof).is_ok());
+
+        let mut tampered = proof.clone();
+        tampered.linearization.evaluations[0] = tampered.linearization.evaluations[0] + Ring::one();
+        assert!(verify(&tampered).is_err());
+        let mut tampered = proof.clone();
+        tampered.decompositions[1].commitments[0][0] = tampered.decompositions[1].commitments[0][0] + Ring::one();
+        assert_eq!(verify(&tampered), Err(FoldingError::DecompositionMismatch));
+        let mut tampered = proof.clone();
+        tampered.decompositions[0].evaluations[2].pop();
+        assert_eq!(verify(&tampered), Err(FoldingError::MalformedProof));
+        let mut tampered = proof.clone();
+        tampered.evaluations[3][0] = tampered.evaluations[3][0] + Ring::one();
+        assert_eq!(verify(&tampered), Err(FoldingError::FoldingMismatch));
+        tampered.evaluations.pop();
+        assert_eq!(verify(&tampered), Err(FoldingError::MalformedProof));
+
+        // The prover refuses witnesses that do not satisfy the CCS.
+        witness[0] = witness[0] + Ring::one();
+        assert_eq!(
+            lf.linearize(&mut Transcript::new(b"t"), &commitment, &public, &witness).map(|_| ()),
+            Err(FoldingError::Ccs(CcsError::Unsatisfied { row: 0 }))
+        );
+    }
+}
@@ -0,0 +1,865 @@
+//! A LatticeFold+ step (Boneh–Chen, 2025): the accumulator, Π_lin and Π_dec
+//! are those of [`crate::latticefold`], but the digits are range checked
+//! algebraically instead of with a degree-b sumcheck.
+//!
+//! For |a| < N/2 let exp(a) be X^a for a > 0, -X^a = X^(N+a) for a < 0 and
+//! 0 for a = 0, and ψ = Σ_(0<i<N/2) i·(X^i + X^(-i)). Then the constant
+//! term ct(ψ·exp(a)) is a, so a witness w ∈ R_q^n has ‖w‖∞ < N/2 iff there
+//! is a matrix M ∈ {0, 1, X, ..., X^(N-1)}^(n×N) with ct(ψ·M[b][j]) equal
+//! to coefficient j of w_b. Per digit witness the step runs
+//!
+//! 1. Monomial commitments: the columns M_j of M are committed to under
+//!    the witness key, cm_j = A·M_j. Multiplying by a monomial is a
+//!    negacyclic rotation, so this needs additions only. The inner
+//!    commitments are split into gadget digits τ and committed to again
+//!    under the same key, and only this double commitment A·τ is sent
+//!    ([`DoubleCommitmentKey::sharing_key`]).
+//! 2. Π_mon: b ∈ R_q is 0 or a monomial iff b(Y)² = b(Y²) as polynomials
+//!    of degree < 2N. One sumcheck over F_q at a random Y = c checks this
+//!    for every entry of every M, and leaves evaluations e_j of the columns
+//!    of M at a point r.
+//! 3. Range check: the prover evaluates the digit witness at r, and the
+//!    verifier checks ct(ψ·e_j) against coefficient j. Both sides are
+//!    Z_q-linear in the rows, so this is the lookup of every digit at once.
+//! 4. Π_cm: short challenges ρ_j bind every claim (cm_j, r, e_j) at once.
+//!    The prover sends c = Σ_j ρ_j·cm_j, and the combined column
+//!    h = Σ_j ρ_j·M_j is an instance with commitment c and evaluation
+//!    Σ_j ρ_j·e_j at r. An entry of h is a sum of N rotated challenges, so
+//!    ‖h‖∞ ≤ N·‖ρ‖∞. Since cm_j is G·τ_j for the gadget matrix G, c is a
+//!    linear function of τ; random ζ ∈ Z_q^κ turn it into Σ_i ζ_i·c_i =
+//!    ⟨ℓ, τ⟩, and a sumcheck reduces that to an evaluation of τ, a third
+//!    instance with commitment A·τ and ‖τ‖∞ ≤ gadget_base/2.
+//! 5. Π_fold: one sumcheck of degree 2 moves all claims of the 2k digit,
+//!    2k combined and 2k opening instances to a common point, and short
+//!    challenges combine them into the next accumulator.
+//!
+//! τ has κ·d·N elements for d gadget digits, so witnesses are padded with
+//! unconstrained zeros to at least that length. Digits must lie below N/2,
+//! so the base can be much larger than in LatticeFold, and the sumcheck
+//! degree does not depend on it.
+
+use crate::algebra::RingElement;
+use crate::ccs::Ccs;
+use crate::challenge::ChallengeSet;
+use crate::commitment::{linear_combination, DoubleCommitmentKey};
+use crate::cyclotomic_ring::CyclotomicRing;
+use crate::decomposition::{balanced_digit_bound, DecompositionProof, NormBound};
+use crate::ext_field::ExtField;
+use crate::latticefold::{
+    absorb_instance, challenge_scalar, sumcheck_challenges, to_ring_point, to_scalars, FoldingError, FoldingParams,
+    LatticeFold, LinearizationProof, LinearizedInstance,
+};
+use crate::mle::{eq, eq_table, eq_table_fq};
+use crate::modular::{add_mod, mul_mod, sub_mod};
+use crate::sumcheck::{verify, RoundPolynomial, SumcheckProof, SumcheckProver};
+use crate::transcript::Transcript;
+
+type Fq<const MOD_Q: u64> = ExtField<MOD_Q, 1>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LatticeFoldPlusParams {
+    pub folding: FoldingParams,
+    /// Base of the gadget decomposition inside double commitments.
+    pub gadget_base: u64,
+}
+
+impl LatticeFoldPlusParams {
+    /// N = 64: b = 32, k = 3, ternary challenges of weight 20, κ = 4 and
+    /// gadget base 32.
+    pub const DEFAULT_64: LatticeFoldPlusParams = LatticeFoldPlusParams {
+        folding: FoldingParams {
+            kappa: 4,
+            base: 32,
+            digits: 3,
+            challenge_set: ChallengeSet::FixedWeightTernary { weight: 20 },
+        },
+        gadget_base: 32,
+    };
+
+    /// Gadget digits needed for every element of Z_q.
+    pub fn gadget_digits(&self, modulus: u64) -> usize {
+        (1..).find(|&digits| balanced_digit_bound(self.gadget_base, digits) >= (modulus / 2) as u128).unwrap()
+    }
+
+    /// Length of the opening of a double commitment to N columns: κ·d·N.
+    pub fn opening_len(&self, modulus: u64, n: usize) -> usize {
+        self.folding.kappa * self.gadget_digits(modulus) * n
+    }
+
+    /// ‖w‖∞ of a folded witness: 2k digit vectors proven below N/2, 2k
+    /// combined columns of norm at most N·‖ρ‖∞ and 2k openings of gadget
+    /// digits, each multiplied by a challenge.
+    pub fn norm_bound(&self, n: usize) -> u64 {
+        let arity = self.folding.arity();
+        let digits = std::iter::repeat_n(NormBound(n as u64 / 2 - 1), arity);
+        let combined = std::iter::repeat_n(NormBound(n as u64 * self.folding.challenge_set.infinity_norm()), arity);
+        let openings = std::iter::repeat_n(NormBound(self.gadget_base / 2), arity);
+        NormBound::fold(digits.chain(combined).chain(openings), &self.folding.challenge_set).0
+    }
+
+    /// Whether honest digits have monomial encodings and folded witnesses
+    /// can be decomposed again.
+    pub fn is_valid(&self, n: usize) -> bool {
+        let folding = &self.folding;
+        folding.base >= 3
+            && folding.base / 2 < n as u64 / 2
+            && self.gadget_base >= 3
+            && self.norm_bound(n) <= folding.decomposition_bound()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LatticeFoldPlusProof<const MOD_Q: u64, const N: usize> {
+    pub linearization: LinearizationProof<MOD_Q, N>,
+    pub decompositions: [DecompositionProof<MOD_Q, N>; 2],
+    pub range: RangeProof<MOD_Q, N>,
+}
+
+/// Range checks the 2k digit instances and folds them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeProof<const MOD_Q: u64, const N: usize> {
+    /// One per digit witness.
+    pub double_commitments: Vec<Vec<CyclotomicRing<MOD_Q, N>>>,
+    pub monomial_sumcheck: SumcheckProof<Fq<MOD_Q>>,
+    /// e_j per digit witness.
+    pub monomial_evaluations: Vec<Vec<CyclotomicRing<MOD_Q, N>>>,
+    /// The digit instances' evaluations at the Π_mon point.
+    pub range_evaluations: Vec<Vec<CyclotomicRing<MOD_Q, N>>>,
+    /// Σ_j ρ_j·cm_j per digit witness.
+    pub combined_commitments: Vec<Vec<CyclotomicRing<MOD_Q, N>>>,
+    /// The combined instances' evaluations at the Π_mon point.
+    pub combined_evaluations: Vec<Vec<CyclotomicRing<MOD_Q, N>>>,
+    /// Reduces the combined commitments to evaluations of the openings.
+    pub opening_sumcheck: SumcheckProof<CyclotomicRing<MOD_Q, N>>,
+    /// The opening instances' evaluations at the Π_cm point.
+    pub opening_evaluations: Vec<Vec<CyclotomicRing<MOD_Q, N>>>,
+    pub sumcheck: SumcheckProof<CyclotomicRing<MOD_Q, N>>,
+    /// Every instance's evaluations at the final point.
+    pub evaluations: Vec<Vec<CyclotomicRing<MOD_Q, N>>>,
+}
+
+/// exp(a) as the exponent of X, `None` for 0. Only |a| < N/2 decodes back
+/// to a; larger values still give a monomial, which the range check
+/// rejects.
+fn exponent<const N: usize>(a: i64) -> Option<usize> {
+    match a {
+        0 => None,
+        a => Some(a.rem_euclid(N as i64) as usize),
+    }
+}
+
+/// The columns of the monomial matrix of `witness`, padded to `len` rows.
+fn monomial_columns<const MOD_Q: u64, const N: usize>(
+    witness: &[CyclotomicRing<MOD_Q, N>],
+    len: usize,
+) -> Vec<Vec<Option<usize>>> {
+    let mut columns = vec![vec![None; len]; N];
+    for (b, w) in witness.iter().enumerate() {
+        for (j, c) in w.centered_coefficients().into_iter().enumerate() {
+            columns[j][b] = exponent::<N>(c);
+        }
+    }
+    columns
+}
+
+/// ψ = Σ_(0<i<N/2) i·(X^i - X^(N-i)).
+fn psi<const MOD_Q: u64, const N: usize>() -> CyclotomicRing<MOD_Q, N> {
+    let mut result = CyclotomicRing::new();
+    for i in 1..N / 2 {
+        result.data[i] = i as u64;
+        result.data[N - i] = MOD_Q - i as u64;
+    }
+    result
+}
+
+fn powers(c: u64, count: usize, modulus: u64) -> Vec<u64> {
+    std::iter::successors(Some(1 % modulus), |&p| Some(mul_mod(p, c, modulus))).take(count).collect()
+}
+
+/// The coefficient polynomial of `element` at a point of Z_q, given its
+/// powers.
+fn evaluate_at<const MOD_Q: u64, const N: usize>(element: &CyclotomicRing<MOD_Q, N>, powers: &[u64]) -> u64 {
+    element.canonical_coefficients().iter().zip(powers).fold(0, |sum, (&e, &p)| add_mod(sum, mul_mod(e, p, MOD_Q), MOD_Q))
+}
+
+/// Σ_b eq_b·X^(column_b) for an eq table over Z_q.
+fn evaluate_monomials<const MOD_Q: u64, const N: usize>(column: &[Option<usize>], eq: &[u64]) -> CyclotomicRing<MOD_Q, N> {
+    let mut result = CyclotomicRing::new();
+    for (&e, &weight) in column.iter().zip(eq) {
+        if let Some(e) = e {
+            result.data[e] = add_mod(result.data[e], weight, MOD_Q);
+        }
+    }
+    result
+}
+
+/// Absorbs each round polynomial and answers with a challenge in F_q.
+fn field_challenges<const MOD_Q: u64>(
+    transcript: &mut Transcript,
+) -> impl FnMut(&RoundPolynomial<Fq<MOD_Q>>) -> Fq<MOD_Q> + '_ {
+    move |round| {
+        for evaluation in &round.evaluations {
+            transcript.append_field(b"round polynomial", evaluation);
+        }
+        transcript.challenge_field(b"sumcheck challenge")
+    }
+}
+
+fn absorb_all<const MOD_Q: u64, const N: usize>(
+    transcript: &mut Transcript,
+    label: &[u8],
+    vectors: &[Vec<CyclotomicRing<MOD_Q, N>>],
+) {
+    for vector in vectors {
+        transcript.append_commitment(label, vector);
+    }
+}
+
+/// Challenges of Π_mon: Σ_b eq(β, b)·Σ_(i,j) γ_(i,j)·(M_i[b][j](c)² - M_i[b][j](c²)).
+struct MonomialChallenges<const MOD_Q: u64> {
+    beta: Vec<u64>,
+    gamma: Vec<Fq<MOD_Q>>,
+    c: u64,
+}
+
+impl<const MOD_Q: u64> MonomialChallenges<MOD_Q> {
+    fn draw(transcript: &mut Transcript, num_variables: usize, columns: usize) -> Self {
+        let beta = (0..num_variables).map(|_| transcript.challenge_u64(b"mon beta", MOD_Q)).collect();
+        let mut rng = transcript.challenge_rng(b"mon gamma");
+        let gamma = (0..columns).map(|_| Fq::from_base(rng.uniform_below(MOD_Q))).collect();
+        let c = transcript.challenge_u64(b"mon point", MOD_Q);
+        Self { beta, gamma, c }
+    }
+
+    /// On the tables eq(β), then M(c) and M(c²) for every column.
+    fn combine(&self, values: &[Fq<MOD_Q>]) -> Fq<MOD_Q> {
+        let sum = values[1..].chunks(2).zip(&self.gamma).fold(Fq::zero(), |sum, (pair, &g)| {
+            sum + g * (pair[0] * pair[0] - pair[1])
+        });
+        values[0] * sum
+    }
+}
+
+/// Evaluation claims of Π_fold: instance i claims its t + 1 evaluation
+/// tables at `points[p]` for every (p, values) in `claims[i]`.
+struct FoldClaims<const MOD_Q: u64, const N: usize> {
+    points: Vec<Vec<u64>>,
+    claims: Vec<Vec<(usize, Vec<CyclotomicRing<MOD_Q, N>>)>>,
+    mu: Vec<Vec<CyclotomicRing<MOD_Q, N>>>,
+    gamma: Vec<CyclotomicRing<MOD_Q, N>>,
+}
+
+impl<const MOD_Q: u64, const N: usize> FoldClaims<MOD_Q, N> {
+    fn new(
+        transcript: &mut Transcript,
+        points: Vec<Vec<u64>>,
+        claims: Vec<Vec<(usize, Vec<CyclotomicRing<MOD_Q, N>>)>>,
+        width: usize,
+    ) -> Self {
+        let mu = claims.iter().map(|c| c.iter().map(|_| challenge_scalar(transcript, b"fold mu")).collect()).collect();
+        let gamma = (0..width).map(|_| challenge_scalar(transcript, b"fold gamma")).collect();
+        Self { points, claims, mu, gamma }
+    }
+
+    fn claimed_sum(&self) -> CyclotomicRing<MOD_Q, N> {
+        self.claims.iter().zip(&self.mu).flat_map(|(c, mu)| c.iter().zip(mu)).fold(RingElement::zero(), |sum, ((_, v), &mu)| {
+            sum + mu * self.gamma.iter().zip(v).fold(RingElement::zero(), |s, (&g, &v)| s + g * v)
+        })
+    }
+
+    /// On the tables eq(point) for every point, then every instance's
+    /// evaluation tables.
+    fn combine(&self, values: &[CyclotomicRing<MOD_Q, N>]) -> CyclotomicRing<MOD_Q, N> {
+        let (eqs, tables) = values.split_at(self.points.len());
+        let width = self.gamma.len();
+        self.claims.iter().zip(&self.mu).enumerate().fold(RingElement::zero(), |sum, (i, (claims, mu))| {
+            let weight = claims.iter().zip(mu).fold(CyclotomicRing::new(), |w, (&(p, _), &mu)| w + mu * eqs[p]);
+            let combined = tables[i * width..(i + 1) * width]
+                .iter()
+                .zip(&self.gamma)
+                .fold(RingElement::zero(), |s, (&e, &g)| s + g * e);
+            sum + weight * combined
+        })
+    }
+}
+
+/// Proves and verifies LatticeFold+ steps for one CCS.
+#[derive(Clone, Debug)]
+pub struct LatticeFoldPlus<const MOD_Q: u64, const N: usize> {
+    /// LatticeFold over the CCS with its witness padded to hold an opening.
+    pub lf: LatticeFold<MOD_Q, N>,
+    pub params: LatticeFoldPlusParams,
+    /// Commits to the monomial matrices, with the witness key in both layers.
+    pub double_key: DoubleCommitmentKey<MOD_Q, N>,
+}
+
+impl<const MOD_Q: u64, const N: usize> LatticeFoldPlus<MOD_Q, N> {
+    pub fn new(ccs: Ccs<MOD_Q, N>, params: LatticeFoldPlusParams, seed: &[u8]) -> Self {
+        assert!(params.is_valid(N), "{params:?} does not fit degree {N}");
+        let witness_len = ccs.num_witness().max(params.opening_len(MOD_Q, N));
+        let lf = LatticeFold::new(ccs.pad_witness(witness_len), params.folding, seed);
+        let double_key = DoubleCommitmentKey::sharing_key(lf.key.clone(), N, params.gadget_base, params.gadget_digits(MOD_Q));
+        Self { lf, params, double_key }
+    }
+
+    /// `witness` followed by zeros up to the padded length.
+    fn pad(&self, witness: &[CyclotomicRing<MOD_Q, N>]) -> Vec<CyclotomicRing<MOD_Q, N>> {
+        let mut padded = witness.to_vec();
+        padded.resize(self.lf.key.witness_len(), RingElement::zero());
+        padded
+    }
+
+    pub fn commit(&self, witness: &[CyclotomicRing<MOD_Q, N>]) -> Vec<CyclotomicRing<MOD_Q, N>> {
+        self.lf.commit(&self.pad(witness))
+    }
+
+    /// Π_lin of [`LatticeFold::linearize`], which starts an accumulator.
+    pub fn linearize(
+        &self,
+        transcript: &mut Transcript,
+        commitment: &[CyclotomicRing<MOD_Q, N>],
+        public: &[CyclotomicRing<MOD_Q, N>],
+        witness: &[CyclotomicRing<MOD_Q, N>],
+    ) -> Result<(LinearizedInstance<MOD_Q, N>, LinearizationProof<MOD_Q, N>), FoldingError> {
+        self.lf.linearize(transcript, commitment, public, &self.pad(witness))
+    }
+
+    pub fn verify_linearization(
+        &self,
+        transcript: &mut Transcript,
+        commitment: &[CyclotomicRing<MOD_Q, N>],
+        public: &[CyclotomicRing<MOD_Q, N>],
+        proof: &LinearizationProof<MOD_Q, N>,
+    ) -> Result<LinearizedInstance<MOD_Q, N>, FoldingError> {
+        self.lf.verify_linearization(transcript, commitment, public, proof)
+    }
+
+    /// Short challenges ρ_j, N per digit witness.
+    fn column_challenges(&self, transcript: &mut Transcript, count: usize) -> Vec<Vec<CyclotomicRing<MOD_Q, N>>> {
+        let set = &self.params.folding.challenge_set;
+        (0..count).map(|_| (0..N).map(|_| transcript.challenge_short_ring(b"column rho", set)).collect()).collect()
+    }
+
+    /// ζ ∈ Z_q^κ per digit witness.
+    fn opening_challenges(&self, transcript: &mut Transcript, count: usize) -> Vec<Vec<u64>> {
+        let kappa = self.lf.key.kappa();
+        (0..count).map(|_| (0..kappa).map(|_| transcript.challenge_u64(b"opening zeta", MOD_Q)).collect()).collect()
+    }
+
+    /// The combined column h = Σ_j ρ_j·M[·][j]. Multiplying by a monomial
+    /// rotates ρ_j, so this needs additions only.
+    fn combined_witness(
+        &self,
+        columns: &[Vec<Option<usize>>],
+        rho: &[CyclotomicRing<MOD_Q, N>],
+    ) -> Vec<CyclotomicRing<MOD_Q, N>> {
+        let rho: Vec<_> = rho.iter().map(CyclotomicRing::canonical_coefficients).collect();
+        (0..self.lf.key.witness_len())
+            .map(|b| {
+                let mut h = CyclotomicRing::<MOD_Q, N>::new();
+                for (column, rho) in columns.iter().zip(&rho) {
+                    let Some(e) = column[b] else { continue };
+                    for (t, &x) in rho.iter().enumerate() {
+                        let position = (t + e) % N;
+                        h.data[position] =
+                            if t + e < N { add_mod(h.data[position], x, MOD_Q) } else { sub_mod(h.data[position], x, MOD_Q) };
+                    }
+                }
+                h
+            })
+            .collect()
+    }
+
+    /// ℓ with ⟨ℓ, τ⟩ = Σ_i ζ_i·(Σ_j ρ_j·cm_j)_i: ρ_j·ζ_i·B^t at the
+    /// position of digit t of row i of column j in the opening τ, for the
+    /// gadget base B.
+    fn opening_weights(&self, rho: &[CyclotomicRing<MOD_Q, N>], zeta: &[u64]) -> Vec<CyclotomicRing<MOD_Q, N>> {
+        let gadget = powers(self.params.gadget_base, self.double_key.gadget_digits, MOD_Q);
+        let mut weights = Vec::with_capacity(1 << self.lf.num_variables);
+        for rho in rho {
+            for &zeta in zeta {
+                weights.extend(gadget.iter().map(|&g| rho.scalar_mul(mul_mod(zeta, g, MOD_Q))));
+            }
+        }
+        weights.resize(1 << self.lf.num_variables, RingElement::zero());
+        weights
+    }
+
+    /// The multilinear extension of [`Self::opening_weights`] at `point`,
+    /// with N ring operations.
+    fn evaluate_opening_weights(
+        &self,
+        rho: &[CyclotomicRing<MOD_Q, N>],
+        zeta: &[u64],
+        point: &[u64],
+    ) -> CyclotomicRing<MOD_Q, N> {
+        let gadget = powers(self.params.gadget_base, self.double_key.gadget_digits, MOD_Q);
+        let eq_point = eq_table_fq(point, MOD_Q);
+        rho.iter().zip(eq_point.chunks(zeta.len() * gadget.len())).fold(RingElement::zero(), |sum, (rho, column)| {
+            let weight = column.chunks(gadget.len()).zip(zeta).fold(0, |weight, (row, &zeta)| {
+                let digits = row.iter().zip(&gadget).fold(0, |s, (&e, &g)| add_mod(s, mul_mod(e, g, MOD_Q), MOD_Q));
+                add_mod(weight, mul_mod(zeta, digits, MOD_Q), MOD_Q)
+            });
+            sum + rho.scalar_mul(weight)
+        })
+    }
+
+    fn zero_public(&self) -> Vec<CyclotomicRing<MOD_Q, N>> {
+        vec![RingElement::zero(); 1 + self.lf.ccs.num_public]
+    }
+
+    /// An instance of one of the witnesses Π_cm adds, with no public part.
+    fn added_instance(
+        &self,
+        commitment: &[CyclotomicRing<MOD_Q, N>],
+        point: &[u64],
+        evaluations: &[CyclotomicRing<MOD_Q, N>],
+    ) -> LinearizedInstance<MOD_Q, N> {
+        LinearizedInstance {
+            commitment: commitment.to_vec(),
+            public: self.zero_public(),
+            point: point.to_vec(),
+            evaluations: evaluations.to_vec(),
+        }
+    }
+
+    /// Points and claims of Π_fold: the digit instances at their own point
+    /// and at the Π_mon point, the combined instances at the latter and
+    /// the opening instances at the Π_cm point.
+    fn fold_claims(
+        transcript: &mut Transcript,
+        digit_instances: &[LinearizedInstance<MOD_Q, N>],
+        monomial_point: &[u64],
+        range_evaluations: &[Vec<CyclotomicRing<MOD_Q, N>>],
+        combined_evaluations: &[Vec<CyclotomicRing<MOD_Q, N>>],
+        opening_point: &[u64],
+        opening_evaluations: &[Vec<CyclotomicRing<MOD_Q, N>>],
+    ) -> FoldClaims<MOD_Q, N> {
+        let mut points: Vec<Vec<u64>> = Vec::new();
+        let mut index_of = |point: &[u64]| match points.iter().position(|p| p == point) {
+            Some(index) => index,
+            None => {
+                points.push(point.to_vec());
+                points.len() - 1
+            }
+        };
+        let mut claims: Vec<Vec<_>> = digit_instances
+            .iter()
+            .zip(range_evaluations)
+            .map(|(u, range)| vec![(index_of(&u.point), u.evaluations.clone()), (index_of(monomial_point), range.clone())])
+            .collect();
+        claims.extend(combined_evaluations.iter().map(|v| vec![(index_of(monomial_point), v.clone())]));
+        claims.extend(opening_evaluations.iter().map(|v| vec![(index_of(opening_point), v.clone())]));
+        let width = digit_instances[0].evaluations.len();
+        FoldClaims::new(transcript, points, claims, width)
+    }
+
+    /// Folds the CCS instance (`commitment`, `public`) with `witness` into
+    /// the accumulator, as [`LatticeFold::prove`] does.
+    #[allow(clippy::type_complexity)]
+    pub fn prove(
+        &self,
+        transcript: &mut Transcript,
+        accumulator: &LinearizedInstance<MOD_Q, N>,
+        accumulator_witness: &[CyclotomicRing<MOD_Q, N>],
+        commitment: &[CyclotomicRing<MOD_Q, N>],
+        public: &[CyclotomicRing<MOD_Q, N>],
+        witness: &[CyclotomicRing<MOD_Q, N>],
+    ) -> Result<(LinearizedInstance<MOD_Q, N>, Vec<CyclotomicRing<MOD_Q, N>>, LatticeFoldPlusProof<MOD_Q, N>), FoldingError>
+    {
+        let lf = &self.lf;
+        let witness = self.pad(witness);
+        absorb_instance(transcript, accumulator);
+        let (fresh, linearization) = lf.linearize(transcript, commitment, public, &witness)?;
+        let (mut instances, mut witnesses, accumulator_digits) =
+            lf.decompose(transcript, accumulator, &self.pad(accumulator_witness))?;
+        let (fresh_instances, fresh_witnesses, fresh_digits) = lf.decompose(transcript, &fresh, &witness)?;
+        instances.extend(fresh_instances);
+        witnesses.extend(fresh_witnesses);
+        let (folded, folded_witness, range) = self.prove_range(transcript, instances, witnesses);
+        Ok((folded, folded_witness, LatticeFoldPlusProof { linearization, decompositions: [accumulator_digits, fresh_digits], range }))
+    }
+
+    /// Range checks the digit instances and folds them: everything after
+    /// Π_dec.
+    #[allow(clippy::type_complexity)]
+    fn prove_range(
+        &self,
+        transcript: &mut Transcript,
+        mut instances: Vec<LinearizedInstance<MOD_Q, N>>,
+        mut witnesses: Vec<Vec<CyclotomicRing<MOD_Q, N>>>,
+    ) -> (LinearizedInstance<MOD_Q, N>, Vec<CyclotomicRing<MOD_Q, N>>, RangeProof<MOD_Q, N>) {
+        let lf = &self.lf;
+        let digit_count = witnesses.len();
+
+        // Monomial matrices and their double commitments.
+        let len = 1 << lf.num_variables;
+        let witness_len = lf.key.witness_len();
+        let columns: Vec<_> = witnesses.iter().map(|w| monomial_columns(w, len)).collect();
+        let inner_commitments: Vec<Vec<_>> =
+            columns.iter().map(|m| m.iter().map(|column| self.double_key.commit_monomials(&column[..witness_len])).collect()).collect();
+        let openings: Vec<_> = inner_commitments.iter().map(|inner| self.double_key.opening(inner)).collect();
+        let double_commitments: Vec<_> = openings.iter().map(|tau| lf.commit(tau)).collect();
+        absorb_all(transcript, b"double commitment", &double_commitments);
+
+        // Π_mon
+        let challenges = MonomialChallenges::<MOD_Q>::draw(transcript, lf.num_variables, columns.len() * N);
+        let (powers_c, powers_c2) = (powers(challenges.c, N, MOD_Q), powers(mul_mod(challenges.c, challenges.c, MOD_Q), N, MOD_Q));
+        let mut tables = vec![eq_table_fq(&challenges.beta, MOD_Q).into_iter().map(Fq::from_base).collect::<Vec<_>>()];
+        for column in columns.iter().flatten() {
+            for powers in [&powers_c, &powers_c2] {
+                tables.push(column.iter().map(|e| e.map_or(Fq::zero(), |e| Fq::from_base(powers[e]))).collect());
+            }
+        }
+        let mut prover = SumcheckProver::new(tables, 3, |f| challenges.combine(f));
+        let (monomial_sumcheck, point) = prover.prove(field_challenges(transcript));
+        let monomial_point: Vec<u64> = point.iter().map(|r| r.coefficients[0]).collect();
+        let eq_point = eq_table_fq(&monomial_point, MOD_Q);
+        let monomial_evaluations: Vec<Vec<_>> =
+            columns.iter().map(|m| m.iter().map(|column| evaluate_monomials(column, &eq_point)).collect()).collect();
+        absorb_all(transcript, b"monomial evaluations", &monomial_evaluations);
+
+        // Range check
+        let range_evaluations: Vec<_> =
+            instances.iter().zip(&witnesses).map(|(u, w)| lf.evaluate(&u.public, w, &monomial_point)).collect();
+        absorb_all(transcript, b"range evaluations", &range_evaluations);
+
+        // Π_cm: the combined columns, then their commitments as evaluations
+        // of the openings.
+        let rho = self.column_challenges(transcript, digit_count);
+        let combined_commitments: Vec<_> = inner_commitments
+            .iter()
+            .zip(&rho)
+            .map(|(inner, rho)| linear_combination(&inner.iter().map(Vec::as_slice).collect::<Vec<_>>(), rho))
+            .collect();
+        absorb_all(transcript, b"combined commitments", &combined_commitments);
+        let combined_witnesses: Vec<_> = columns.iter().zip(&rho).map(|(m, rho)| self.combined_witness(m, rho)).collect();
+        let combined_evaluations: Vec<_> =
+            combined_witnesses.iter().map(|h| lf.evaluate(&self.zero_public(), h, &monomial_point)).collect();
+        absorb_all(transcript, b"combined evaluations", &combined_evaluations);
+
+        let zeta = self.opening_challenges(transcript, digit_count);
+        let mut tables: Vec<_> = rho.iter().zip(&zeta).map(|(rho, zeta)| self.opening_weights(rho, zeta)).collect();
+        for tau in &openings {
+            let mut table = tau.clone();
+            table.resize(len, RingElement::zero());
+            tables.push(table);
+        }
+        let mut prover = SumcheckProver::new(tables, 2, |f| {
+            let (weights, openings) = f.split_at(digit_count);
+            weights.iter().zip(openings).fold(RingElement::zero(), |sum, (&l, &tau)| sum + l * tau)
+        });
+        let (opening_sumcheck, point) = prover.prove(sumcheck_challenges(transcript));
+        let opening_point = to_scalars(&point);
+        let opening_evaluations: Vec<_> =
+            openings.iter().map(|tau| lf.evaluate(&self.zero_public(), tau, &opening_point)).collect();
+        absorb_all(transcript, b"opening evaluations", &opening_evaluations);
+
+        // Π_fold
+        let claims = Self::fold_claims(
+            transcript,
+            &instances,
+            &monomial_point,
+            &range_evaluations,
+            &combined_evaluations,
+            &opening_point,
+            &opening_evaluations,
+        );
+        for (c, v) in combined_commitments.iter().zip(&combined_evaluations) {
+            instances.push(self.added_instance(c, &monomial_point, v));
+        }
+        for (d, v) in double_commitments.iter().zip(&opening_evaluations) {
+            instances.push(self.added_instance(d, &opening_point, v));
+        }
+        witnesses.extend(combined_witnesses);
+        witnesses.extend(openings);
+        let mut tables: Vec<_> = claims.points.iter().map(|p| eq_table(&to_ring_point::<MOD_Q, N>(p))).collect();
+        for (u, w) in instances.iter().zip(&witnesses) {
+            tables.extend(lf.evaluation_tables(&u.public, w));
+        }
+        let mut prover = SumcheckProver::new(tables, 2, |f| claims.combine(f));
+        let (sumcheck, point) = prover.prove(sumcheck_challenges(transcript));
+        let width = claims.gamma.len();
+        let evaluations: Vec<Vec<_>> =
+            prover.final_evaluations()[claims.points.len()..].chunks(width).map(<[_]>::to_vec).collect();
+        absorb_all(transcript, b"fold evaluations", &evaluations);
+
+        let (folded, rho) = lf.combine_instances(transcript, &instances, to_scalars(&point), &evaluations);
+        let witness_refs: Vec<&[_]> = witnesses.iter().map(Vec::as_slice).collect();
+        let folded_witness = linear_combination(&witness_refs, &rho);
+        let proof = RangeProof {
+            double_commitments,
+            monomial_sumcheck,
+            monomial_evaluations,
+            range_evaluations,
+            combined_commitments,
+            combined_evaluations,
+            opening_sumcheck,
+            opening_evaluations,
+            sumcheck,
+            evaluations,
+        };
+        (folded, folded_witness, proof)
+    }
+
+    /// Whether `proof` has the shape of a range proof for `digit_count`
+    /// digit instances.
+    fn check_range_shape(&self, proof: &RangeProof<MOD_Q, N>, digit_count: usize) -> Result<(), FoldingError> {
+        let kappa = self.lf.key.kappa();
+        let width = self.lf.ccs.matrices.len() + 1;
+        let all = |vectors: &[Vec<CyclotomicRing<MOD_Q, N>>], count: usize, len: usize| {
+            vectors.len() == count && vectors.iter().all(|v| v.len() == len)
+        };
+        let well_formed = all(&proof.double_commitments, digit_count, kappa)
+            && all(&proof.monomial_evaluations, digit_count, N)
+            && all(&proof.range_evaluations, digit_count, width)
+            && all(&proof.combined_commitments, digit_count, kappa)
+            && all(&proof.combined_evaluations, digit_count, width)
+            && all(&proof.opening_evaluations, digit_count, width)
+            && all(&proof.evaluations, 3 * digit_count, width);
+        if !well_formed {
+            return Err(FoldingError::MalformedProof);
+        }
+        Ok(())
+    }
+
+    /// Replays [`LatticeFoldPlus::prove`] and returns the new accumulator.
+    pub fn verify(
+        &self,
+        transcript: &mut Transcript,
+        accumulator: &LinearizedInstance<MOD_Q, N>,
+        commitment: &[CyclotomicRing<MOD_Q, N>],
+        public: &[CyclotomicRing<MOD_Q, N>],
+        proof: &LatticeFoldPlusProof<MOD_Q, N>,
+    ) -> Result<LinearizedInstance<MOD_Q, N>, FoldingError> {
+        let lf = &self.lf;
+        lf.check_shape(accumulator)?;
+        self.check_range_shape(&proof.range, self.params.folding.arity())?;
+
+        absorb_instance(transcript, accumulator);
+        let fresh = lf.verify_linearization(transcript, commitment, public, &proof.linearization)?;
+        let mut instances = lf.verify_decomposition(transcript, accumulator, &proof.decompositions[0])?;
+        instances.extend(lf.verify_decomposition(transcript, &fresh, &proof.decompositions[1])?);
+        self.verify_range(transcript, instances, &proof.range)
+    }
+
+    /// Replays [`LatticeFoldPlus::prove_range`] on a proof of checked shape.
+    fn verify_range(
+        &self,
+        transcript: &mut Transcript,
+        mut instances: Vec<LinearizedInstance<MOD_Q, N>>,
+        proof: &RangeProof<MOD_Q, N>,
+    ) -> Result<LinearizedInstance<MOD_Q, N>, FoldingError> {
+        let lf = &self.lf;
+        let width = lf.ccs.matrices.len() + 1;
+        let digit_count = instances.len();
+        absorb_all(transcript, b"double commitment", &proof.double_commitments);
+
+        // Π_mon
+        let challenges = MonomialChallenges::<MOD_Q>::draw(transcript, lf.num_variables, digit_count * N);
+        let subclaim = verify(Fq::zero(), &proof.monomial_sumcheck, lf.num_variables, 3, field_challenges(transcript))?;
+        let (powers_c, powers_c2) = (powers(challenges.c, N, MOD_Q), powers(mul_mod(challenges.c, challenges.c, MOD_Q), N, MOD_Q));
+        let mut values = vec![eq(&challenges.beta.iter().map(|&b| Fq::from_base(b)).collect::<Vec<_>>(), &subclaim.point)];
+        for e in proof.monomial_evaluations.iter().flatten() {
+            values.push(Fq::from_base(evaluate_at(e, &powers_c)));
+            values.push(Fq::from_base(evaluate_at(e, &powers_c2)));
+        }
+        if challenges.combine(&values) != subclaim.value {
+            return Err(FoldingError::MonomialMismatch);
+        }
+        let monomial_point: Vec<u64> = subclaim.point.iter().map(|r| r.coefficients[0]).collect();
+        absorb_all(transcript, b"monomial evaluations", &proof.monomial_evaluations);
+
+        // Range check
+        let psi = psi::<MOD_Q, N>();
+        for (e, v) in proof.monomial_evaluations.iter().zip(&proof.range_evaluations) {
+            let coefficients = v[width - 1].canonical_coefficients();
+            if e.iter().zip(coefficients).any(|(&e, c)| (psi * e).canonical_coefficients()[0] != c) {
+                return Err(FoldingError::RangeMismatch);
+            }
+        }
+        absorb_all(transcript, b"range evaluations", &proof.range_evaluations);
+
+        // Π_cm
+        let rho = self.column_challenges(transcript, digit_count);
+        absorb_all(transcript, b"combined commitments", &proof.combined_commitments);
+        for ((e, rho), v) in proof.monomial_evaluations.iter().zip(&rho).zip(&proof.combined_evaluations) {
+            if e.iter().zip(rho).fold(RingElement::zero(), |sum: CyclotomicRing<MOD_Q, N>, (&e, &rho)| sum + rho * e) != v[width - 1] {
+                return Err(FoldingError::CombinationMismatch);
+            }
+        }
+        absorb_all(transcript, b"combined evaluations", &proof.combined_evaluations);
+
+        let zeta = self.opening_challenges(transcript, digit_count);
+        let claimed_sum = proof.combined_commitments.iter().zip(&zeta).flat_map(|(c, zeta)| c.iter().zip(zeta)).fold(
+            RingElement::zero(),
+            |sum: CyclotomicRing<MOD_Q, N>, (c, &zeta)| sum + c.scalar_mul(zeta),
+        );
+        let subclaim = verify(claimed_sum, &proof.opening_sumcheck, lf.num_variables, 2, sumcheck_challenges(transcript))?;
+        let opening_point = to_scalars(&subclaim.point);
+        let expected = rho.iter().zip(&zeta).zip(&proof.opening_evaluations).fold(CyclotomicRing::new(), |sum, ((rho, zeta), v)| {
+            sum + self.evaluate_opening_weights(rho, zeta, &opening_point) * v[width - 1]
+        });
+        if expected != subclaim.value {
+            return Err(FoldingError::DoubleCommitmentMismatch);
+        }
+        absorb_all(transcript, b"opening evaluations", &proof.opening_evaluations);
+
+        // Π_fold
+        let claims = Self::fold_claims(
+            transcript,
+            &instances,
+            &monomial_point,
+            &proof.range_evaluations,
+            &proof.combined_evaluations,
+            &opening_point,
+            &proof.opening_evaluations,
+        );
+        for (c, v) in proof.combined_commitments.iter().zip(&proof.combined_evaluations) {
+            instances.push(self.added_instance(c, &monomial_point, v));
+        }
+        for (d, v) in proof.double_commitments.iter().zip(&proof.opening_evaluations) {
+            instances.push(self.added_instance(d, &opening_point, v));
+        }
+        let subclaim = verify(claims.claimed_sum(), &proof.sumcheck, lf.num_variables, 2, sumcheck_challenges(transcript))?;
+        let mut values: Vec<_> = claims.points.iter().map(|p| eq(&to_ring_point(p), &subclaim.point)).collect();
+        values.extend(proof.evaluations.iter().flatten().copied());
+        if claims.combine(&values) != subclaim.value {
+            return Err(FoldingError::FoldingMismatch);
+        }
+        absorb_all(transcript, b"fold evaluations", &proof.evaluations);
+        let (folded, _) = lf.combine_instances(transcript, &instances, to_scalars(&subclaim.point), &proof.evaluations);
+        Ok(folded)
+    }
+
+    /// [`LatticeFold::check`] on the shared accumulator relation.
+    pub fn check(&self, instance: &LinearizedInstance<MOD_Q, N>, witness: &[CyclotomicRing<MOD_Q, N>]) -> Result<(), FoldingError> {
+        self.lf.check(instance, &self.pad(witness))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ccs::{synthetic_ccs, SyntheticParams};
+    use crate::decomposition::max_norm;
+    use crate::algebra::PolynomialRing;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    const MOD_Q: u64 = 1125899904679937;
+    const N: usize = 64;
+
+    type Ring = CyclotomicRing<MOD_Q, N>;
+
+    fn monomial(exponent: usize) -> Ring {
+        let mut result = Ring::new();
+        result.data[exponent] = 1;
+        result
+    }
+
+    fn lattice_fold_plus(rng: &mut StdRng) -> (LatticeFoldPlus<MOD_Q, N>, Vec<Ring>, Vec<Ring>) {
+        let shape = SyntheticParams { num_constraints: 8, num_public: 1, num_witness: 7, row_weight: 3, degree: 2, witness_bound: 20 };
+        let (ccs, public, witness) = synthetic_ccs::<MOD_Q, N, _>(shape, rng);
+        (LatticeFoldPlus::new(ccs, LatticeFoldPlusParams::DEFAULT_64, b"test key"), public, witness)
+    }
+
+    #[test]
+    fn test_monomial_encoding() {
+        let psi = psi::<MOD_Q, N>();
+        for a in -31..32i64 {
+            let encoded = exponent::<N>(a).map_or(Ring::new(), monomial);
+            let expected = if a < 0 { MOD_Q - a.unsigned_abs() } else { a as u64 };
+            assert_eq!((psi * encoded).canonical_coefficients()[0], expected);
+        }
+    }
+
+    #[test]
+    fn test_fold_ccs_instances() {
+        let mut rng = StdRng::seed_from_u64(9);
+        let (lfp, public, witness) = lattice_fold_plus(&mut rng);
+        let mut prover_transcript = Transcript::new(b"latticefold+ test");
+        let mut verifier_transcript = Transcript::new(b"latticefold+ test");
+
+        let commitment = lfp.commit(&witness);
+        let (mut accumulator, proof) = lfp.linearize(&mut prover_transcript, &commitment, &public, &witness).unwrap();
+        assert_eq!(lfp.verify_linearization(&mut verifier_transcript, &commitment, &public, &proof), Ok(accumulator.clone()));
+        let mut accumulator_witness = witness.clone();
+
+        for _ in 0..2 {
+            let (folded, folded_witness, proof) = lfp
+                .prove(&mut prover_transcript, &accumulator, &accumulator_witness, &commitment, &public, &witness)
+                .unwrap();
+            let verified = lfp.verify(&mut verifier_transcript, &accumulator, &commitment, &public, &proof);
+            assert_eq!(verified, Ok(folded.clone()));
+            assert_eq!(lfp.check(&folded, &folded_witness), Ok(()));
+            assert!(max_norm(&folded_witness) <= lfp.params.norm_bound(N));
+            accumulator = folded;
+            accumulator_witness = folded_witness;
+        }
+
+        let (_, _, proof) = lfp
+            .prove(&mut Transcript::new(b"t"), &accumulator, &accumulator_witness, &commitment, &public, &witness)
+            .unwrap();
+        let verify = |proof: &LatticeFoldPlusProof<MOD_Q, N>| {
+            lfp.verify(&mut Transcript::new(b"t"), &accumulator, &commitment, &public, proof)
+        };
+        let width = lfp.lf.ccs.matrices.len() + 1;
+        assert!(verify(&proof).is_ok());
+        let mut tampered = proof.clone();
+        tampered.range.monomial_evaluations[1][5] = tampered.range.monomial_evaluations[1][5] + Ring::one();
+        assert_eq!(verify(&tampered), Err(FoldingError::MonomialMismatch));
+        let mut tampered = proof.clone();
+        tampered.range.range_evaluations[0][2] = tampered.range.range_evaluations[0][2] + Ring::one();
+        assert!(verify(&tampered).is_err());
+        let mut tampered = proof.clone();
+        tampered.range.combined_evaluations[3][width - 1] = tampered.range.combined_evaluations[3][width - 1] + Ring::one();
+        assert_eq!(verify(&tampered), Err(FoldingError::CombinationMismatch));
+        let mut tampered = proof.clone();
+        tampered.range.combined_commitments[2][0] = tampered.range.combined_commitments[2][0] + Ring::one();
+        assert!(verify(&tampered).is_err());
+        let mut tampered = proof.clone();
+        tampered.range.opening_evaluations[2][width - 1] = tampered.range.opening_evaluations[2][width - 1] + Ring::one();
+        assert_eq!(verify(&tampered), Err(FoldingError::DoubleCommitmentMismatch));
+        let mut tampered = proof.clone();
+        tampered.range.evaluations[4][0] = tampered.range.evaluations[4][0] + Ring::one();
+        assert_eq!(verify(&tampered), Err(FoldingError::FoldingMismatch));
+        let mut tampered = proof.clone();
+        tampered.range.combined_commitments.pop();
+        assert_eq!(verify(&tampered), Err(FoldingError::MalformedProof));
+    }
+
+    #[test]
+    fn test_reject_out_of_range_digits() {
+        let mut rng = StdRng::seed_from_u64(10);
+        let (lfp, _, _) = lattice_fold_plus(&mut rng);
+        let lf = &lfp.lf;
+        let zero_public = lfp.zero_public();
+        let point: Vec<u64> = (0..lf.num_variables).map(|_| rng.random_range(0..MOD_Q)).collect();
+        let mut witnesses: Vec<Vec<Ring>> = (0..lfp.params.folding.arity())
+            .map(|_| {
+                (0..lf.key.witness_len())
+                    .map(|_| Ring::from_centered_coefficients(std::array::from_fn(|_| rng.random_range(-15..16))))
+                    .collect()
+            })
+            .collect();
+        let prove_and_verify = |witnesses: &[Vec<Ring>]| {
+            let instances: Vec<_> = witnesses
+                .iter()
+                .map(|w| LinearizedInstance {
+                    commitment: lf.commit(w),
+                    public: zero_public.clone(),
+                    point: point.clone(),
+                    evaluations: lf.evaluate(&zero_public, w, &point),
+                })
+                .collect();
+            let (_, _, proof) = lfp.prove_range(&mut Transcript::new(b"range"), instances.clone(), witnesses.to_vec());
+            lfp.check_range_shape(&proof, instances.len())?;
+            lfp.verify_range(&mut Transcript::new(b"range"), instances, &proof)
+        };
+        assert!(prove_and_verify(&witnesses).is_ok());
+
+        witnesses[3][17].data[5] = 40;
+        assert_eq!(prove_and_verify(&witnesses), Err(FoldingError::RangeMismatch));
+    }
+}
@@ -1,6 +1,29 @@
 #![feature(generic_const_exprs)]
 #![feature(adt_const_params)]
 
+pub mod algebra;
+pub mod ccs;
+pub mod challenge;
+pub mod circom;
+pub mod commitment;
 pub mod cyclotomic_ring;
+pub mod decomposition;
+pub mod ext_field;
 pub mod hexl;
+pub mod inversion;
+pub mod latticefold;
+pub mod latticefold_plus;
+pub mod mle;
+pub mod modular;
+pub mod params;
+pub mod polymul;
+pub mod power_of_two_ring;
 pub mod ringops;
+pub mod rounding;
+pub mod shake;
+pub mod slots;
+pub mod splitting;
+pub mod sumcheck;
+pub mod transcript;
+pub mod typed_ring;
+pub mod wide_ring;
@@ -0,0 +1,301 @@
+//! Multilinear extensions of tables on {0,1}^μ and eq tables.
+//!
+//! As in [`crate::sumcheck`], variable x_1 is the lowest bit of a table
+//! index. Fixing the last variable x_μ = r folds the two contiguous halves,
+//! f(b) ← f(b) + r·(f(b + 2^(μ-1)) - f(b)), which over F_q is one HEXL
+//! subtraction and one fused multiply-add, and over R_q with a scalar r is
+//! the same kernel per ring element. Evaluation fixes x_μ, ..., x_1 in
+//! turn. eq(r, x) = Π (r_i·x_i + (1 - r_i)(1 - x_i)) is built by doubling
+//! the table once per variable.
+//!
+//! The `_parallel` variants split the halves into chunks processed on
+//! `threads` scoped threads; small tables stay on the calling thread.
+
+use crate::algebra::RingElement;
+use crate::hexl::safe::{eltwise_fma_mod, eltwise_fma_mod_assign, eltwise_sub_mod_assign};
+
+/// Fewest entries per chunk worth a thread of their own.
+const MIN_CHUNK: usize = 1 << 10;
+
+/// Runs `f` on matching chunks of `low` and `high`, on up to `threads`
+/// threads.
+fn for_each_chunk_pair<T: Send>(low: &mut [T], high: &mut [T], threads: usize, f: impl Fn(&mut [T], &mut [T]) + Sync) {
+    let chunk = low.len().div_ceil(threads.max(1)).max(MIN_CHUNK);
+    if chunk >= low.len() {
+        f(low, high);
+        return;
+    }
+    let f = &f;
+    std::thread::scope(|scope| {
+        for (low, high) in low.chunks_mut(chunk).zip(high.chunks_mut(chunk)) {
+            scope.spawn(move || f(low, high));
+        }
+    });
+}
+
+fn check_table_len(len: usize) {
+    assert!(len.is_power_of_two(), "table length {len} is not a power of two");
+}
+
+/// Fixes the last variable of a reduced table over F_q.
+pub fn fix_last_variable_fq(table: &mut Vec<u64>, r: u64, modulus: u64) {
+    fix_last_variable_fq_parallel(table, r, modulus, 1);
+}
+
+pub fn fix_last_variable_fq_parallel(table: &mut Vec<u64>, r: u64, modulus: u64, threads: usize) {
+    check_table_len(table.len());
+    assert!(table.len() > 1, "no variable left to fix");
+    let half = table.len() / 2;
+    let (low, high) = table.split_at_mut(half);
+    for_each_chunk_pair(low, high, threads, |low, high| {
+        eltwise_sub_mod_assign(high, low, modulus).expect("f(1, b) - f(0, b)");
+        eltwise_fma_mod_assign(high, r % modulus, Some(low), modulus, 1).expect("f(0, b) + r·(...)");
+        low.copy_from_slice(high);
+    });
+    table.truncate(half);
+}
+
+/// f(point) for a reduced table over F_q.
+pub fn evaluate_fq(table: &[u64], point: &[u64], modulus: u64) -> u64 {
+    evaluate_fq_parallel(table, point, modulus, 1)
+}
+
+pub fn evaluate_fq_parallel(table: &[u64], point: &[u64], modulus: u64, threads: usize) -> u64 {
+    check_table_len(table.len());
+    assert_eq!(table.len(), 1 << point.len(), "point has the wrong number of variables");
+    let mut table = table.to_vec();
+    for &r in point.iter().rev() {
+        fix_last_variable_fq_parallel(&mut table, r, modulus, threads);
+    }
+    table[0]
+}
+
+/// eq(point, x) for all x ∈ {0,1}^μ over F_q.
+pub fn eq_table_fq(point: &[u64], modulus: u64) -> Vec<u64> {
+    eq_table_fq_parallel(point, modulus, 1)
+}
+
+pub fn eq_table_fq_parallel(point: &[u64], modulus: u64, threads: usize) -> Vec<u64> {
+    let mut table = Vec::with_capacity(1 << point.len());
+    table.push(1 % modulus);
+    for &r in point {
+        let len = table.len();
+        table.resize(2 * len, 0);
+        let (low, high) = table.split_at_mut(len);
+        // high = low·r, low = low - high = low·(1 - r)
+        for_each_chunk_pair(low, high, threads, |low, high| {
+            eltwise_fma_mod(high, low, r % modulus, None, modulus, 1).expect("low·r");
+            eltwise_sub_mod_assign(low, high, modulus).expect("low·(1 - r)");
+        });
+    }
+    table
+}
+
+/// The multilinear extension of `evaluations` over any [`RingElement`]:
+/// F_q and F_{q^K} as `ExtField`, or R_q.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultilinearExtension<R> {
+    pub evaluations: Vec<R>,
+}
+
+impl<R: RingElement> MultilinearExtension<R> {
+    pub fn new(evaluations: Vec<R>) -> Self {
+        check_table_len(evaluations.len());
+        Self { evaluations }
+    }
+
+    pub fn num_variables(&self) -> usize {
+        self.evaluations.len().trailing_zeros() as usize
+    }
+
+    fn halves(&mut self) -> (&mut [R], &mut [R]) {
+        assert!(self.num_variables() > 0, "no variable left to fix");
+        let half = self.evaluations.len() / 2;
+        self.evaluations.split_at_mut(half)
+    }
+
+    /// Sets x_μ = `r`.
+    pub fn fix_last_variable(&mut self, r: R) {
+        self.fix_last_variable_parallel(r, 1);
+    }
+
+    pub fn fix_last_variable_parallel(&mut self, mut r: R, threads: usize) {
+        r.prepare();
+        let (low, high) = self.halves();
+        let half = low.len();
+        for_each_chunk_pair(low, high, threads, |low, high| {
+            for (l, &h) in low.iter_mut().zip(high.iter()) {
+                *l = *l + r * (h - *l);
+            }
+        });
+        self.evaluations.truncate(half);
+    }
+
+    /// Sets x_μ = `r` for r ∈ Z_q, which acts on R_q coefficient-wise.
+    pub fn fix_last_variable_scalar(&mut self, r: u64) {
+        self.fix_last_variable_scalar_parallel(r, 1);
+    }
+
+    pub fn fix_last_variable_scalar_parallel(&mut self, r: u64, threads: usize) {
+        let (low, high) = self.halves();
+        let half = low.len();
+        for_each_chunk_pair(low, high, threads, |low, high| {
+            for (l, &h) in low.iter_mut().zip(high.iter()) {
+                *l = *l + (h - *l).scalar_mul(r);
+            }
+        });
+        self.evaluations.truncate(half);
+    }
+
+    /// Sets x_1 = `r`, folding adjacent pairs as the sumcheck prover does.
+    pub fn fix_first_variable(&mut self, mut r: R) {
+        assert!(self.num_variables() > 0, "no variable left to fix");
+        r.prepare();
+        let half = self.evaluations.len() / 2;
+        for b in 0..half {
+            let (low, high) = (self.evaluations[2 * b], self.evaluations[2 * b + 1]);
+            self.evaluations[b] = low + r * (high - low);
+        }
+        self.evaluations.truncate(half);
+    }
+
+    pub fn evaluate(&self, point: &[R]) -> R {
+        self.evaluate_parallel(point, 1)
+    }
+
+    pub fn evaluate_parallel(&self, point: &[R], threads: usize) -> R {
+        assert_eq!(point.len(), self.num_variables(), "point has the wrong number of variables");
+        let mut folded = self.clone();
+        for &r in point.iter().rev() {
+            folded.fix_last_variable_parallel(r, threads);
+        }
+        folded.evaluations[0]
+    }
+
+    /// f(point) for a point in Z_q^μ.
+    pub fn evaluate_scalar(&self, point: &[u64]) -> R {
+        self.evaluate_scalar_parallel(point, 1)
+    }
+
+    pub fn evaluate_scalar_parallel(&self, point: &[u64], threads: usize) -> R {
+        assert_eq!(point.len(), self.num_variables(), "point has the wrong number of variables");
+        let mut folded = self.clone();
+        for &r in point.iter().rev() {
+            folded.fix_last_variable_scalar_parallel(r, threads);
+        }
+        folded.evaluations[0]
+    }
+}
+
+/// eq(point, x) for all x ∈ {0,1}^μ.
+pub fn eq_table<R: RingElement>(point: &[R]) -> Vec<R> {
+    eq_table_parallel(point, 1)
+}
+
+pub fn eq_table_parallel<R: RingElement>(point: &[R], threads: usize) -> Vec<R> {
+    let mut table = Vec::with_capacity(1 << point.len());
+    table.push(R::one());
+    for &r in point {
+        let mut r = r;
+        r.prepare();
+        let len = table.len();
+        table.resize(2 * len, R::zero());
+        let (low, high) = table.split_at_mut(len);
+        for_each_chunk_pair(low, high, threads, |low, high| {
+            for (l, h) in low.iter_mut().zip(high.iter_mut()) {
+                *h = *l * r;
+                *l = *l - *h;
+            }
+        });
+    }
+    table
+}
+
+/// eq(a, b) = Π (a_i·b_i + (1 - a_i)(1 - b_i)).
+pub fn eq<R: RingElement>(a: &[R], b: &[R]) -> R {
+    assert_eq!(a.len(), b.len(), "points with different numbers of variables");
+    a.iter().zip(b).fold(R::one(), |product, (&a, &b)| {
+        let ab = a * b;
+        product * (R::one() - a - b + ab + ab)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cyclotomic_ring::CyclotomicRing;
+    use crate::ext_field::ExtField;
+    use rand::Rng;
+
+    const MOD_Q: u64 = 1125899904679937;
+    const N: usize = 64;
+    const THREADS: usize = 3;
+
+    fn random_point<R: RingElement>(num_variables: usize) -> Vec<R> {
+        (0..num_variables).map(|_| R::random()).collect()
+    }
+
+    /// On the hypercube the extension agrees with the table, and
+    /// Σ_x eq(r, x)·f(x) = f(r) off it.
+    fn check_extension<R: RingElement>(num_variables: usize) {
+        let f = MultilinearExtension::new(random_point::<R>(1 << num_variables));
+        let x = 5 % (1 << num_variables);
+        let corner: Vec<R> = (0..num_variables).map(|i| if x >> i & 1 == 1 { R::one() } else { R::zero() }).collect();
+        assert_eq!(f.evaluate(&corner), f.evaluations[x]);
+
+        let r = random_point::<R>(num_variables);
+        let value = f.evaluate(&r);
+        let table = eq_table(&r);
+        let inner_product = table.iter().zip(&f.evaluations).fold(R::zero(), |sum, (&e, &v)| sum + e * v);
+        assert_eq!(inner_product, value);
+        assert_eq!(table[x], eq(&r, &corner));
+
+        // Fixing x_1 first gives the same value.
+        let mut first_fixed = f.clone();
+        first_fixed.fix_first_variable(r[0]);
+        assert_eq!(first_fixed.evaluate(&r[1..]), value);
+
+        assert_eq!(f.evaluate_parallel(&r, THREADS), value);
+        assert_eq!(eq_table_parallel(&r, THREADS), table);
+    }
+
+    #[test]
+    fn test_extensions_over_fields_and_rings() {
+        check_extension::<ExtField<MOD_Q, 1>>(12);
+        check_extension::<ExtField<MOD_Q, 2>>(12);
+        check_extension::<ExtField<MOD_Q, 4>>(4);
+        check_extension::<CyclotomicRing<MOD_Q, N>>(4);
+    }
+
+    #[test]
+    fn test_base_field_kernels_match_generic() {
+        type F = ExtField<MOD_Q, 1>;
+        let mut rng = rand::rng();
+        let num_variables = 13;
+        let table: Vec<u64> = (0..1 << num_variables).map(|_| rng.random_range(0..MOD_Q)).collect();
+        let point: Vec<u64> = (0..num_variables).map(|_| rng.random_range(0..MOD_Q)).collect();
+
+        let f = MultilinearExtension::new(table.iter().map(|&v| F::from_base(v)).collect());
+        let lifted_point: Vec<F> = point.iter().map(|&r| F::from_base(r)).collect();
+        let expected = f.evaluate(&lifted_point).coefficients[0];
+        assert_eq!(evaluate_fq(&table, &point, MOD_Q), expected);
+        assert_eq!(evaluate_fq_parallel(&table, &point, MOD_Q, THREADS), expected);
+        assert_eq!(f.evaluate_scalar_parallel(&point, THREADS).coefficients[0], expected);
+
+        let eq_expected: Vec<u64> = eq_table(&lifted_point).iter().map(|e| e.coefficients[0]).collect();
+        assert_eq!(eq_table_fq(&point, MOD_Q), eq_expected);
+        assert_eq!(eq_table_fq_parallel(&point, MOD_Q, THREADS), eq_expected);
+    }
+
+    #[test]
+    fn test_ring_tables_with_scalar_points() {
+        type R = CyclotomicRing<MOD_Q, N>;
+        let mut rng = rand::rng();
+        let f = MultilinearExtension::new(random_point::<R>(1 << 11));
+        let point: Vec<u64> = (0..11).map(|_| rng.random_range(0..MOD_Q)).collect();
+        let lifted_point: Vec<R> = point.iter().map(|&r| R::from_u64(r)).collect();
+        let expected = f.evaluate(&lifted_point);
+        assert_eq!(f.evaluate_scalar(&point), expected);
+        assert_eq!(f.evaluate_scalar_parallel(&point, THREADS), expected);
+    }
+}
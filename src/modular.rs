@@ -0,0 +1,251 @@
+//! Scalar modular arithmetic in pure Rust, for parameter analysis and for
+//! code paths that cannot go through HEXL. All functions are `const` so they
+//! can back compile-time checks.
+
+pub const fn mul_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    ((a as u128 * b as u128) % modulus as u128) as u64
+}
+
+pub const fn add_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    ((a as u128 + b as u128) % modulus as u128) as u64
+}
+
+pub const fn sub_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    ((a as u128 + modulus as u128 - (b % modulus) as u128) % modulus as u128) as u64
+}
+
+pub const fn pow_mod(base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    let mut result = 1 % modulus;
+    let mut base = base % modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exponent >>= 1;
+    }
+    result
+}
+
+pub const fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a
+}
+
+/// Inverse of `a` modulo `modulus`, or `None` if they are not coprime.
+pub const fn inv_mod(a: u64, modulus: u64) -> Option<u64> {
+    let (mut old_r, mut r) = ((a % modulus) as i128, modulus as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    while r != 0 {
+        let quotient = old_r / r;
+        let t = old_r - quotient * r;
+        old_r = r;
+        r = t;
+        let t = old_s - quotient * s;
+        old_s = s;
+        s = t;
+    }
+    if old_r != 1 {
+        return None;
+    }
+    Some(old_s.rem_euclid(modulus as i128) as u64)
+}
+
+/// Deterministic Miller–Rabin; these bases are sufficient for all of u64.
+pub const fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    let mut i = 0;
+    while i < BASES.len() {
+        if n.is_multiple_of(BASES[i]) {
+            return n == BASES[i];
+        }
+        i += 1;
+    }
+    let mut d = n - 1;
+    let mut s = 0;
+    while d.is_multiple_of(2) {
+        d /= 2;
+        s += 1;
+    }
+    let mut i = 0;
+    'witness: while i < BASES.len() {
+        let mut x = pow_mod(BASES[i], d, n);
+        i += 1;
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        let mut r = 1;
+        while r < s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'witness;
+            }
+            r += 1;
+        }
+        return false;
+    }
+    true
+}
+
+/// Multiplicative order of odd `a` modulo the power of two `modulus`.
+/// `(Z/2^k)^*` is a 2-group, so the order is found by repeated squaring.
+pub const fn order_mod_power_of_two(a: u64, modulus: u64) -> u64 {
+    let mut x = a % modulus;
+    let mut order = 1;
+    while x != 1 % modulus {
+        x = mul_mod(x, x, modulus);
+        order *= 2;
+    }
+    order
+}
+
+/// Centered representative of `a` in `(-q/2, q/2]`.
+pub const fn centered(a: u64, modulus: u64) -> i64 {
+    if a > modulus / 2 {
+        -((modulus - a) as i64)
+    } else {
+        a as i64
+    }
+}
+
+/// Writes the balanced base-`base` digits of `value` to `digits`, least
+/// significant first, each in `(-base/2, base/2]`. Returns `false` if
+/// `digits.len()` digits do not suffice to represent `value`.
+pub fn balanced_digits(mut value: i128, base: u64, digits: &mut [i64]) -> bool {
+    let base = base as i128;
+    for digit in digits.iter_mut() {
+        let mut remainder = value.rem_euclid(base);
+        if remainder > base / 2 {
+            remainder -= base;
+        }
+        *digit = remainder as i64;
+        value = (value - remainder) / base;
+    }
+    value == 0
+}
+
+/// Legendre symbol of `a` modulo the odd prime `p` by Euler's criterion:
+/// 1 for nonzero squares, `p - 1` for non-squares, 0 for 0.
+pub const fn legendre(a: u64, p: u64) -> u64 {
+    pow_mod(a, (p - 1) / 2, p)
+}
+
+/// The smallest quadratic non-residue modulo the odd prime `p`.
+pub const fn smallest_non_residue(p: u64) -> u64 {
+    let mut z = 2;
+    while legendre(z, p) != p - 1 {
+        z += 1;
+    }
+    z
+}
+
+/// A square root of `a` modulo the odd prime `p` (Tonelli–Shanks), the
+/// smaller of the two, or `None` if `a` is not a square.
+pub const fn sqrt_mod(a: u64, p: u64) -> Option<u64> {
+    let a = a % p;
+    if a == 0 {
+        return Some(0);
+    }
+    if legendre(a, p) != 1 {
+        return None;
+    }
+    let mut q = p - 1;
+    let mut s = 0;
+    while q.is_multiple_of(2) {
+        q /= 2;
+        s += 1;
+    }
+    let mut m = s;
+    let mut c = pow_mod(smallest_non_residue(p), q, p);
+    let mut t = pow_mod(a, q, p);
+    let mut r = pow_mod(a, q.div_ceil(2), p);
+    while t != 1 {
+        let mut i = 0;
+        let mut t2i = t;
+        while t2i != 1 {
+            t2i = mul_mod(t2i, t2i, p);
+            i += 1;
+        }
+        let b = pow_mod(c, 1 << (m - i - 1), p);
+        m = i;
+        c = mul_mod(b, b, p);
+        t = mul_mod(t, c, p);
+        r = mul_mod(r, b, p);
+    }
+    Some(if r > p - r { p - r } else { r })
+}
+
+/// Replaces every value by its inverse modulo `modulus` with Montgomery's
+/// trick: one `inv_mod` and 3(n - 1) multiplications. Returns `false`, and
+/// leaves `values` unchanged, if some value is not invertible.
+pub fn batch_inv_mod(values: &mut [u64], modulus: u64) -> bool {
+    let mut prefix = Vec::with_capacity(values.len());
+    let mut acc = 1 % modulus;
+    for &value in values.iter() {
+        acc = mul_mod(acc, value, modulus);
+        prefix.push(acc);
+    }
+    let Some(mut inverse) = inv_mod(acc, modulus) else {
+        return false;
+    };
+    for i in (0..values.len()).rev() {
+        let previous = if i == 0 { 1 % modulus } else { prefix[i - 1] };
+        let value = values[i];
+        values[i] = mul_mod(inverse, previous, modulus);
+        inverse = mul_mod(inverse, value, modulus);
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_prime() {
+        let primes = [2u64, 3, 17, 65537, 1125899904679937, 2305843009213693921, 18446744073709551557];
+        let composites = [0u64, 1, 4, 561, 1125899904679939, 3215031751, 18446744073709551615];
+        assert!(primes.iter().all(|&p| is_prime(p)));
+        assert!(composites.iter().all(|&c| !is_prime(c)));
+    }
+
+    #[test]
+    fn test_inverse_and_order() {
+        assert_eq!(inv_mod(3, 17), Some(6));
+        assert_eq!(inv_mod(4, 16), None);
+        assert_eq!(order_mod_power_of_two(97, 128), 4);
+        assert_eq!(order_mod_power_of_two(17, 16), 1);
+        assert_eq!(centered(16, 17), -1);
+        assert_eq!(centered(8, 17), 8);
+
+        let mut values = [3u64, 5, 16];
+        assert!(batch_inv_mod(&mut values, 17));
+        assert_eq!(values, [6, 7, 16]);
+        assert_eq!(sqrt_mod(13, 17), Some(8));
+        assert_eq!(sqrt_mod(3, 17), None);
+        let q = 1125899904679937;
+        let square = mul_mod(123456789, 123456789, q);
+        let root = sqrt_mod(square, q).unwrap();
+        assert_eq!(mul_mod(root, root, q), square);
+        assert_eq!(sqrt_mod(smallest_non_residue(q), q), None);
+        let mut with_zero = [3u64, 0];
+        assert!(!batch_inv_mod(&mut with_zero, 17));
+        assert_eq!(with_zero, [3, 0]);
+    }
+
+    #[test]
+    fn test_balanced_digits() {
+        let mut digits = [0i64; 4];
+        assert!(balanced_digits(-1234, 16, &mut digits));
+        assert!(digits.iter().all(|d| d.abs() <= 8));
+        assert_eq!(digits.iter().rev().fold(0i128, |acc, &d| acc * 16 + d as i128), -1234);
+        assert!(!balanced_digits(1 << 20, 16, &mut digits));
+    }
+}
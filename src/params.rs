@@ -0,0 +1,173 @@
+//! Validation of ring parameters (q, N) before they reach HEXL.
+//!
+//! HEXL aborts the process from C++ when asked for an NTT it cannot
+//! compute, and silently returns garbage for some out-of-range inputs. The
+//! checks here are `const fn`s: `CyclotomicRing` evaluates them as
+//! associated constants, so checks that every instance needs (N a power of
+//! two, q ≥ 2, and a valid q whenever an NTT strategy is selected) fail the
+//! build, and checks only the NTT paths need are computed once at compile
+//! time and reported as `RingError` at the call site.
+
+use std::fmt;
+
+use crate::hexl::safe::{HexlError, MAX_MODULUS_BITS};
+use crate::splitting::{fast_strategy, incomplete_degree, select_strategy, MultiplicationStrategy, StrategyError};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RingError {
+    DegreeNotPowerOfTwo(usize),
+    ModulusTooSmall(u64),
+    NotPrime(u64),
+    ModulusTooLarge { modulus: u64, max_bits: u32 },
+    /// q ≢ 1 mod 2N / degree, so no degree-`degree` incomplete NTT exists.
+    NotNttFriendly { modulus: u64, n: usize, degree: usize },
+    Backend(HexlError),
+}
+
+impl fmt::Display for RingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RingError::DegreeNotPowerOfTwo(n) => write!(f, "ring degree N = {n} is not a power of two ≥ 2"),
+            RingError::ModulusTooSmall(q) => write!(f, "modulus {q} is below 2"),
+            RingError::NotPrime(q) => write!(f, "modulus {q} is not an odd prime"),
+            RingError::ModulusTooLarge { modulus, max_bits } => {
+                write!(f, "modulus {modulus} exceeds the backend limit of 2^{max_bits}")
+            }
+            RingError::NotNttFriendly { modulus, n, degree } => write!(
+                f,
+                "modulus {modulus} is not 1 mod {} (needed for a degree-{degree} NTT of X^{n} + 1)",
+                2 * n / degree
+            ),
+            RingError::Backend(error) => write!(f, "HEXL: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for RingError {}
+
+impl From<HexlError> for RingError {
+    fn from(error: HexlError) -> Self {
+        RingError::Backend(error)
+    }
+}
+
+impl From<StrategyError> for RingError {
+    fn from(error: StrategyError) -> Self {
+        RingError::from_strategy(error)
+    }
+}
+
+impl RingError {
+    /// Why [`fast_strategy`] found no NTT: a composite q, a q beyond the
+    /// backend limit, or too few factors of two in q - 1.
+    const fn from_strategy(error: StrategyError) -> Self {
+        match error {
+            StrategyError::NotPrime(q) => RingError::NotPrime(q),
+            StrategyError::NoFastPath { modulus, .. } if modulus >= 1 << MAX_MODULUS_BITS => {
+                RingError::ModulusTooLarge { modulus, max_bits: MAX_MODULUS_BITS }
+            }
+            StrategyError::NoFastPath { modulus, n } => {
+                RingError::NotNttFriendly { modulus, n, degree: incomplete_degree(modulus, n) }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RingParams {
+    pub modulus: u64,
+    pub n: usize,
+}
+
+impl RingParams {
+    pub const fn new(modulus: u64, n: usize) -> Self {
+        Self { modulus, n }
+    }
+
+    /// N must be a power of two ≥ 2 for X^N + 1 to be cyclotomic; every
+    /// ring operation relies on it.
+    pub const fn check_degree(&self) -> Result<(), RingError> {
+        if self.n < 2 || !self.n.is_power_of_two() {
+            return Err(RingError::DegreeNotPowerOfTwo(self.n));
+        }
+        Ok(())
+    }
+
+    /// Checks everything the NTT-based multiplications need and returns the
+    /// strategy they would use: N a power of two, q an odd prime below the
+    /// backend limit and q ≡ 1 mod 2N/d for some d ≤ N/2.
+    pub const fn validate(&self) -> Result<MultiplicationStrategy, RingError> {
+        if let Err(error) = self.check_degree() {
+            return Err(error);
+        }
+        match fast_strategy(self.modulus, self.n) {
+            Ok(strategy) => Ok(strategy),
+            Err(error) => Err(RingError::from_strategy(error)),
+        }
+    }
+
+    /// What every ring needs, whatever its strategy: N a power of two,
+    /// q ≥ 2, and all of [`RingParams::validate`] unless the ring falls back
+    /// to coefficient-domain multiplication. Returns the strategy in use.
+    pub const fn check(&self) -> Result<MultiplicationStrategy, RingError> {
+        if let Err(error) = self.check_degree() {
+            return Err(error);
+        }
+        if self.modulus < 2 {
+            return Err(RingError::ModulusTooSmall(self.modulus));
+        }
+        match select_strategy(self.modulus, self.n) {
+            MultiplicationStrategy::NonNtt => Ok(MultiplicationStrategy::NonNtt),
+            _ => self.validate(),
+        }
+    }
+
+    /// Like [`RingParams::validate`], but for one specific transform: the
+    /// degree-`degree` incomplete NTT (degree 1 is the full NTT).
+    pub const fn check_ntt_degree(&self, degree: usize) -> Result<(), RingError> {
+        if let Err(error) = self.validate() {
+            return Err(error);
+        }
+        if self.modulus % (2 * self.n / degree) as u64 != 1 {
+            return Err(RingError::NotNttFriendly { modulus: self.modulus, n: self.n, degree });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation_errors() {
+        assert_eq!(RingParams::new(17, 8).validate(), Ok(MultiplicationStrategy::FullySplitting));
+        assert_eq!(RingParams::new(17, 12).validate(), Err(RingError::DegreeNotPowerOfTwo(12)));
+        assert_eq!(RingParams::new(1 << 20, 16).validate(), Err(RingError::NotPrime(1 << 20)));
+        assert_eq!(
+            RingParams::new(9223372036854775783, 16).validate(),
+            Err(RingError::ModulusTooLarge { modulus: 9223372036854775783, max_bits: MAX_MODULUS_BITS })
+        );
+        assert_eq!(
+            RingParams::new(31, 16).validate(),
+            Err(RingError::NotNttFriendly { modulus: 31, n: 16, degree: 16 })
+        );
+        // 41 ≡ 1 mod 8 but not mod 16: only the 2-way incomplete NTT exists for N = 8.
+        assert_eq!(RingParams::new(41, 8).check_ntt_degree(2), Ok(()));
+        assert_eq!(
+            RingParams::new(41, 8).check_ntt_degree(1),
+            Err(RingError::NotNttFriendly { modulus: 41, n: 8, degree: 1 })
+        );
+    }
+
+    #[test]
+    fn test_construction_check() {
+        assert_eq!(RingParams::new(17, 8).check(), Ok(MultiplicationStrategy::FullySplitting));
+        assert_eq!(RingParams::new(97, 64).check(), Ok(MultiplicationStrategy::IncompleteNtt { degree: 4 }));
+        // Composite and non-splitting moduli fall back to Karatsuba.
+        assert_eq!(RingParams::new(1 << 32, 64).check(), Ok(MultiplicationStrategy::NonNtt));
+        assert_eq!(RingParams::new(31, 16).check(), Ok(MultiplicationStrategy::NonNtt));
+        assert_eq!(RingParams::new(1, 64).check(), Err(RingError::ModulusTooSmall(1)));
+        assert_eq!(RingParams::new(17, 12).check(), Err(RingError::DegreeNotPowerOfTwo(12)));
+    }
+}
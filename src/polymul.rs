@@ -0,0 +1,430 @@
+//! Coefficient-domain negacyclic multiplication for moduli without a usable
+//! NTT: Karatsuba, Toom-3 and a CRT-lifted variant that multiplies over
+//! several NTT-friendly primes and reduces the exact integer product mod q.
+//!
+//! The subquadratic algorithms are generic over [`CoefficientArith`], so the
+//! same code serves prime moduli and the wrapping 2^64 arithmetic used by
+//! the power-of-two ring.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use crate::cyclotomic_ring::CyclotomicRing;
+use crate::hexl::safe::{eltwise_mult_mod_lazy_assign, ntt_forward_lazy, ntt_inverse};
+use crate::modular::{gcd, inv_mod, is_prime, mul_mod};
+
+/// Below this length Karatsuba falls back to schoolbook multiplication.
+const KARATSUBA_THRESHOLD: usize = 32;
+/// Below this length Toom-3 falls back to Karatsuba.
+const TOOM3_THRESHOLD: usize = 96;
+
+pub trait CoefficientArith: Copy {
+    fn add(self, a: u64, b: u64) -> u64;
+    fn sub(self, a: u64, b: u64) -> u64;
+    /// Writes the acyclic product of `a` and `b` (length `a.len() + b.len() - 1`) to `out`.
+    fn schoolbook(self, a: &[u64], b: &[u64], out: &mut [u64]);
+
+    /// a + b for a sum below 4q, left unreduced when the arithmetic allows it.
+    fn add_lazy(self, a: u64, b: u64) -> u64 {
+        self.add(a, b)
+    }
+
+    /// a - b for a below q and b below 2q, left unreduced when the
+    /// arithmetic allows it.
+    fn sub_lazy(self, a: u64, b: u64) -> u64 {
+        self.sub(a, b)
+    }
+
+    /// Brings a lazy value below 4q back to the canonical range.
+    fn reduce(self, a: u64) -> u64 {
+        a
+    }
+}
+
+/// Arithmetic in Z_q for any modulus q ≥ 2.
+#[derive(Clone, Copy, Debug)]
+pub struct Modulus(pub u64);
+
+impl Modulus {
+    /// Lazy values stay below 4q, which needs q < 2^62 to fit a u64.
+    fn is_lazy(self) -> bool {
+        self.0 < 1 << 62
+    }
+}
+
+impl CoefficientArith for Modulus {
+    fn add(self, a: u64, b: u64) -> u64 {
+        let (sum, carry) = a.overflowing_add(b);
+        if carry || sum >= self.0 { sum.wrapping_sub(self.0) } else { sum }
+    }
+
+    fn sub(self, a: u64, b: u64) -> u64 {
+        if a >= b { a - b } else { a.wrapping_sub(b).wrapping_add(self.0) }
+    }
+
+    /// Products are summed in a u128 and reduced once per output
+    /// coefficient, or earlier if the sum could overflow.
+    fn schoolbook(self, a: &[u64], b: &[u64], out: &mut [u64]) {
+        let q = self.0 as u128;
+        let max_terms = (u128::MAX / ((q - 1) * (q - 1)).max(1)) as usize;
+        for (k, out) in out.iter_mut().enumerate() {
+            let start = k.saturating_sub(b.len() - 1);
+            let end = k.min(a.len() - 1);
+            let mut acc: u128 = 0;
+            let mut terms = 0;
+            for i in start..=end {
+                if terms == max_terms {
+                    acc %= q;
+                    terms = 1;
+                }
+                acc += a[i] as u128 * b[k - i] as u128;
+                terms += 1;
+            }
+            *out = (acc % q) as u64;
+        }
+    }
+
+    fn add_lazy(self, a: u64, b: u64) -> u64 {
+        if self.is_lazy() { a + b } else { self.add(a, b) }
+    }
+
+    fn sub_lazy(self, a: u64, b: u64) -> u64 {
+        if self.is_lazy() { a + 2 * self.0 - b } else { self.sub(a, b) }
+    }
+
+    fn reduce(self, a: u64) -> u64 {
+        let a = if a >= 2 * self.0 && self.is_lazy() { a - 2 * self.0 } else { a };
+        if a >= self.0 { a - self.0 } else { a }
+    }
+}
+
+/// Arithmetic in Z_{2^64}; reducing the result modulo 2^k gives Z_{2^k}.
+#[derive(Clone, Copy, Debug)]
+pub struct Wrapping;
+
+impl CoefficientArith for Wrapping {
+    fn add(self, a: u64, b: u64) -> u64 {
+        a.wrapping_add(b)
+    }
+
+    fn sub(self, a: u64, b: u64) -> u64 {
+        a.wrapping_sub(b)
+    }
+
+    fn schoolbook(self, a: &[u64], b: &[u64], out: &mut [u64]) {
+        out.fill(0);
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                out[i + j] = out[i + j].wrapping_add(x.wrapping_mul(y));
+            }
+        }
+    }
+}
+
+/// Acyclic product of two equal-length polynomials by Karatsuba. The three
+/// sub-products are combined lazily in [0, 4q) and reduced once at the end.
+pub fn karatsuba<A: CoefficientArith>(arith: A, a: &[u64], b: &[u64]) -> Vec<u64> {
+    assert_eq!(a.len(), b.len(), "Karatsuba operands must have equal length");
+    let n = a.len();
+    let mut out = vec![0u64; 2 * n - 1];
+    if n <= KARATSUBA_THRESHOLD {
+        arith.schoolbook(a, b, &mut out);
+        return out;
+    }
+
+    let h = n.div_ceil(2);
+    let (a0, a1) = a.split_at(h);
+    let (b0, b1) = b.split_at(h);
+    let mut a_sum = a0.to_vec();
+    let mut b_sum = b0.to_vec();
+    for i in 0..n - h {
+        a_sum[i] = arith.add(a_sum[i], a1[i]);
+        b_sum[i] = arith.add(b_sum[i], b1[i]);
+    }
+
+    let z0 = karatsuba(arith, a0, b0);
+    let z2 = if n - h == h { karatsuba(arith, a1, b1) } else { karatsuba(arith, &pad(a1, h), &pad(b1, h)) };
+    let mut z1 = karatsuba(arith, &a_sum, &b_sum);
+    for i in 0..z1.len() {
+        z1[i] = arith.sub_lazy(z1[i], arith.add_lazy(z0[i], z2[i]));
+    }
+
+    // z0 and z2 do not overlap, so every output is one of them plus z1.
+    out[..z0.len()].copy_from_slice(&z0);
+    for (i, &c) in z1.iter().enumerate() {
+        out[i + h] = arith.add_lazy(out[i + h], c);
+    }
+    // z2 may carry zero padding beyond the true product length.
+    for (i, &c) in z2.iter().enumerate().take(out.len() - 2 * h) {
+        out[i + 2 * h] = arith.add_lazy(out[i + 2 * h], c);
+    }
+    out.iter_mut().for_each(|c| *c = arith.reduce(*c));
+    out
+}
+
+fn pad(a: &[u64], len: usize) -> Vec<u64> {
+    let mut padded = a.to_vec();
+    padded.resize(len, 0);
+    padded
+}
+
+/// Acyclic product of two equal-length polynomials by Toom-3 with the
+/// evaluation points 0, 1, -1, -2, ∞ and Bodrato's interpolation sequence.
+/// The interpolation divides by 2 and 3, so q must be coprime to 6.
+pub fn toom3(modulus: Modulus, a: &[u64], b: &[u64]) -> Vec<u64> {
+    assert_eq!(a.len(), b.len(), "Toom-3 operands must have equal length");
+    assert_eq!(gcd(modulus.0, 6), 1, "Toom-3 needs a modulus coprime to 6, got {}", modulus.0);
+    let inv2 = inv_mod(2, modulus.0).unwrap();
+    let inv3 = inv_mod(3, modulus.0).unwrap();
+    toom3_inner(modulus, inv2, inv3, a, b)
+}
+
+fn toom3_inner(m: Modulus, inv2: u64, inv3: u64, a: &[u64], b: &[u64]) -> Vec<u64> {
+    let n = a.len();
+    if n <= TOOM3_THRESHOLD {
+        return karatsuba(m, a, b);
+    }
+    let q = m.0;
+    let k = n.div_ceil(3);
+    let a = pad(a, 3 * k);
+    let b = pad(b, 3 * k);
+
+    // Values of the three-part split at 0, 1, -1, -2 and ∞.
+    let evaluate = |p: &[u64]| {
+        let (p0, rest) = p.split_at(k);
+        let (p1, p2) = rest.split_at(k);
+        let mut points = vec![vec![0u64; k]; 5];
+        for i in 0..k {
+            let even = m.add(p0[i], p2[i]);
+            points[0][i] = p0[i];
+            points[1][i] = m.add(even, p1[i]);
+            points[2][i] = m.sub(even, p1[i]);
+            let two_p1 = m.add(p1[i], p1[i]);
+            let four_p2 = mul_mod(p2[i], 4 % q, q);
+            points[3][i] = m.add(m.sub(p0[i], two_p1), four_p2);
+            points[4][i] = p2[i];
+        }
+        points
+    };
+    let pa = evaluate(&a);
+    let pb = evaluate(&b);
+    let r: Vec<Vec<u64>> = pa.iter().zip(pb.iter()).map(|(x, y)| toom3_inner(m, inv2, inv3, x, y)).collect();
+
+    let len = 2 * k - 1;
+    let mut c = vec![vec![0u64; len]; 5];
+    for i in 0..len {
+        let (r0, r1, r_1, r_2, rinf) = (r[0][i], r[1][i], r[2][i], r[3][i], r[4][i]);
+        let mut t3 = mul_mod(m.sub(r_2, r1), inv3, q);
+        let mut t1 = mul_mod(m.sub(r1, r_1), inv2, q);
+        let mut t2 = m.sub(r_1, r0);
+        t3 = m.add(mul_mod(m.sub(t2, t3), inv2, q), m.add(rinf, rinf));
+        t2 = m.sub(m.add(t2, t1), rinf);
+        t1 = m.sub(t1, t3);
+        c[0][i] = r0;
+        c[1][i] = t1;
+        c[2][i] = t2;
+        c[3][i] = t3;
+        c[4][i] = rinf;
+    }
+
+    let mut out = vec![0u64; 2 * n - 1];
+    for (j, part) in c.iter().enumerate() {
+        for (i, &value) in part.iter().enumerate() {
+            if j * k + i < out.len() {
+                out[j * k + i] = m.add(out[j * k + i], value);
+            }
+        }
+    }
+    out
+}
+
+/// Folds an acyclic product of length 2N - 1 modulo X^N + 1.
+pub fn negacyclic_fold<A: CoefficientArith>(arith: A, product: &[u64], out: &mut [u64]) {
+    let n = out.len();
+    out.copy_from_slice(&product[..n]);
+    for (i, &high) in product[n..].iter().enumerate() {
+        out[i] = arith.sub(out[i], high);
+    }
+}
+
+pub fn karatsuba_multiply<const MOD_Q: u64, const N: usize>(
+    operand1: &mut CyclotomicRing<MOD_Q, N>,
+    operand2: &mut CyclotomicRing<MOD_Q, N>,
+) -> CyclotomicRing<MOD_Q, N> {
+    operand1.to_coeff_representation();
+    operand2.to_coeff_representation();
+    let product = karatsuba(Modulus(MOD_Q), &operand1.data, &operand2.data);
+    let mut result = CyclotomicRing::<MOD_Q, N>::new();
+    negacyclic_fold(Modulus(MOD_Q), &product, &mut result.data);
+    result
+}
+
+pub fn toom_cook_multiply<const MOD_Q: u64, const N: usize>(
+    operand1: &mut CyclotomicRing<MOD_Q, N>,
+    operand2: &mut CyclotomicRing<MOD_Q, N>,
+) -> CyclotomicRing<MOD_Q, N> {
+    operand1.to_coeff_representation();
+    operand2.to_coeff_representation();
+    let product = toom3(Modulus(MOD_Q), &operand1.data, &operand2.data);
+    let mut result = CyclotomicRing::<MOD_Q, N>::new();
+    negacyclic_fold(Modulus(MOD_Q), &product, &mut result.data);
+    result
+}
+
+/// Largest bit size of the auxiliary NTT primes; HEXL needs them below 2^62.
+const CRT_PRIME_BITS: u32 = 61;
+
+type CrtPrimesCache = Mutex<HashMap<(usize, u32), Vec<u64>>>;
+
+static CRT_PRIMES_CACHE: OnceLock<CrtPrimesCache> = OnceLock::new();
+
+/// The largest primes below 2^61 with p ≡ 1 mod 2·`ntt_size` whose product
+/// has more than `bits` bits.
+pub fn crt_primes(ntt_size: usize, bits: u32) -> Vec<u64> {
+    let cache = CRT_PRIMES_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    cache
+        .lock()
+        .unwrap()
+        .entry((ntt_size, bits))
+        .or_insert_with(|| {
+            let step = 2 * ntt_size as u64;
+            let mut candidate = ((1u64 << CRT_PRIME_BITS) - 1) / step * step + 1;
+            let mut primes = Vec::new();
+            let mut total_bits = 0.0;
+            while total_bits <= bits as f64 {
+                while !is_prime(candidate) {
+                    candidate -= step;
+                }
+                primes.push(candidate);
+                total_bits += (candidate as f64).log2();
+                candidate -= step;
+            }
+            primes
+        })
+        .clone()
+}
+
+/// Multiplication through exact integer arithmetic: the acyclic product of
+/// the canonical representatives has coefficients below N·q², so it is
+/// computed modulo enough NTT-friendly primes (with zero-padded size-2N
+/// NTTs), lifted by Garner's mixed-radix CRT and only then reduced mod q.
+/// Works for any modulus, including powers of two and primes that barely
+/// split.
+pub fn crt_multiply<const MOD_Q: u64, const N: usize>(
+    operand1: &mut CyclotomicRing<MOD_Q, N>,
+    operand2: &mut CyclotomicRing<MOD_Q, N>,
+) -> CyclotomicRing<MOD_Q, N> {
+    operand1.to_coeff_representation();
+    operand2.to_coeff_representation();
+    let bound_bits = 2 * (64 - (MOD_Q - 1).leading_zeros()) + N.trailing_zeros() + 1;
+    let primes = crt_primes(2 * N, bound_bits);
+
+    // Residues of the acyclic product modulo every prime.
+    let residues: Vec<Vec<u64>> = primes
+        .iter()
+        .map(|&p| {
+            let mut a = vec![0u64; 2 * N];
+            let mut b = vec![0u64; 2 * N];
+            for i in 0..N {
+                a[i] = operand1.data[i] % p;
+                b[i] = operand2.data[i] % p;
+            }
+            ntt_forward_lazy(&mut a, p, 1, 4).expect("forward NTT");
+            ntt_forward_lazy(&mut b, p, 1, 4).expect("forward NTT");
+            eltwise_mult_mod_lazy_assign(&mut a, &b, p, 4).expect("slot-wise multiplication");
+            ntt_inverse(&mut a, p).expect("inverse NTT");
+            a
+        })
+        .collect();
+
+    // Garner: x = d_0 + d_1 p_0 + d_2 p_0 p_1 + ..., evaluated mod q.
+    let radix_mod_q: Vec<u64> = primes
+        .iter()
+        .scan(1 % MOD_Q, |acc, &p| {
+            let current = *acc;
+            *acc = mul_mod(*acc, p % MOD_Q, MOD_Q);
+            Some(current)
+        })
+        .collect();
+    let mut inverses = vec![vec![0u64; primes.len()]; primes.len()];
+    for i in 0..primes.len() {
+        for j in 0..i {
+            inverses[i][j] = inv_mod(primes[j] % primes[i], primes[i]).unwrap();
+        }
+    }
+
+    let m = Modulus(MOD_Q);
+    let mut product = vec![0u64; 2 * N - 1];
+    let mut digits = vec![0u64; primes.len()];
+    for (k, coefficient) in product.iter_mut().enumerate() {
+        for i in 0..primes.len() {
+            let p = primes[i];
+            let mut t = residues[i][k];
+            for j in 0..i {
+                t = mul_mod(Modulus(p).sub(t, digits[j] % p), inverses[i][j], p);
+            }
+            digits[i] = t;
+            *coefficient = m.add(*coefficient, mul_mod(t % MOD_Q, radix_mod_q[i], MOD_Q));
+        }
+    }
+
+    let mut result = CyclotomicRing::<MOD_Q, N>::new();
+    negacyclic_fold(m, &product, &mut result.data);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cyclotomic_ring::naive_multiply;
+
+    fn check_all_variants<const MOD_Q: u64, const N: usize>() {
+        for _ in 0..3 {
+            let a = CyclotomicRing::<MOD_Q, N>::random();
+            let b = CyclotomicRing::<MOD_Q, N>::random();
+            let expected = naive_multiply(&mut a.clone(), &mut b.clone());
+
+            assert_eq!(karatsuba_multiply(&mut a.clone(), &mut b.clone()), expected);
+            assert_eq!(crt_multiply(&mut a.clone(), &mut b.clone()), expected);
+            if gcd(MOD_Q, 6) == 1 {
+                assert_eq!(toom_cook_multiply(&mut a.clone(), &mut b.clone()), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ntt_friendly_prime() {
+        check_all_variants::<1125899904679937, 64>();
+    }
+
+    #[test]
+    fn test_barely_splitting_prime() {
+        // 2^61 - 1 ≡ -1 mod 2N: X^N + 1 only splits into quadratics.
+        check_all_variants::<2305843009213693951, 256>();
+    }
+
+    #[test]
+    fn test_power_of_two_modulus() {
+        check_all_variants::<{ 1 << 32 }, 128>();
+    }
+
+    #[test]
+    fn test_modulus_divisible_by_three() {
+        check_all_variants::<{ 3 * 5 * 7 * 11 * 13 * 17 * 19 * 23 }, 512>();
+    }
+
+    #[test]
+    fn test_large_modulus() {
+        // 2^63 - 25, the largest prime `naive_multiply` handles without overflow.
+        check_all_variants::<9223372036854775783, 64>();
+    }
+
+    #[test]
+    fn test_wrapping_karatsuba() {
+        let a: Vec<u64> = (0..100).map(|i| u64::MAX - i * 977).collect();
+        let b: Vec<u64> = (0..100).map(|i| i * i * 131 + 7).collect();
+        let mut expected = vec![0u64; 199];
+        Wrapping.schoolbook(&a, &b, &mut expected);
+        assert_eq!(karatsuba(Wrapping, &a, &b), expected);
+    }
+}
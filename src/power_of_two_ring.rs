@@ -0,0 +1,187 @@
+//! Z_{2^k}[X]/(X^N + 1), for comparisons with schemes over power-of-two
+//! moduli. Reduction mod 2^k is a mask over wrapping u64 arithmetic and
+//! there is no NTT, so multiplication goes through Karatsuba. Norms and
+//! decomposition come from [`PolynomialRing`], as for `CyclotomicRing`.
+
+use rand::Rng;
+use std::ops::{Add, Mul, Sub};
+
+#[cfg(doc)]
+use crate::algebra::PolynomialRing;
+use crate::polymul::{karatsuba, negacyclic_fold, Wrapping};
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub struct PowerOfTwoRing<const LOG_Q: u32, const N: usize> {
+    pub data: [u64; N],
+}
+
+impl<const LOG_Q: u32, const N: usize> Add for PowerOfTwoRing<LOG_Q, N> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        let mut result = Self::new();
+        for i in 0..N {
+            result.data[i] = Self::reduce(self.data[i].wrapping_add(other.data[i]));
+        }
+        result
+    }
+}
+
+impl<const LOG_Q: u32, const N: usize> Sub for PowerOfTwoRing<LOG_Q, N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        let mut result = Self::new();
+        for i in 0..N {
+            result.data[i] = Self::reduce(self.data[i].wrapping_sub(other.data[i]));
+        }
+        result
+    }
+}
+
+impl<const LOG_Q: u32, const N: usize> Mul for PowerOfTwoRing<LOG_Q, N> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        karatsuba_multiply_power_of_two(&self, &other)
+    }
+}
+
+impl<const LOG_Q: u32, const N: usize> Default for PowerOfTwoRing<LOG_Q, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const LOG_Q: u32, const N: usize> PowerOfTwoRing<LOG_Q, N> {
+    pub const MASK: u64 = if LOG_Q == 64 { u64::MAX } else { (1u64 << LOG_Q) - 1 };
+
+    /// Reduction mod 2^k of a wrapped u64.
+    pub const fn reduce(value: u64) -> u64 {
+        value & Self::MASK
+    }
+
+    pub fn new() -> Self {
+        assert!(LOG_Q >= 1 && LOG_Q <= 64, "power-of-two modulus 2^{LOG_Q} must fit in 64 bits");
+        Self { data: [0u64; N] }
+    }
+
+    pub fn random() -> Self {
+        let mut rng = rand::rng();
+        let mut result = Self::new();
+        for i in 0..N {
+            result.data[i] = rng.random::<u64>() & Self::MASK;
+        }
+        result
+    }
+
+    /// Coefficients uniform in `(-bound, bound)`.
+    pub fn random_bounded(bound: u64) -> Self {
+        let mut rng = rand::rng();
+        let mut result = Self::new();
+        for i in 0..N {
+            let value = rng.random_range(0..bound);
+            result.data[i] = if rng.random_bool(0.5) { value.wrapping_neg() & Self::MASK } else { value };
+        }
+        result
+    }
+
+    pub fn constant(value: u64) -> Self {
+        let mut result = Self::new();
+        result.data[0] = value & Self::MASK;
+        result
+    }
+
+    pub fn one() -> Self {
+        Self::constant(1)
+    }
+
+    /// The automorphism X ↦ X^{-1}.
+    pub fn conjugate(&self) -> Self {
+        let mut conjugated = *self;
+        for i in 1..N {
+            conjugated.data[i] = self.data[N - i].wrapping_neg() & Self::MASK;
+        }
+        conjugated
+    }
+
+    pub fn scalar_mul(&self, scalar: u64) -> Self {
+        let mut result = *self;
+        for c in result.data.iter_mut() {
+            *c = c.wrapping_mul(scalar) & Self::MASK;
+        }
+        result
+    }
+
+    /// Coefficients as centered representatives in `[-2^(k-1), 2^(k-1))`.
+    pub fn centered_coefficients(&self) -> [i64; N] {
+        // Sign-extending from bit k - 1 gives the centered representative.
+        let shift = 64 - LOG_Q;
+        self.data.map(|c| ((c << shift) as i64) >> shift)
+    }
+}
+
+/// Karatsuba over Z_{2^64}, masked to k bits at the end.
+pub fn karatsuba_multiply_power_of_two<const LOG_Q: u32, const N: usize>(
+    operand1: &PowerOfTwoRing<LOG_Q, N>,
+    operand2: &PowerOfTwoRing<LOG_Q, N>,
+) -> PowerOfTwoRing<LOG_Q, N> {
+    let product = karatsuba(Wrapping, &operand1.data, &operand2.data);
+    let mut result = PowerOfTwoRing::<LOG_Q, N>::new();
+    negacyclic_fold(Wrapping, &product, &mut result.data);
+    for c in result.data.iter_mut() {
+        *c &= PowerOfTwoRing::<LOG_Q, N>::MASK;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra::PolynomialRing;
+    use crate::cyclotomic_ring::{naive_multiply, CyclotomicRing};
+
+    const LOG_Q: u32 = 32;
+    const N: usize = 64;
+
+    #[test]
+    fn test_multiplication_matches_naive() {
+        let a = PowerOfTwoRing::<LOG_Q, N>::random();
+        let b = PowerOfTwoRing::<LOG_Q, N>::random();
+        let mut a_wide = CyclotomicRing::<{ 1 << LOG_Q }, N>::new();
+        let mut b_wide = CyclotomicRing::<{ 1 << LOG_Q }, N>::new();
+        a_wide.data = a.data;
+        b_wide.data = b.data;
+
+        assert_eq!((a * b).data, naive_multiply(&mut a_wide, &mut b_wide).data);
+    }
+
+    #[test]
+    fn test_full_width_modulus() {
+        let a = PowerOfTwoRing::<64, N>::random();
+        let one = PowerOfTwoRing::<64, N>::one();
+        assert_eq!(a * one, a);
+        assert_eq!(a - a, PowerOfTwoRing::new());
+    }
+
+    #[test]
+    fn test_conjugate_and_norms() {
+        let mut a = PowerOfTwoRing::<LOG_Q, N>::new();
+        a.data[1] = 5;
+        a.data[2] = PowerOfTwoRing::<LOG_Q, N>::MASK; // -1
+        let conjugated = a.conjugate();
+        assert_eq!(conjugated.centered_coefficients()[N - 1], -5);
+        assert_eq!(conjugated.centered_coefficients()[N - 2], 1);
+        assert_eq!(a.infinity_norm(), 5);
+        assert_eq!(a.l2_norm_squared(), 26);
+    }
+
+    #[test]
+    fn test_decomposition() {
+        let mut a = PowerOfTwoRing::<LOG_Q, N>::random();
+        a.data[0] = 1 << (LOG_Q - 1); // -2^31 needs a carry out of the top digit
+        let parts = a.decompose(1 << 8, 4);
+        assert!(parts.iter().all(|part| part.infinity_norm() <= 1 << 7));
+        assert_eq!(PowerOfTwoRing::recompose(&parts, 1 << 8), a);
+    }
+}
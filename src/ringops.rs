@@ -1,7 +1,7 @@
 use std::marker::ConstParamTy;
 
 #[cfg(feature = "hexl")]
-use crate::hexl::bindings::{ntt_forward_in_place, ntt_inverse_in_place, eltwise_mult_mod};
+use crate::hexl::safe::{ntt_forward, ntt_inverse, eltwise_mult_mod};
 #[cfg(feature = "tfhe")]
 use tfhe_ntt::*;
 use once_cell::sync::OnceCell;
@@ -23,26 +23,14 @@ pub struct NTT<const MOD_Q: u64, const N: usize>;
 #[cfg(feature = "hexl")]
 impl<const MOD_Q: u64, const N: usize> RingOps<MOD_Q, N> for NTT<MOD_Q, N> { 
     fn fwd(data: &mut [u64]) {
-        unsafe {
-            ntt_forward_in_place(data.as_mut_ptr(), N, MOD_Q)
-        }
+        ntt_forward(&mut data[..N], MOD_Q).expect("forward NTT")
     }
     fn inv(data: &mut [u64]) {
-        unsafe {
-            ntt_inverse_in_place(data.as_mut_ptr(), N, MOD_Q)
-        }
+        ntt_inverse(&mut data[..N], MOD_Q).expect("inverse NTT")
     }
 
     fn multiply(result: &mut [u64], left: &mut [u64], right: &mut [u64]) {
-        unsafe {
-            eltwise_mult_mod(
-                result.as_mut_ptr(),
-                left.as_ptr(),
-                right.as_ptr(),
-                N as u64,
-                MOD_Q
-            )
-        }
+        eltwise_mult_mod(&mut result[..N], &left[..N], &right[..N], MOD_Q).expect("slot-wise multiplication")
     }
 }
 
@@ -0,0 +1,166 @@
+//! Modulus switching and the rounding decompositions used to compress
+//! commitments (Power2Round and HighBits/LowBits, as in Dilithium).
+//!
+//! All functions take coefficient-form or NTT-form inputs and return
+//! coefficient-form outputs. "Small" outputs are stored as ring elements
+//! whose centered coefficients carry the signed value.
+
+use crate::cyclotomic_ring::CyclotomicRing;
+use crate::modular::centered;
+
+/// Maps the centered integer `value` into `[0, q)`.
+fn from_centered<const MOD_Q: u64>(value: i64) -> u64 {
+    (value as i128).rem_euclid(MOD_Q as i128) as u64
+}
+
+/// `round(c · q2 / q1)` for every centered coefficient `c`, reduced mod q2.
+/// Each output coefficient is within 1/2 of the exact scaled value, so
+/// `switch(a) - (q2/q1)·a` has infinity norm at most 1/2 before reduction.
+pub fn switch_modulus<const Q1: u64, const Q2: u64, const N: usize>(
+    operand: &CyclotomicRing<Q1, N>,
+) -> CyclotomicRing<Q2, N> {
+    let mut result = CyclotomicRing::<Q2, N>::new();
+    for (out, c) in result.data.iter_mut().zip(operand.centered_coefficients()) {
+        // |c · q2| < 2^63 · 2^64, which fits in an i128.
+        let scaled = c as i128 * Q2 as i128;
+        let mut rounded = scaled.div_euclid(Q1 as i128);
+        if 2 * scaled.rem_euclid(Q1 as i128) >= Q1 as i128 {
+            rounded += 1;
+        }
+        *out = rounded.rem_euclid(Q2 as i128) as u64;
+    }
+    result
+}
+
+/// Splits every coefficient as `r = r1 · 2^d + r0` with `r0` centered in
+/// `(-2^(d-1), 2^(d-1)]`. Returns `(r1, r0)`; `r1` has coefficients in
+/// `[0, ⌈q / 2^d⌉]`.
+pub fn power2round<const MOD_Q: u64, const N: usize>(
+    operand: &CyclotomicRing<MOD_Q, N>,
+    d: u32,
+) -> (CyclotomicRing<MOD_Q, N>, CyclotomicRing<MOD_Q, N>) {
+    assert!((1..63).contains(&d), "rounding bit count {d} out of range");
+    let mut coefficients = *operand;
+    coefficients.to_coeff_representation();
+    let (mut high, mut low) = (CyclotomicRing::new(), CyclotomicRing::new());
+    for i in 0..N {
+        let r = coefficients.data[i];
+        let r0 = centered(r & ((1 << d) - 1), 1 << d);
+        high.data[i] = (r as i64 - r0) as u64 >> d;
+        low.data[i] = from_centered::<MOD_Q>(r0);
+    }
+    (high, low)
+}
+
+/// Splits every coefficient as `r = r1 · alpha + r0` with `r0` centered in
+/// `(-alpha/2, alpha/2]` and `r1` in `[0, (q-1)/alpha)`. As in Dilithium,
+/// the corner case `r - r0 = q - 1` is folded into `r1 = 0, r0 = r0 - 1`,
+/// so `r1` never wraps. `alpha` must be even and divide `q - 1`.
+pub fn decompose_high_low<const MOD_Q: u64, const N: usize>(
+    operand: &CyclotomicRing<MOD_Q, N>,
+    alpha: u64,
+) -> (CyclotomicRing<MOD_Q, N>, CyclotomicRing<MOD_Q, N>) {
+    assert!(alpha.is_multiple_of(2) && (MOD_Q - 1).is_multiple_of(alpha), "alpha = {alpha} must be even and divide q - 1");
+    let mut coefficients = *operand;
+    coefficients.to_coeff_representation();
+    let (mut high, mut low) = (CyclotomicRing::new(), CyclotomicRing::new());
+    for i in 0..N {
+        let r = coefficients.data[i];
+        let mut r0 = centered(r % alpha, alpha);
+        let r1 = if r as i64 - r0 == (MOD_Q - 1) as i64 {
+            r0 -= 1;
+            0
+        } else {
+            (r as i64 - r0) as u64 / alpha
+        };
+        high.data[i] = r1;
+        low.data[i] = from_centered::<MOD_Q>(r0);
+    }
+    (high, low)
+}
+
+pub fn high_bits<const MOD_Q: u64, const N: usize>(
+    operand: &CyclotomicRing<MOD_Q, N>,
+    alpha: u64,
+) -> CyclotomicRing<MOD_Q, N> {
+    decompose_high_low(operand, alpha).0
+}
+
+pub fn low_bits<const MOD_Q: u64, const N: usize>(
+    operand: &CyclotomicRing<MOD_Q, N>,
+    alpha: u64,
+) -> CyclotomicRing<MOD_Q, N> {
+    decompose_high_low(operand, alpha).1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra::PolynomialRing;
+
+    const MOD_Q: u64 = 1125899904679937;
+    const MOD_Q_SMALL: u64 = 1073479681;
+    const N: usize = 64;
+    // q - 1 = 2^16 · 45737 · 375623.
+    const ALPHA: u64 = 1 << 16;
+
+    #[test]
+    fn test_switch_modulus_error_bound() {
+        let a = CyclotomicRing::<MOD_Q, N>::random();
+        let switched = switch_modulus::<MOD_Q, MOD_Q_SMALL, N>(&a);
+        for (c, s) in a.centered_coefficients().into_iter().zip(switched.centered_coefficients()) {
+            // |s - c · q2/q1| ≤ 1/2, scaled by q1.
+            let error = (s as i128 * MOD_Q as i128 - c as i128 * MOD_Q_SMALL as i128).abs();
+            assert!(error <= MOD_Q as i128 / 2 + 1, "rounding error {error} too large");
+        }
+
+        // Switching is additive up to one unit of rounding per summand.
+        let b = CyclotomicRing::<MOD_Q, N>::random_bounded(1 << 40);
+        let small_a = CyclotomicRing::<MOD_Q, N>::random_bounded(1 << 40);
+        let defect = switch_modulus::<MOD_Q, MOD_Q_SMALL, N>(&(small_a + b))
+            - switch_modulus::<MOD_Q, MOD_Q_SMALL, N>(&small_a)
+            - switch_modulus::<MOD_Q, MOD_Q_SMALL, N>(&b);
+        assert!(defect.infinity_norm() <= 1);
+    }
+
+    #[test]
+    fn test_power2round() {
+        const D: u32 = 13;
+        let a = CyclotomicRing::<MOD_Q, N>::random();
+        let (high, low) = power2round(&a, D);
+        assert!(low.infinity_norm() <= 1 << (D - 1));
+        assert!(high.data.iter().all(|&r1| r1 <= MOD_Q.div_ceil(1 << D)));
+        assert_eq!(high.scalar_mul(1 << D) + low, a);
+    }
+
+    #[test]
+    fn test_high_low_bits() {
+        let a = CyclotomicRing::<MOD_Q, N>::random();
+        let (high, low) = decompose_high_low(&a, ALPHA);
+        assert!(low.infinity_norm() <= ALPHA / 2);
+        assert!(high.data.iter().all(|&r1| r1 < (MOD_Q - 1) / ALPHA));
+        assert_eq!(high.scalar_mul(ALPHA) + low, a);
+
+        // The corner case r = q - 1 lands in r1 = 0.
+        let mut top = CyclotomicRing::<MOD_Q, N>::new();
+        top.data[0] = MOD_Q - 1;
+        assert_eq!(high_bits(&top, ALPHA).data[0], 0);
+        assert_eq!(low_bits(&top, ALPHA).centered_coefficients()[0], -1);
+    }
+
+    #[test]
+    fn test_high_bits_stable_under_small_shift() {
+        // If ‖LowBits(r)‖ < alpha/2 - β and ‖s‖ ≤ β, HighBits(r + s) = HighBits(r).
+        const BETA: u64 = 1 << 8;
+        let s = CyclotomicRing::<MOD_Q, N>::random_bounded(BETA);
+        let mut r = CyclotomicRing::<MOD_Q, N>::random();
+        for i in 0..N {
+            let low = centered(r.data[i] % ALPHA, ALPHA);
+            if low.unsigned_abs() >= ALPHA / 2 - BETA {
+                r.data[i] = (r.data[i] as i64 - low) as u64;
+            }
+        }
+        assert!(low_bits(&r, ALPHA).infinity_norm() < ALPHA / 2 - BETA);
+        assert_eq!(high_bits(&(r + s), ALPHA), high_bits(&r, ALPHA));
+    }
+}
@@ -0,0 +1,173 @@
+//! SHAKE128 (FIPS 202) on a local Keccak-f[1600], for the Fiat–Shamir
+//! transcript. Absorb any number of times, then squeeze any number of
+//! times; the padding is applied at the first squeeze.
+
+const RATE: usize = 168;
+const SHAKE_PADDING: u8 = 0x1f;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Rotation offsets and destinations of the combined ρ and π steps,
+/// following the lane visited before.
+const RHO: [u32; 24] = [1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44];
+const PI: [usize; 24] = [10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1];
+
+/// Keccak-f[1600] on lanes indexed x + 5y.
+pub fn keccak_f1600(state: &mut [u64; 25]) {
+    for &round_constant in &ROUND_CONSTANTS {
+        // θ
+        let mut parity = [0u64; 5];
+        for x in 0..5 {
+            parity[x] = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+        }
+        for x in 0..5 {
+            let d = parity[(x + 4) % 5] ^ parity[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[5 * y + x] ^= d;
+            }
+        }
+        // ρ and π
+        let mut carried = state[1];
+        for (&destination, &rotation) in PI.iter().zip(&RHO) {
+            let next = state[destination];
+            state[destination] = carried.rotate_left(rotation);
+            carried = next;
+        }
+        // χ
+        for y in 0..5 {
+            let row = [state[5 * y], state[5 * y + 1], state[5 * y + 2], state[5 * y + 3], state[5 * y + 4]];
+            for x in 0..5 {
+                state[5 * y + x] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+        // ι
+        state[0] ^= round_constant;
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Shake128 {
+    state: [u64; 25],
+    /// Next byte of the rate to absorb into or squeeze from.
+    position: usize,
+    squeezing: bool,
+}
+
+impl Default for Shake128 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shake128 {
+    pub fn new() -> Self {
+        Self { state: [0; 25], position: 0, squeezing: false }
+    }
+
+    fn xor_byte(&mut self, index: usize, byte: u8) {
+        self.state[index / 8] ^= (byte as u64) << (8 * (index % 8));
+    }
+
+    fn byte(&self, index: usize) -> u8 {
+        (self.state[index / 8] >> (8 * (index % 8))) as u8
+    }
+
+    pub fn absorb(&mut self, data: &[u8]) {
+        assert!(!self.squeezing, "cannot absorb after squeezing");
+        for &byte in data {
+            self.xor_byte(self.position, byte);
+            self.position += 1;
+            if self.position == RATE {
+                keccak_f1600(&mut self.state);
+                self.position = 0;
+            }
+        }
+    }
+
+    pub fn squeeze(&mut self, output: &mut [u8]) {
+        if !self.squeezing {
+            self.xor_byte(self.position, SHAKE_PADDING);
+            self.xor_byte(RATE - 1, 0x80);
+            keccak_f1600(&mut self.state);
+            self.position = 0;
+            self.squeezing = true;
+        }
+        for byte in output.iter_mut() {
+            if self.position == RATE {
+                keccak_f1600(&mut self.state);
+                self.position = 0;
+            }
+            *byte = self.byte(self.position);
+            self.position += 1;
+        }
+    }
+
+    /// SHAKE128(`data`) truncated to `len` bytes.
+    pub fn digest(data: &[u8], len: usize) -> Vec<u8> {
+        let mut shake = Self::new();
+        shake.absorb(data);
+        let mut output = vec![0u8; len];
+        shake.squeeze(&mut output);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    fn test_shake128_vectors() {
+        assert_eq!(
+            hex(&Shake128::digest(b"", 32)),
+            "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26"
+        );
+        assert_eq!(
+            hex(&Shake128::digest(b"abc", 32)),
+            "5881092dd818bf5cf8a3ddb793fbcba74097d5c526a6d35f97b83351940f2cc8"
+        );
+
+        // Input and output both span more than one 168-byte block, absorbed
+        // and squeezed in uneven pieces.
+        let message: Vec<u8> = (0..200).map(|i| i as u8).collect();
+        let mut shake = Shake128::new();
+        shake.absorb(&message[..7]);
+        shake.absorb(&message[7..]);
+        let mut output = vec![0u8; 300];
+        let (head, tail) = output.split_at_mut(100);
+        shake.squeeze(head);
+        shake.squeeze(tail);
+        assert_eq!(hex(&output[..32]), "0c4234ca1e31801ae606f8b8d8e0665c66f42a21d601c2681858a92c79ad5d69");
+        assert_eq!(hex(&output[268..]), "9b1f345feebde0f271a418c12e126fbe086095b9433e06a84f609a0c91793cc7");
+        assert_eq!(output, Shake128::digest(&message, 300));
+    }
+}
@@ -0,0 +1,264 @@
+//! CRT slot packing.
+//!
+//! In NTT form an element of Z_q[X]/(X^N + 1) is the vector of its values
+//! at the N roots of X^N + 1, and in the 2-way incomplete form it is N/2
+//! elements c0 + c1·X of F_q[X]/(X^2 - s_i), where the s_i are the roots of
+//! Y^(N/2) + 1. Addition and multiplication in these forms already act slot
+//! by slot; this module adds encoding, decoding and rotation.
+//!
+//! HEXL stores slots in its own (bit-reversed) order. Encoding uses a
+//! canonical order instead, read off from NTT(X), the roots themselves:
+//! with ζ the root in HEXL's first slot, canonical slot t holds the root
+//! ζ^(5^t) in the first half and ζ^(-5^t) in the second. The automorphism
+//! X ↦ X^(5^k) then rotates each half by k slots. The incomplete form is
+//! ordered the same way by its roots s_i.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::cyclotomic_ring::{get_shift_factors_cached_for, CyclotomicRing, Representation};
+use crate::modular::{mul_mod, pow_mod};
+
+/// Canonical slot order for one modulus and degree.
+#[derive(Debug)]
+pub struct SlotLayout {
+    /// HEXL position of every canonical NTT slot.
+    pub ntt_positions: Vec<usize>,
+    /// HEXL position of every canonical incomplete-NTT slot.
+    pub incomplete_positions: Vec<usize>,
+    /// s_i of every HEXL incomplete-NTT position: X^2 = s_i in that slot.
+    pub incomplete_roots: Vec<u64>,
+}
+
+/// Positions of `base^(±5^t)`, t = 0, 1, ..., in `roots`; `order` is the
+/// multiplicative order of `base`.
+fn canonical_positions(roots: &[u64], base: u64, order: u64, modulus: u64) -> Vec<usize> {
+    let position: HashMap<u64, usize> = roots.iter().enumerate().map(|(i, &root)| (root, i)).collect();
+    let row = roots.len().div_ceil(2);
+    let mut positions = vec![0usize; roots.len()];
+    let mut exponent = 1u64;
+    for t in 0..row {
+        positions[t] = position[&pow_mod(base, exponent, modulus)];
+        if t + row < roots.len() {
+            positions[t + row] = position[&pow_mod(base, order - exponent, modulus)];
+        }
+        exponent = exponent * 5 % order;
+    }
+    positions
+}
+
+impl SlotLayout {
+    fn new<const MOD_Q: u64, const N: usize>() -> Self {
+        let ntt_positions = match CyclotomicRing::<MOD_Q, N>::PARAMS.check_ntt_degree(1) {
+            Ok(()) => {
+                let mut x = CyclotomicRing::<MOD_Q, N>::new();
+                x.data[1] = 1;
+                x.to_ntt_representation();
+                canonical_positions(&x.data, x.data[0], 2 * N as u64, MOD_Q)
+            }
+            Err(_) => Vec::new(),
+        };
+        let incomplete_roots = get_shift_factors_cached_for(MOD_Q, N / 2).to_vec();
+        let incomplete_positions = canonical_positions(&incomplete_roots, incomplete_roots[0], N as u64, MOD_Q);
+        Self { ntt_positions, incomplete_positions, incomplete_roots }
+    }
+}
+
+type SlotLayoutCache = Mutex<HashMap<(u64, usize), Arc<SlotLayout>>>;
+
+static SLOT_LAYOUT_CACHE: OnceLock<SlotLayoutCache> = OnceLock::new();
+
+/// The slot layout for `MOD_Q` and `N`. Needs q ≡ 1 mod N; the NTT slots
+/// are only available for q ≡ 1 mod 2N.
+pub fn slot_layout<const MOD_Q: u64, const N: usize>() -> Arc<SlotLayout> {
+    if let Err(error) = CyclotomicRing::<MOD_Q, N>::PARAMS.check_ntt_degree(2) {
+        panic!("no slot structure: {error}");
+    }
+    let cache = SLOT_LAYOUT_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    cache
+        .lock()
+        .unwrap()
+        .entry((MOD_Q, N))
+        .or_insert_with(|| Arc::new(SlotLayout::new::<MOD_Q, N>()))
+        .clone()
+}
+
+fn ntt_positions<const MOD_Q: u64, const N: usize>() -> Arc<SlotLayout> {
+    let layout = slot_layout::<MOD_Q, N>();
+    assert!(!layout.ntt_positions.is_empty(), "q = {MOD_Q} has no fully splitting NTT for N = {N}");
+    layout
+}
+
+/// The element whose NTT slots hold `values`, in canonical order.
+pub fn encode_ntt_slots<const MOD_Q: u64, const N: usize>(values: &[u64]) -> CyclotomicRing<MOD_Q, N> {
+    assert_eq!(values.len(), N, "expected one value per slot");
+    let layout = ntt_positions::<MOD_Q, N>();
+    let mut result = CyclotomicRing::<MOD_Q, N>::new();
+    for (&position, &value) in layout.ntt_positions.iter().zip(values) {
+        result.data[position] = value % MOD_Q;
+    }
+    result.representation = Representation::NTT;
+    result
+}
+
+pub fn decode_ntt_slots<const MOD_Q: u64, const N: usize>(element: &CyclotomicRing<MOD_Q, N>) -> Vec<u64> {
+    let layout = ntt_positions::<MOD_Q, N>();
+    let mut slots = *element;
+    slots.to_ntt_representation();
+    layout.ntt_positions.iter().map(|&position| slots.data[position]).collect()
+}
+
+/// The element whose incomplete-NTT slots hold `values[t] = [c0, c1]`,
+/// i.e. c0 + c1·X in slot t, in canonical order.
+pub fn encode_incomplete_slots<const MOD_Q: u64, const N: usize>(values: &[[u64; 2]]) -> CyclotomicRing<MOD_Q, N> {
+    assert_eq!(values.len(), N / 2, "expected one value per slot");
+    let layout = slot_layout::<MOD_Q, N>();
+    let mut result = CyclotomicRing::<MOD_Q, N>::new();
+    for (&position, &[c0, c1]) in layout.incomplete_positions.iter().zip(values) {
+        result.data[position] = c0 % MOD_Q;
+        result.data[position + N / 2] = c1 % MOD_Q;
+    }
+    result.representation = Representation::IncompleteNTT;
+    result
+}
+
+pub fn decode_incomplete_slots<const MOD_Q: u64, const N: usize>(
+    element: &CyclotomicRing<MOD_Q, N>,
+) -> Vec<[u64; 2]> {
+    let layout = slot_layout::<MOD_Q, N>();
+    let mut slots = *element;
+    slots.to_incomplete_ntt_representation();
+    layout
+        .incomplete_positions
+        .iter()
+        .map(|&position| [slots.data[position], slots.data[position + N / 2]])
+        .collect()
+}
+
+/// Canonical index `steps` further along the same half.
+fn rotated(t: usize, steps: usize, slot_count: usize) -> usize {
+    let row = slot_count.div_ceil(2);
+    let (start, offset) = if t < row { (0, t) } else { (row, t - row) };
+    let length = if t < row { row } else { slot_count - row };
+    start + (offset + steps) % length
+}
+
+/// Applies X ↦ X^(5^steps), which moves canonical slot t + steps to slot t
+/// within each half. In NTT form this is a permutation of the slots. In
+/// the incomplete form the slots are different fields F_q[X]/(X^2 - s_i),
+/// and moving a value between them also scales its X-coefficient by
+/// s_i^((5^steps - 1)/2). Coefficient-form inputs are rotated in NTT form
+/// if the modulus allows it and in the incomplete form otherwise; the
+/// result keeps the input's representation.
+pub fn rotate_slots<const MOD_Q: u64, const N: usize>(
+    element: &CyclotomicRing<MOD_Q, N>,
+    steps: usize,
+) -> CyclotomicRing<MOD_Q, N> {
+    let layout = slot_layout::<MOD_Q, N>();
+    let g = (0..steps).fold(1u64, |g, _| g * 5 % (2 * N as u64));
+    let mut source = element.reduced();
+    let mut result = CyclotomicRing::<MOD_Q, N>::new();
+    let use_ntt = match element.representation {
+        Representation::NTT => true,
+        Representation::IncompleteNTT => false,
+        Representation::Coefficient => !layout.ntt_positions.is_empty(),
+    };
+    if use_ntt {
+        source.to_ntt_representation();
+        let positions = &layout.ntt_positions;
+        for t in 0..N {
+            result.data[positions[t]] = source.data[positions[rotated(t, steps, N)]];
+        }
+        result.representation = Representation::NTT;
+    } else {
+        source.to_incomplete_ntt_representation();
+        let positions = &layout.incomplete_positions;
+        let twist_exponent = (g - 1) / 2;
+        for t in 0..N / 2 {
+            let (to, from) = (positions[t], positions[rotated(t, steps, N / 2)]);
+            let twist = pow_mod(layout.incomplete_roots[to], twist_exponent, MOD_Q);
+            result.data[to] = source.data[from];
+            result.data[to + N / 2] = mul_mod(source.data[from + N / 2], twist, MOD_Q);
+        }
+        result.representation = Representation::IncompleteNTT;
+    }
+    if element.representation == Representation::Coefficient {
+        result.to_coeff_representation();
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cyclotomic_ring::multiply;
+    use rand::Rng;
+
+    const MOD_Q: u64 = 1125899904679937;
+    // q ≡ 65 mod 128: only the 2-way incomplete NTT exists for N = 64.
+    const MOD_Q_INCOMPLETE: u64 = 1125899906842817;
+    const N: usize = 64;
+
+    fn random_values(len: usize, modulus: u64) -> Vec<u64> {
+        let mut rng = rand::rng();
+        (0..len).map(|_| rng.random_range(0..modulus)).collect()
+    }
+
+    #[test]
+    fn test_ntt_slots_are_slot_wise() {
+        let (u, v) = (random_values(N, MOD_Q), random_values(N, MOD_Q));
+        let (a, b) = (encode_ntt_slots::<MOD_Q, N>(&u), encode_ntt_slots::<MOD_Q, N>(&v));
+        assert_eq!(decode_ntt_slots(&a), u);
+
+        let sum = decode_ntt_slots(&(a + b));
+        let product = decode_ntt_slots(&multiply(&mut a.clone(), &mut b.clone()));
+        for t in 0..N {
+            assert_eq!(sum[t], (u[t] + v[t]) % MOD_Q);
+            assert_eq!(product[t], mul_mod(u[t], v[t], MOD_Q));
+        }
+    }
+
+    #[test]
+    fn test_ntt_rotation_is_an_automorphism() {
+        let u = random_values(N, MOD_Q);
+        let a = encode_ntt_slots::<MOD_Q, N>(&u);
+        for steps in [1, 5, N / 2] {
+            let rotated_slots = decode_ntt_slots(&rotate_slots(&a, steps));
+            for t in 0..N {
+                assert_eq!(rotated_slots[t], u[rotated(t, steps, N)]);
+            }
+            let g = (0..steps).fold(1, |g, _| g * 5 % (2 * N));
+            assert_eq!(rotate_slots(&a, steps), a.automorphism(g));
+        }
+        // Conjugation swaps the two halves.
+        let conjugated = decode_ntt_slots(&a.conjugate());
+        assert_eq!(conjugated[..N / 2], u[N / 2..]);
+    }
+
+    #[test]
+    fn test_incomplete_slots() {
+        let values: Vec<[u64; 2]> = (0..N / 2)
+            .map(|_| {
+                let v = random_values(2, MOD_Q_INCOMPLETE);
+                [v[0], v[1]]
+            })
+            .collect();
+        let a = encode_incomplete_slots::<MOD_Q_INCOMPLETE, N>(&values);
+        assert_eq!(decode_incomplete_slots(&a), values);
+
+        // Squaring X in slot t gives the root s_t.
+        let mut x = CyclotomicRing::<MOD_Q_INCOMPLETE, N>::new();
+        x.data[1] = 1;
+        let x_squared = decode_incomplete_slots(&multiply(&mut x.clone(), &mut x.clone()));
+        let layout = slot_layout::<MOD_Q_INCOMPLETE, N>();
+        assert_eq!(x_squared.len(), N / 2);
+        for (square, &position) in x_squared.iter().zip(&layout.incomplete_positions) {
+            assert_eq!(*square, [layout.incomplete_roots[position], 0]);
+        }
+
+        for steps in [1, 3] {
+            let g = (0..steps).fold(1, |g, _| g * 5 % (2 * N));
+            assert_eq!(rotate_slots(&a, steps), a.automorphism(g));
+        }
+    }
+}
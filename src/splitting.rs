@@ -0,0 +1,127 @@
+//! How X^N + 1 factors modulo q, and which multiplication strategy that
+//! allows.
+//!
+//! For an odd prime q and N a power of two, X^N + 1 splits into N / d
+//! irreducible factors of degree d, where d is the multiplicative order of
+//! q modulo 2N. The NTT-based multiplications need more than that: a
+//! d-way incomplete NTT evaluates in Y = X^d with a negacyclic NTT of size
+//! N / d, which requires q ≡ 1 mod 2N / d. The fully splitting NTT is the
+//! case d = 1.
+//!
+//! Everything here is a `const fn`, so the ring can fix its strategy at
+//! compile time.
+
+use std::fmt;
+
+use crate::hexl::safe::MAX_MODULUS_BITS;
+use crate::modular::{is_prime, order_mod_power_of_two};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Splitting {
+    /// Number of irreducible factors of X^N + 1 mod q.
+    pub slot_count: usize,
+    /// Degree of each factor.
+    pub slot_degree: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MultiplicationStrategy {
+    /// q ≡ 1 mod 2N: slot-wise products after a full NTT.
+    FullySplitting,
+    /// q ≡ 1 mod 2N / degree: products of degree-`degree` polynomials in
+    /// each slot of a size-N/degree NTT.
+    IncompleteNtt { degree: usize },
+    /// No NTT is available; multiply in the coefficient domain.
+    NonNtt,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StrategyError {
+    NotPrime(u64),
+    NoFastPath { modulus: u64, n: usize },
+}
+
+impl fmt::Display for StrategyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StrategyError::NotPrime(q) => write!(f, "modulus {q} is not an odd prime"),
+            StrategyError::NoFastPath { modulus, n } => write!(
+                f,
+                "no NTT-based multiplication for q = {modulus}, N = {n}: q - 1 has too few factors of two \
+                 (need q ≡ 1 mod 2N/d with d ≤ N/2) or q exceeds 2^{MAX_MODULUS_BITS}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StrategyError {}
+
+/// Factorization pattern of X^N + 1 modulo the odd prime `modulus`.
+pub const fn splitting(modulus: u64, n: usize) -> Result<Splitting, StrategyError> {
+    if modulus == 2 || !is_prime(modulus) {
+        return Err(StrategyError::NotPrime(modulus));
+    }
+    let slot_degree = order_mod_power_of_two(modulus, 2 * n as u64) as usize;
+    Ok(Splitting { slot_count: n / slot_degree, slot_degree })
+}
+
+/// Smallest power-of-two degree d with q ≡ 1 mod 2N / d, i.e. the least
+/// incomplete NTT that HEXL can compute for this modulus.
+pub const fn incomplete_degree(modulus: u64, n: usize) -> usize {
+    let two_n = 2 * n as u64;
+    let mut valuation = (modulus - 1).trailing_zeros();
+    if valuation > two_n.trailing_zeros() {
+        valuation = two_n.trailing_zeros();
+    }
+    (two_n >> valuation) as usize
+}
+
+/// Cheapest strategy for multiplying in Z_q[X]/(X^N + 1), falling back to
+/// coefficient-domain multiplication when no NTT is available.
+pub const fn select_strategy(modulus: u64, n: usize) -> MultiplicationStrategy {
+    match fast_strategy(modulus, n) {
+        Ok(strategy) => strategy,
+        Err(_) => MultiplicationStrategy::NonNtt,
+    }
+}
+
+/// Like [`select_strategy`], but reports why no NTT-based strategy exists.
+pub const fn fast_strategy(modulus: u64, n: usize) -> Result<MultiplicationStrategy, StrategyError> {
+    if let Err(error) = splitting(modulus, n) {
+        return Err(error);
+    }
+    if modulus >= 1 << MAX_MODULUS_BITS {
+        return Err(StrategyError::NoFastPath { modulus, n });
+    }
+    match incomplete_degree(modulus, n) {
+        1 => Ok(MultiplicationStrategy::FullySplitting),
+        // HEXL needs an NTT of size at least 2 in each of the d parts.
+        degree if degree <= n / 2 => Ok(MultiplicationStrategy::IncompleteNtt { degree }),
+        _ => Err(StrategyError::NoFastPath { modulus, n }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_splitting_patterns() {
+        assert_eq!(splitting(17, 8), Ok(Splitting { slot_count: 8, slot_degree: 1 }));
+        assert_eq!(splitting(41, 8), Ok(Splitting { slot_count: 4, slot_degree: 2 }));
+        assert_eq!(splitting(97, 64), Ok(Splitting { slot_count: 16, slot_degree: 4 }));
+        // q ≡ -1 mod 2N splits into quadratics, but no power-of-two NTT exists.
+        assert_eq!(splitting(31, 16), Ok(Splitting { slot_count: 8, slot_degree: 2 }));
+        assert_eq!(splitting(1 << 20, 16), Err(StrategyError::NotPrime(1 << 20)));
+    }
+
+    #[test]
+    fn test_strategy_selection() {
+        assert_eq!(fast_strategy(17, 8), Ok(MultiplicationStrategy::FullySplitting));
+        assert_eq!(fast_strategy(41, 8), Ok(MultiplicationStrategy::IncompleteNtt { degree: 2 }));
+        assert_eq!(fast_strategy(97, 64), Ok(MultiplicationStrategy::IncompleteNtt { degree: 4 }));
+        assert_eq!(fast_strategy(31, 16), Err(StrategyError::NoFastPath { modulus: 31, n: 16 }));
+        assert_eq!(select_strategy(31, 16), MultiplicationStrategy::NonNtt);
+        assert_eq!(select_strategy(1 << 20, 16), MultiplicationStrategy::NonNtt);
+    }
+}
@@ -0,0 +1,331 @@
+//! The sumcheck protocol for Σ_{x ∈ {0,1}^μ} g(x) with
+//! g(x) = combine(f_1(x), ..., f_t(x)), where the f_j are multilinear and
+//! given by their evaluation tables on the hypercube and `combine` is a
+//! polynomial of total degree d, e.g. f_1·f_2 - f_3 or eq·(f^2 - f).
+//!
+//! Round i sends g_i(X) = Σ g(r_1, ..., r_(i-1), X, x_(i+1), ..., x_μ) as
+//! its evaluations at 0, 1, ..., d. The verifier checks
+//! g_i(0) + g_i(1) against the running claim, samples r_i and continues with
+//! the claim g_i(r_i). After μ rounds the claim g(r) is left to the caller,
+//! who checks it against openings of the f_j.
+//!
+//! Variable x_1 is the lowest bit of a table index, so fixing it folds
+//! adjacent pairs. Everything is generic over [`RingElement`]: challenges
+//! live in the same ring as the tables, F_{q^K} for soundness over small q
+//! or R_q itself.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::algebra::RingElement;
+use crate::modular::{inv_mod, mul_mod};
+
+/// A univariate round polynomial by its values at 0, 1, ..., degree.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoundPolynomial<R> {
+    pub evaluations: Vec<R>,
+}
+
+impl<R: RingElement> RoundPolynomial<R> {
+    pub fn degree(&self) -> usize {
+        self.evaluations.len() - 1
+    }
+
+    /// Lagrange interpolation through the nodes 0, ..., d. The weights
+    /// 1/Π_(j≠i)(i - j) are integers, inverted in Z_q, so d < q is needed.
+    pub fn evaluate(&self, point: R) -> R {
+        let d = self.degree();
+        let differences: Vec<R> = (0..=d as u64).map(|j| point - R::from_u64(j)).collect();
+        // suffix[i] = Π_(j ≥ i) (point - j)
+        let mut suffix = vec![R::one(); d + 2];
+        for j in (0..=d).rev() {
+            suffix[j] = suffix[j + 1] * differences[j];
+        }
+        let mut prefix = R::one();
+        let mut result = R::zero();
+        for (i, &value) in self.evaluations.iter().enumerate() {
+            // Π_(j≠i) (i - j) = (-1)^(d-i) · i! · (d-i)!
+            let mut denominator = 1u64;
+            for j in 0..=d as u64 {
+                if j != i as u64 {
+                    let difference = (i as u64 + R::MODULUS - j) % R::MODULUS;
+                    denominator = mul_mod(denominator, difference, R::MODULUS);
+                }
+            }
+            let weight = inv_mod(denominator, R::MODULUS).expect("interpolation nodes must be distinct mod q");
+            result = result + (value * prefix * suffix[i + 1]).scalar_mul(weight);
+            prefix = prefix * differences[i];
+        }
+        result
+    }
+}
+
+/// The round polynomials of one run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SumcheckProof<R> {
+    pub rounds: Vec<RoundPolynomial<R>>,
+}
+
+/// What is left after the last round: g(point) must equal `value`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubClaim<R> {
+    pub point: Vec<R>,
+    pub value: R,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SumcheckError {
+    WrongRoundCount { expected: usize, actual: usize },
+    DegreeTooHigh { round: usize, degree: usize, max_degree: usize },
+    /// g_i(0) + g_i(1) differs from the claim of the previous round.
+    RoundSumMismatch { round: usize },
+}
+
+impl fmt::Display for SumcheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SumcheckError::WrongRoundCount { expected, actual } => {
+                write!(f, "expected {expected} sumcheck rounds, got {actual}")
+            }
+            SumcheckError::DegreeTooHigh { round, degree, max_degree } => {
+                write!(f, "round {round} polynomial has degree {degree}, at most {max_degree} allowed")
+            }
+            SumcheckError::RoundSumMismatch { round } => {
+                write!(f, "round {round} polynomial does not sum to the claim")
+            }
+        }
+    }
+}
+
+impl Error for SumcheckError {}
+
+/// Prover state: the tables with the variables fixed so far.
+pub struct SumcheckProver<R, F> {
+    tables: Vec<Vec<R>>,
+    degree: usize,
+    combine: F,
+}
+
+impl<R: RingElement, F: Fn(&[R]) -> R> SumcheckProver<R, F> {
+    /// `tables` are the f_j on {0,1}^μ, all of length 2^μ; `degree` bounds
+    /// the total degree of `combine`. The tables are prepared for
+    /// multiplication here.
+    pub fn new(mut tables: Vec<Vec<R>>, degree: usize, combine: F) -> Self {
+        assert!(!tables.is_empty(), "sumcheck needs at least one table");
+        let len = tables[0].len();
+        assert!(len.is_power_of_two(), "table length {len} is not a power of two");
+        assert!(tables.iter().all(|table| table.len() == len), "tables of different lengths");
+        tables.iter_mut().flatten().for_each(R::prepare);
+        Self { tables, degree, combine }
+    }
+
+    /// Variables not fixed yet.
+    pub fn num_variables(&self) -> usize {
+        self.tables[0].len().trailing_zeros() as usize
+    }
+
+    /// Σ_x g(x) over the remaining variables.
+    pub fn sum(&self) -> R {
+        let mut values = vec![R::zero(); self.tables.len()];
+        let mut sum = R::zero();
+        for x in 0..self.tables[0].len() {
+            for (value, table) in values.iter_mut().zip(&self.tables) {
+                *value = table[x];
+            }
+            sum = sum + (self.combine)(&values);
+        }
+        sum
+    }
+
+    /// The polynomial in the first remaining variable.
+    pub fn round_polynomial(&self) -> RoundPolynomial<R> {
+        assert!(self.num_variables() > 0, "all variables are fixed");
+        let t = self.tables.len();
+        let mut evaluations = vec![R::zero(); self.degree + 1];
+        let mut values = vec![R::zero(); t];
+        let mut steps = vec![R::zero(); t];
+        for b in 0..self.tables[0].len() / 2 {
+            for j in 0..t {
+                let (low, high) = (self.tables[j][2 * b], self.tables[j][2 * b + 1]);
+                values[j] = low;
+                steps[j] = high - low;
+            }
+            // f_j(X, b) at X = 0, 1, 2, ... by repeated addition of the slope.
+            for evaluation in evaluations.iter_mut() {
+                *evaluation = *evaluation + (self.combine)(&values);
+                for (value, &step) in values.iter_mut().zip(&steps) {
+                    *value = *value + step;
+                }
+            }
+        }
+        RoundPolynomial { evaluations }
+    }
+
+    /// Sets the first remaining variable to `challenge`:
+    /// f(b) ← f(0, b) + challenge·(f(1, b) - f(0, b)).
+    pub fn fix_first_variable(&mut self, mut challenge: R) {
+        challenge.prepare();
+        for table in self.tables.iter_mut() {
+            let half = table.len() / 2;
+            for b in 0..half {
+                let (low, high) = (table[2 * b], table[2 * b + 1]);
+                table[b] = low + challenge * (high - low);
+            }
+            table.truncate(half);
+        }
+    }
+
+    /// f_j(r) for every table, once all variables are fixed.
+    pub fn final_evaluations(&self) -> Vec<R> {
+        assert_eq!(self.num_variables(), 0, "variables left to fix");
+        self.tables.iter().map(|table| table[0]).collect()
+    }
+
+    /// Runs all rounds, drawing r_i = `challenge(g_i)`. Returns the proof and
+    /// the point r.
+    pub fn prove(&mut self, mut challenge: impl FnMut(&RoundPolynomial<R>) -> R) -> (SumcheckProof<R>, Vec<R>) {
+        let mut rounds = Vec::with_capacity(self.num_variables());
+        let mut point = Vec::with_capacity(self.num_variables());
+        while self.num_variables() > 0 {
+            let round = self.round_polynomial();
+            let r = challenge(&round);
+            self.fix_first_variable(r);
+            rounds.push(round);
+            point.push(r);
+        }
+        (SumcheckProof { rounds }, point)
+    }
+}
+
+/// Checks the rounds of `proof` against `claimed_sum`, drawing challenges
+/// the same way as the prover. The returned claim g(point) = value is left
+/// to the caller.
+pub fn verify<R: RingElement>(
+    claimed_sum: R,
+    proof: &SumcheckProof<R>,
+    num_variables: usize,
+    degree: usize,
+    mut challenge: impl FnMut(&RoundPolynomial<R>) -> R,
+) -> Result<SubClaim<R>, SumcheckError> {
+    if proof.rounds.len() != num_variables {
+        return Err(SumcheckError::WrongRoundCount { expected: num_variables, actual: proof.rounds.len() });
+    }
+    let mut claim = claimed_sum;
+    let mut point = Vec::with_capacity(num_variables);
+    for (round, polynomial) in proof.rounds.iter().enumerate() {
+        if polynomial.evaluations.is_empty() || polynomial.degree() > degree {
+            let degree_found = polynomial.evaluations.len().saturating_sub(1);
+            return Err(SumcheckError::DegreeTooHigh { round, degree: degree_found, max_degree: degree });
+        }
+        let at_one = polynomial.evaluations.get(1).copied().unwrap_or(polynomial.evaluations[0]);
+        if polynomial.evaluations[0] + at_one != claim {
+            return Err(SumcheckError::RoundSumMismatch { round });
+        }
+        let r = challenge(polynomial);
+        claim = polynomial.evaluate(r);
+        point.push(r);
+    }
+    Ok(SubClaim { point, value: claim })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cyclotomic_ring::CyclotomicRing;
+    use crate::ext_field::ExtField;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const MOD_Q: u64 = 1125899904679937;
+    // q ≡ 65 mod 128: the 2-way incomplete NTT for N = 64.
+    const MOD_Q_INCOMPLETE: u64 = 1125899906842817;
+    const N: usize = 64;
+
+    /// f(r) for a multilinear f given on the hypercube, x_1 the lowest bit.
+    fn evaluate_multilinear<R: RingElement>(table: &[R], point: &[R]) -> R {
+        let mut table = table.to_vec();
+        for &r in point {
+            table = table.chunks(2).map(|pair| pair[0] + r * (pair[1] - pair[0])).collect();
+        }
+        table[0]
+    }
+
+    /// A combining function and its degree.
+    type Combination<R> = (usize, fn(&[R]) -> R);
+
+    /// Proves Σ f_1·f_2 - f_3 and Σ f_1·f_2·f_3 with a seeded verifier and
+    /// checks the final claims against direct evaluations.
+    fn check_sumcheck<R: RingElement>(num_variables: usize) {
+        let tables: Vec<Vec<R>> = (0..3).map(|_| (0..1 << num_variables).map(|_| R::random()).collect()).collect();
+        let combinations: [Combination<R>; 2] = [(2, |f| f[0] * f[1] - f[2]), (3, |f| f[0] * f[1] * f[2])];
+        for (degree, combine) in combinations {
+            let mut prover = SumcheckProver::new(tables.clone(), degree, combine);
+            let claimed_sum = prover.sum();
+            let mut prover_rng = StdRng::seed_from_u64(7);
+            let (proof, point) = prover.prove(|_| R::random_with(&mut prover_rng));
+
+            let mut verifier_rng = StdRng::seed_from_u64(7);
+            let subclaim = verify(claimed_sum, &proof, num_variables, degree, |_| R::random_with(&mut verifier_rng))
+                .expect("honest proof");
+            assert_eq!(subclaim.point, point);
+            let openings: Vec<R> = tables.iter().map(|table| evaluate_multilinear(table, &point)).collect();
+            assert_eq!(prover.final_evaluations(), openings);
+            assert_eq!(combine(&openings), subclaim.value);
+
+            let wrong_sum = claimed_sum + R::one();
+            let mut verifier_rng = StdRng::seed_from_u64(7);
+            assert_eq!(
+                verify(wrong_sum, &proof, num_variables, degree, |_| R::random_with(&mut verifier_rng)),
+                Err(SumcheckError::RoundSumMismatch { round: 0 })
+            );
+        }
+    }
+
+    #[test]
+    fn test_sumcheck_over_fields() {
+        check_sumcheck::<ExtField<MOD_Q, 1>>(5);
+        check_sumcheck::<ExtField<MOD_Q, 2>>(5);
+        check_sumcheck::<ExtField<MOD_Q, 4>>(3);
+    }
+
+    #[test]
+    fn test_sumcheck_over_rings() {
+        check_sumcheck::<CyclotomicRing<MOD_Q, N>>(4);
+        check_sumcheck::<CyclotomicRing<MOD_Q_INCOMPLETE, N>>(3);
+    }
+
+    #[test]
+    fn test_rejects_malformed_proofs() {
+        type F = ExtField<MOD_Q, 2>;
+        let table: Vec<F> = (0..8).map(|_| F::random()).collect();
+        let mut prover = SumcheckProver::new(vec![table.clone(), table], 2, |f: &[F]| f[0] * f[1]);
+        let claimed_sum = prover.sum();
+        let seeded = || {
+            let mut rng = StdRng::seed_from_u64(1);
+            move |_: &RoundPolynomial<F>| F::random_with(&mut rng)
+        };
+        let (mut proof, _) = prover.prove(seeded());
+        assert!(verify(claimed_sum, &proof, 3, 2, seeded()).is_ok());
+
+        assert_eq!(
+            verify(claimed_sum, &proof, 4, 2, seeded()),
+            Err(SumcheckError::WrongRoundCount { expected: 4, actual: 3 })
+        );
+        assert_eq!(
+            verify(claimed_sum, &proof, 3, 1, seeded()),
+            Err(SumcheckError::DegreeTooHigh { round: 0, degree: 2, max_degree: 1 })
+        );
+        // A later round that no longer matches the claim is caught there.
+        proof.rounds[1].evaluations[0] += F::one();
+        assert_eq!(
+            verify(claimed_sum, &proof, 3, 2, seeded()),
+            Err(SumcheckError::RoundSumMismatch { round: 1 })
+        );
+
+        // Interpolation agrees with direct evaluation of a known polynomial.
+        let cubic = |x: F| x * x * x + x.scalar_mul(5) + F::from_u64(3);
+        let polynomial = RoundPolynomial { evaluations: (0..4).map(|i| cubic(F::from_u64(i))).collect() };
+        let r = F::random();
+        assert_eq!(polynomial.evaluate(r), cubic(r));
+    }
+}
@@ -0,0 +1,316 @@
+//! Fiat–Shamir transcript over SHAKE128.
+//!
+//! Every message is absorbed as
+//! `kind ‖ len(label) ‖ label ‖ len(payload) ‖ payload`, lengths as u64
+//! little-endian, so no two different message sequences absorb the same
+//! bytes. Payloads are canonical: field elements and ring elements are
+//! written as their reduced coefficients (u64 little-endian) after the
+//! modulus and dimension, whatever representation a ring element is
+//! stored in.
+//!
+//! A challenge is squeezed from a copy of the sponge after absorbing the
+//! request, and then absorbed itself, so later challenges depend on it.
+//! Challenges that need more than a fixed number of bytes (uniform values
+//! mod q, short ring elements) read from a SHAKE128 stream seeded by one
+//! such 32-byte challenge.
+
+use rand::RngCore;
+
+use crate::challenge::ChallengeSet;
+use crate::cyclotomic_ring::CyclotomicRing;
+use crate::ext_field::ExtField;
+use crate::shake::Shake128;
+
+const PROTOCOL_LABEL: &[u8] = b"ring-arith transcript v1";
+const STREAM_LABEL: &[u8] = b"ring-arith transcript stream";
+
+/// The kind byte in front of every absorbed message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+enum Kind {
+    Domain = 0,
+    Bytes = 1,
+    U64 = 2,
+    Field = 3,
+    Ring = 4,
+    Commitment = 5,
+    ChallengeRequest = 6,
+    Challenge = 7,
+}
+
+#[derive(Clone, Debug)]
+pub struct Transcript {
+    sponge: Shake128,
+}
+
+impl Transcript {
+    /// A transcript for the protocol named `domain`; transcripts with
+    /// different domains never produce related challenges.
+    pub fn new(domain: &[u8]) -> Self {
+        let mut transcript = Self { sponge: Shake128::new() };
+        transcript.absorb(Kind::Domain, PROTOCOL_LABEL, domain);
+        transcript
+    }
+
+    fn absorb(&mut self, kind: Kind, label: &[u8], payload: &[u8]) {
+        self.sponge.absorb(&[kind as u8]);
+        self.sponge.absorb(&(label.len() as u64).to_le_bytes());
+        self.sponge.absorb(label);
+        self.sponge.absorb(&(payload.len() as u64).to_le_bytes());
+        self.sponge.absorb(payload);
+    }
+
+    pub fn append_bytes(&mut self, label: &[u8], bytes: &[u8]) {
+        self.absorb(Kind::Bytes, label, bytes);
+    }
+
+    pub fn append_u64(&mut self, label: &[u8], value: u64) {
+        self.absorb(Kind::U64, label, &value.to_le_bytes());
+    }
+
+    pub fn append_field<const MOD_Q: u64, const K: usize>(&mut self, label: &[u8], value: &ExtField<MOD_Q, K>) {
+        let payload = encode(MOD_Q, &[value.coefficients.as_slice()]);
+        self.absorb(Kind::Field, label, &payload);
+    }
+
+    pub fn append_ring<const MOD_Q: u64, const N: usize>(&mut self, label: &[u8], value: &CyclotomicRing<MOD_Q, N>) {
+        let payload = encode(MOD_Q, &[value.canonical_coefficients().as_slice()]);
+        self.absorb(Kind::Ring, label, &payload);
+    }
+
+    /// A commitment, i.e. a vector of ring elements, absorbed as one message.
+    pub fn append_commitment<const MOD_Q: u64, const N: usize>(
+        &mut self,
+        label: &[u8],
+        commitment: &[CyclotomicRing<MOD_Q, N>],
+    ) {
+        let coefficients: Vec<[u64; N]> = commitment.iter().map(|c| c.canonical_coefficients()).collect();
+        let parts: Vec<&[u64]> = coefficients.iter().map(|c| c.as_slice()).collect();
+        self.absorb(Kind::Commitment, label, &encode(MOD_Q, &parts));
+    }
+
+    /// Fills `output` with challenge bytes and absorbs them.
+    pub fn challenge_bytes(&mut self, label: &[u8], output: &mut [u8]) {
+        self.absorb(Kind::ChallengeRequest, label, &(output.len() as u64).to_le_bytes());
+        self.sponge.clone().squeeze(output);
+        self.absorb(Kind::Challenge, label, output);
+    }
+
+    /// A deterministic RNG for samplers that take one.
+    pub fn challenge_rng(&mut self, label: &[u8]) -> TranscriptRng {
+        let mut seed = [0u8; 32];
+        self.challenge_bytes(label, &mut seed);
+        let mut stream = Shake128::new();
+        stream.absorb(STREAM_LABEL);
+        stream.absorb(&seed);
+        TranscriptRng { stream }
+    }
+
+    /// Uniform in [0, modulus).
+    pub fn challenge_u64(&mut self, label: &[u8], modulus: u64) -> u64 {
+        self.challenge_rng(label).uniform_below(modulus)
+    }
+
+    /// Uniform in F_{q^K}.
+    pub fn challenge_field<const MOD_Q: u64, const K: usize>(&mut self, label: &[u8]) -> ExtField<MOD_Q, K> {
+        let mut rng = self.challenge_rng(label);
+        ExtField { coefficients: std::array::from_fn(|_| rng.uniform_below(MOD_Q)) }
+    }
+
+    /// A short ring element from `set`, with positions and signs drawn by
+    /// [`TranscriptRng::uniform_below`].
+    pub fn challenge_short_ring<const MOD_Q: u64, const N: usize>(
+        &mut self,
+        label: &[u8],
+        set: &ChallengeSet,
+    ) -> CyclotomicRing<MOD_Q, N> {
+        let mut rng = self.challenge_rng(label);
+        set.sample_by(|bound| rng.uniform_below(bound))
+    }
+
+    /// X^k for k uniform in [0, 2N), i.e. a uniform ±X^i.
+    pub fn challenge_monomial<const MOD_Q: u64, const N: usize>(&mut self, label: &[u8]) -> CyclotomicRing<MOD_Q, N> {
+        let k = self.challenge_rng(label).uniform_below(2 * N as u64) as usize;
+        let mut monomial = CyclotomicRing::<MOD_Q, N>::new();
+        monomial.data[k % N] = if k < N { 1 } else { MOD_Q - 1 };
+        monomial
+    }
+}
+
+/// `modulus ‖ part count ‖ (len ‖ values)*`, all u64 little-endian.
+fn encode(modulus: u64, parts: &[&[u64]]) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(16 + parts.iter().map(|part| 8 * (part.len() + 1)).sum::<usize>());
+    payload.extend_from_slice(&modulus.to_le_bytes());
+    payload.extend_from_slice(&(parts.len() as u64).to_le_bytes());
+    for part in parts {
+        payload.extend_from_slice(&(part.len() as u64).to_le_bytes());
+        for value in part.iter() {
+            payload.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    payload
+}
+
+/// A SHAKE128 output stream seeded from a transcript challenge.
+#[derive(Clone, Debug)]
+pub struct TranscriptRng {
+    stream: Shake128,
+}
+
+impl TranscriptRng {
+    /// Uniform in [0, bound) by rejection on the smallest covering power of
+    /// two, so the result does not depend on any library's sampling
+    /// algorithm.
+    pub fn uniform_below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "empty range");
+        let mask = u64::MAX >> (bound - 1).leading_zeros().min(63);
+        loop {
+            let candidate = self.next_u64() & mask;
+            if candidate < bound {
+                return candidate;
+            }
+        }
+    }
+}
+
+impl RngCore for TranscriptRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.stream.squeeze(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.stream.squeeze(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.stream.squeeze(dest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra::PolynomialRing;
+
+    const MOD_Q: u64 = 1125899904679937;
+    const N: usize = 64;
+
+    fn transcript_with_messages() -> Transcript {
+        let mut transcript = Transcript::new(b"test protocol");
+        transcript.append_u64(b"round", 3);
+        transcript.append_bytes(b"message", b"abc");
+        transcript
+    }
+
+    /// First challenge bytes (as a u64) and first uniform value mod q of
+    /// `transcript_with_messages`. These change with any change to the
+    /// encoding, which has to stay stable across versions.
+    const CHALLENGE_VECTOR: [u64; 2] = [14491435470348135178, 956179510802419];
+
+    #[test]
+    fn test_challenge_vectors() {
+        let mut transcript = transcript_with_messages();
+        let mut bytes = [0u8; 8];
+        transcript.challenge_bytes(b"c", &mut bytes);
+        assert_eq!(u64::from_le_bytes(bytes), CHALLENGE_VECTOR[0]);
+        assert_eq!(transcript.challenge_u64(b"r", MOD_Q), CHALLENGE_VECTOR[1]);
+
+        // Replaying the same messages gives the same challenges.
+        let mut replay = transcript_with_messages();
+        let mut replayed = [0u8; 8];
+        replay.challenge_bytes(b"c", &mut replayed);
+        assert_eq!(replayed, bytes);
+        let field = replay.challenge_field::<MOD_Q, 2>(b"r");
+        assert_eq!(field.coefficients[0], CHALLENGE_VECTOR[1]);
+        assert!(field.coefficients.iter().all(|&c| c < MOD_Q));
+    }
+
+    #[test]
+    fn test_domain_separation() {
+        let squeeze = |transcript: &mut Transcript| {
+            let mut bytes = [0u8; 16];
+            transcript.challenge_bytes(b"c", &mut bytes);
+            bytes
+        };
+        let reference = squeeze(&mut transcript_with_messages());
+
+        let mut other_domain = Transcript::new(b"other protocol");
+        other_domain.append_u64(b"round", 3);
+        other_domain.append_bytes(b"message", b"abc");
+        assert_ne!(squeeze(&mut other_domain), reference);
+
+        // The same bytes with a different label split or message kind.
+        let mut shifted = Transcript::new(b"test protocol");
+        shifted.append_u64(b"roun", 3);
+        shifted.append_bytes(b"dmessage", b"abc");
+        assert_ne!(squeeze(&mut shifted), reference);
+        let mut retyped = Transcript::new(b"test protocol");
+        retyped.append_bytes(b"round", &3u64.to_le_bytes());
+        retyped.append_bytes(b"message", b"abc");
+        assert_ne!(squeeze(&mut retyped), reference);
+
+        // Successive challenges differ.
+        let mut transcript = transcript_with_messages();
+        assert_ne!(squeeze(&mut transcript), squeeze(&mut transcript));
+    }
+
+    #[test]
+    fn test_ring_absorption_is_canonical() {
+        let a = CyclotomicRing::<MOD_Q, N>::random();
+        let mut a_ntt = a;
+        a_ntt.to_ntt_representation_lazy();
+        let mut a_incomplete = a;
+        a_incomplete.to_incomplete_ntt_representation();
+
+        let challenge = |element: &CyclotomicRing<MOD_Q, N>| {
+            let mut transcript = Transcript::new(b"test protocol");
+            transcript.append_ring(b"a", element);
+            transcript.append_commitment(b"commitment", &[*element, *element]);
+            transcript.challenge_field::<MOD_Q, 2>(b"r")
+        };
+        assert_eq!(challenge(&a), challenge(&a_ntt));
+        assert_eq!(challenge(&a), challenge(&a_incomplete));
+        assert_ne!(challenge(&a), challenge(&(a + CyclotomicRing::one())));
+    }
+
+    /// Centered coefficients of the first LaBRADOR challenge and the
+    /// exponent k of the first monomial X^k, k in [0, 2N), drawn from
+    /// `transcript_with_messages`. Like [`CHALLENGE_VECTOR`] these must stay
+    /// stable, so they also pin down the samplers.
+    const SHORT_RING_VECTOR: [i64; N] = [
+        0, -1, -1, 1, -2, 0, 0, -2, -2, 2, 0, 1, 0, 0, 0, 1,
+        1, 1, 1, -2, -1, 0, 0, 0, 0, 1, 1, -1, -1, 1, 0, -1,
+        0, -1, -1, -1, -1, -1, 0, 0, -1, -2, 2, 0, -1, 0, -2, 0,
+        0, 1, -1, 2, 0, 1, -1, -1, 0, -1, 1, -2, 0, 1, -1, 0,
+    ];
+    const MONOMIAL_VECTOR: usize = 117;
+
+    #[test]
+    fn test_ring_challenge_vectors() {
+        let mut transcript = transcript_with_messages();
+        let c = transcript.challenge_short_ring::<MOD_Q, N>(b"c", &ChallengeSet::LABRADOR_64);
+        assert_eq!(c.centered_coefficients(), SHORT_RING_VECTOR);
+        let monomial = transcript.challenge_monomial::<MOD_Q, N>(b"m");
+        let mut expected = CyclotomicRing::<MOD_Q, N>::new();
+        expected.data[MONOMIAL_VECTOR % N] = if MONOMIAL_VECTOR < N { 1 } else { MOD_Q - 1 };
+        assert_eq!(monomial, expected);
+    }
+
+    #[test]
+    fn test_ring_challenges() {
+        let mut transcript = transcript_with_messages();
+        let set = ChallengeSet::LABRADOR_64;
+        let c = transcript.challenge_short_ring::<MOD_Q, N>(b"c", &set);
+        assert_eq!(c, transcript_with_messages().challenge_short_ring::<MOD_Q, N>(b"c", &set));
+        assert!(c.infinity_norm() <= 2);
+
+        let monomial = transcript.challenge_monomial::<MOD_Q, N>(b"m");
+        let coefficients = monomial.centered_coefficients();
+        assert_eq!(coefficients.iter().filter(|&&x| x != 0).count(), 1);
+        assert!(coefficients.iter().all(|x| x.abs() <= 1));
+    }
+}
@@ -0,0 +1,268 @@
+//! Type-level representations for `CyclotomicRing`.
+//!
+//! `CyclotomicRing` converts operands on demand: `Add` moves the left
+//! operand into the right one's representation and the multiplications
+//! transform both inputs in place, so NTTs happen wherever the operands
+//! happen to disagree. `Ring<MOD_Q, N, R>` fixes the representation `R` in
+//! the type instead. Arithmetic is only defined between elements of the
+//! same representation and never transforms, and every NTT is an explicit
+//! `into_*` call. The wrapper is optional and converts to and from the
+//! untyped ring for free.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, Mul, Sub};
+
+use crate::algebra::PolynomialRing;
+use crate::cyclotomic_ring::{
+    fully_splitting_ntt_multiplication, incomplete_ntt_multiplication, CyclotomicRing, Representation,
+};
+use crate::params::RingError;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Marker for one of the three representations.
+pub trait Repr: sealed::Sealed + Copy {
+    const REPRESENTATION: Representation;
+}
+
+/// Coefficients of the polynomial.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Coeff;
+/// Slots of the fully splitting NTT.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Ntt;
+/// Even/odd halves of the 2-way incomplete NTT.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IncompleteNtt;
+
+impl sealed::Sealed for Coeff {}
+impl sealed::Sealed for Ntt {}
+impl sealed::Sealed for IncompleteNtt {}
+
+impl Repr for Coeff {
+    const REPRESENTATION: Representation = Representation::Coefficient;
+}
+impl Repr for Ntt {
+    const REPRESENTATION: Representation = Representation::NTT;
+}
+impl Repr for IncompleteNtt {
+    const REPRESENTATION: Representation = Representation::IncompleteNTT;
+}
+
+/// A `CyclotomicRing` whose representation is the type parameter `R`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ring<const MOD_Q: u64, const N: usize, R: Repr> {
+    inner: CyclotomicRing<MOD_Q, N>,
+    _representation: PhantomData<R>,
+}
+
+impl<const MOD_Q: u64, const N: usize, R: Repr> Ring<MOD_Q, N, R> {
+    fn wrap(inner: CyclotomicRing<MOD_Q, N>) -> Self {
+        debug_assert_eq!(inner.representation, R::REPRESENTATION);
+        Self { inner, _representation: PhantomData }
+    }
+
+    /// The untyped element, still in representation `R`.
+    pub fn into_inner(self) -> CyclotomicRing<MOD_Q, N> {
+        self.inner
+    }
+
+    pub fn as_inner(&self) -> &CyclotomicRing<MOD_Q, N> {
+        &self.inner
+    }
+
+    /// `c · self`; scalars act slot-wise in every representation.
+    pub fn scalar_mul(&self, scalar: u64) -> Self {
+        Self::wrap(self.inner.scalar_mul(scalar))
+    }
+
+    pub fn zero() -> Self {
+        // Zero is zero in every representation, so no transform is needed.
+        let mut inner = CyclotomicRing::new();
+        inner.representation = R::REPRESENTATION;
+        Self::wrap(inner)
+    }
+}
+
+/// Wraps an untyped element without converting it; fails with the element
+/// itself if its representation is not `R`.
+impl<const MOD_Q: u64, const N: usize, R: Repr> TryFrom<CyclotomicRing<MOD_Q, N>> for Ring<MOD_Q, N, R> {
+    type Error = CyclotomicRing<MOD_Q, N>;
+
+    fn try_from(inner: CyclotomicRing<MOD_Q, N>) -> Result<Self, Self::Error> {
+        if inner.representation != R::REPRESENTATION {
+            return Err(inner);
+        }
+        Ok(Self::wrap(inner))
+    }
+}
+
+impl<const MOD_Q: u64, const N: usize, R: Repr> Add for Ring<MOD_Q, N, R> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self::wrap(self.inner + other.inner)
+    }
+}
+
+impl<const MOD_Q: u64, const N: usize, R: Repr> Sub for Ring<MOD_Q, N, R> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self::wrap(self.inner - other.inner)
+    }
+}
+
+/// Slot-wise product.
+impl<const MOD_Q: u64, const N: usize> Mul for Ring<MOD_Q, N, Ntt> {
+    type Output = Self;
+
+    fn mul(mut self, mut other: Self) -> Self::Output {
+        Self::wrap(fully_splitting_ntt_multiplication(&mut self.inner, &mut other.inner))
+    }
+}
+
+/// Product of the degree-1 polynomials in each slot.
+impl<const MOD_Q: u64, const N: usize> Mul for Ring<MOD_Q, N, IncompleteNtt> {
+    type Output = Self;
+
+    fn mul(mut self, mut other: Self) -> Self::Output {
+        Self::wrap(incomplete_ntt_multiplication(&mut self.inner, &mut other.inner, true))
+    }
+}
+
+impl<const MOD_Q: u64, const N: usize> Default for Ring<MOD_Q, N, Coeff> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const MOD_Q: u64, const N: usize> Ring<MOD_Q, N, Coeff> {
+    pub fn new() -> Self {
+        Self::wrap(CyclotomicRing::new())
+    }
+
+    pub fn from_coefficients(data: [u64; N]) -> Self {
+        let mut inner = CyclotomicRing::new();
+        inner.data = data.map(|c| c % MOD_Q);
+        Self::wrap(inner)
+    }
+
+    pub fn random() -> Self {
+        Self::wrap(CyclotomicRing::random())
+    }
+
+    pub fn random_bounded(bound: u64) -> Self {
+        Self::wrap(CyclotomicRing::random_bounded(bound))
+    }
+
+    pub fn one() -> Self {
+        Self::wrap(CyclotomicRing::one())
+    }
+
+    pub fn coefficients(&self) -> &[u64; N] {
+        &self.inner.data
+    }
+
+    pub fn centered_coefficients(&self) -> [i64; N] {
+        self.inner.centered_coefficients()
+    }
+
+    pub fn infinity_norm(&self) -> u64 {
+        self.inner.infinity_norm()
+    }
+
+    pub fn conjugate(&self) -> Self {
+        Self::wrap(self.inner.conjugate())
+    }
+
+    /// Forward NTT; values are left in `[0, 4q)` for the next product.
+    pub fn into_ntt(self) -> Ring<MOD_Q, N, Ntt> {
+        self.try_into_ntt().unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_into_ntt(mut self) -> Result<Ring<MOD_Q, N, Ntt>, RingError> {
+        CyclotomicRing::<MOD_Q, N>::PARAMS.check_ntt_degree(1)?;
+        self.inner.to_ntt_representation_lazy();
+        Ok(Ring::wrap(self.inner))
+    }
+
+    /// Forward 2-way incomplete NTT; values are left in `[0, 4q)`.
+    pub fn into_incomplete_ntt(self) -> Ring<MOD_Q, N, IncompleteNtt> {
+        self.try_into_incomplete_ntt().unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_into_incomplete_ntt(mut self) -> Result<Ring<MOD_Q, N, IncompleteNtt>, RingError> {
+        CyclotomicRing::<MOD_Q, N>::PARAMS.check_ntt_degree(2)?;
+        self.inner.to_incomplete_ntt_representation_lazy();
+        Ok(Ring::wrap(self.inner))
+    }
+}
+
+impl<const MOD_Q: u64, const N: usize> Ring<MOD_Q, N, Ntt> {
+    /// Inverse NTT.
+    pub fn into_coeff(mut self) -> Ring<MOD_Q, N, Coeff> {
+        self.inner.to_coeff_representation();
+        Ring::wrap(self.inner)
+    }
+}
+
+impl<const MOD_Q: u64, const N: usize> Ring<MOD_Q, N, IncompleteNtt> {
+    /// Inverse 2-way incomplete NTT.
+    pub fn into_coeff(mut self) -> Ring<MOD_Q, N, Coeff> {
+        self.inner.to_coeff_representation();
+        Ring::wrap(self.inner)
+    }
+}
+
+impl<const MOD_Q: u64, const N: usize, R: Repr> fmt::Display for Ring<MOD_Q, N, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+impl<const MOD_Q: u64, const N: usize, R: Repr> fmt::Debug for Ring<MOD_Q, N, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cyclotomic_ring::naive_multiply;
+
+    const MOD_Q: u64 = 1125899904679937;
+    const N: usize = 64;
+
+    #[test]
+    fn test_typed_products_match_naive() {
+        let a = Ring::<MOD_Q, N, Coeff>::random();
+        let b = Ring::<MOD_Q, N, Coeff>::random();
+        let c = Ring::<MOD_Q, N, Coeff>::random_bounded(4);
+        let expected = naive_multiply(&mut a.into_inner(), &mut b.into_inner()) + c.into_inner();
+
+        let ntt = a.into_ntt() * b.into_ntt() + c.into_ntt();
+        let incomplete = a.into_incomplete_ntt() * b.into_incomplete_ntt() + c.into_incomplete_ntt();
+
+        assert_eq!(ntt.into_coeff().into_inner(), expected);
+        assert_eq!(incomplete.into_coeff().into_inner(), expected);
+    }
+
+    #[test]
+    fn test_wrapping_checks_representation() {
+        let mut untyped = CyclotomicRing::<MOD_Q, N>::random();
+        assert!(Ring::<MOD_Q, N, Coeff>::try_from(untyped).is_ok());
+        untyped.to_ntt_representation();
+        assert!(Ring::<MOD_Q, N, Coeff>::try_from(untyped).is_err());
+        let typed = Ring::<MOD_Q, N, Ntt>::try_from(untyped).unwrap();
+        assert_eq!(typed + Ring::zero(), typed);
+        assert_eq!(
+            Ring::<41, 8, Coeff>::random().try_into_ntt().unwrap_err(),
+            RingError::NotNttFriendly { modulus: 41, n: 8, degree: 1 }
+        );
+    }
+}
@@ -0,0 +1,483 @@
+//! Z_q[X]/(X^N + 1) with 128-bit coefficients, for single-prime moduli
+//! beyond HEXL's 62-bit limit. Arithmetic is Montgomery multiplication with
+//! R = 2^128 over a 256-bit product assembled from 64-bit halves, and the
+//! ring has its own negacyclic NTT (Cooley–Tukey forward, Gentleman–Sande
+//! inverse, slots in bit-reversed order).
+//!
+//! This is the single-prime alternative to an RNS representation over
+//! several HEXL-sized primes; `benches/bench.rs` compares the two.
+
+use rand::Rng;
+use std::collections::HashMap;
+use std::ops::{Add, Mul, Sub};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::cyclotomic_ring::Representation;
+
+/// Moduli must stay below 2^126 so that sums of two residues and the
+/// intermediate values of the Montgomery reduction fit in a u128.
+pub const MAX_WIDE_MODULUS_BITS: u32 = 126;
+
+/// Full 256-bit product of `a` and `b` as `(low, high)`.
+const fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_lo, a_hi) = (a & MASK, a >> 64);
+    let (b_lo, b_hi) = (b & MASK, b >> 64);
+    let lo_lo = a_lo * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_lo = a_hi * b_lo;
+    let hi_hi = a_hi * b_hi;
+    // Middle column; each term is below 2^64, so the sum cannot overflow.
+    let middle = (lo_lo >> 64) + (lo_hi & MASK) + (hi_lo & MASK);
+    let low = (middle << 64) | (lo_lo & MASK);
+    let high = hi_hi + (lo_hi >> 64) + (hi_lo >> 64) + (middle >> 64);
+    (low, high)
+}
+
+/// Montgomery arithmetic modulo an odd `modulus` below 2^126.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Montgomery {
+    pub modulus: u128,
+    /// -q^{-1} mod 2^128.
+    q_inv_neg: u128,
+    /// R^2 mod q, for conversions into Montgomery form.
+    r2: u128,
+}
+
+impl Montgomery {
+    pub const fn new(modulus: u128) -> Self {
+        assert!(modulus % 2 == 1, "Montgomery arithmetic needs an odd modulus");
+        assert!(modulus < 1 << MAX_WIDE_MODULUS_BITS, "modulus exceeds 2^126");
+        // Newton iteration doubles the number of correct low bits; q is its
+        // own inverse mod 8, so six steps reach 192 ≥ 128 bits.
+        let mut inv = modulus;
+        let mut i = 0;
+        while i < 6 {
+            inv = inv.wrapping_mul(2u128.wrapping_sub(modulus.wrapping_mul(inv)));
+            i += 1;
+        }
+        // R mod q, doubled 128 more times to give R^2 mod q.
+        let mut r2 = (u128::MAX % modulus + 1) % modulus;
+        let mut i = 0;
+        while i < 128 {
+            r2 <<= 1;
+            if r2 >= modulus {
+                r2 -= modulus;
+            }
+            i += 1;
+        }
+        Self { modulus, q_inv_neg: inv.wrapping_neg(), r2 }
+    }
+
+    /// `(low + 2^128 high) · R^{-1} mod q` for inputs below `q · R`.
+    #[inline(always)]
+    const fn reduce(&self, low: u128, high: u128) -> u128 {
+        let m = low.wrapping_mul(self.q_inv_neg);
+        let (mq_low, mq_high) = widening_mul(m, self.modulus);
+        // low + mq_low ≡ 0 mod 2^128, so only its carry survives.
+        let carry = low.overflowing_add(mq_low).1 as u128;
+        let t = high + mq_high + carry;
+        if t >= self.modulus {
+            t - self.modulus
+        } else {
+            t
+        }
+    }
+
+    /// Montgomery product `a · b · R^{-1} mod q`.
+    #[inline(always)]
+    pub const fn mul(&self, a: u128, b: u128) -> u128 {
+        let (low, high) = widening_mul(a, b);
+        self.reduce(low, high)
+    }
+
+    pub const fn to_montgomery(&self, a: u128) -> u128 {
+        self.mul(a % self.modulus, self.r2)
+    }
+
+    pub const fn from_montgomery(&self, a: u128) -> u128 {
+        self.reduce(a, 0)
+    }
+
+    /// Plain product `a · b mod q` of reduced operands.
+    pub const fn mul_mod(&self, a: u128, b: u128) -> u128 {
+        self.mul(self.mul(a, b), self.r2)
+    }
+
+    #[inline(always)]
+    pub const fn add_mod(&self, a: u128, b: u128) -> u128 {
+        let sum = a + b;
+        if sum >= self.modulus {
+            sum - self.modulus
+        } else {
+            sum
+        }
+    }
+
+    #[inline(always)]
+    pub const fn sub_mod(&self, a: u128, b: u128) -> u128 {
+        if a >= b {
+            a - b
+        } else {
+            a + self.modulus - b
+        }
+    }
+
+    pub const fn pow_mod(&self, base: u128, mut exponent: u128) -> u128 {
+        let mut result = self.to_montgomery(1);
+        let mut base = self.to_montgomery(base);
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = self.mul(result, base);
+            }
+            base = self.mul(base, base);
+            exponent >>= 1;
+        }
+        self.from_montgomery(result)
+    }
+}
+
+/// Twiddle factors of the size-n negacyclic NTT, in Montgomery form and
+/// bit-reversed order.
+#[derive(Debug)]
+pub struct WideNttTables {
+    pub arith: Montgomery,
+    psi_powers: Vec<u128>,
+    inv_psi_powers: Vec<u128>,
+    /// n^{-1} in Montgomery form.
+    n_inv: u128,
+}
+
+impl WideNttTables {
+    /// Panics unless `modulus` is a prime with `modulus ≡ 1 mod 2n`.
+    pub fn new(modulus: u128, n: usize) -> Self {
+        assert!(n.is_power_of_two() && n >= 2, "NTT size {n} must be a power of two ≥ 2");
+        assert!(
+            (modulus - 1).is_multiple_of(2 * n as u128),
+            "modulus {modulus} is not ≡ 1 mod {}",
+            2 * n
+        );
+        let arith = Montgomery::new(modulus);
+        let psi = Self::primitive_root(&arith, 2 * n);
+        let psi_inv = arith.pow_mod(psi, 2 * n as u128 - 1);
+
+        let log_n = n.trailing_zeros();
+        let mut psi_powers = vec![0u128; n];
+        let mut inv_psi_powers = vec![0u128; n];
+        let (mut power, mut inv_power) = (1u128, 1u128);
+        for i in 0..n {
+            let index = i.reverse_bits() >> (usize::BITS - log_n);
+            psi_powers[index] = arith.to_montgomery(power);
+            inv_psi_powers[index] = arith.to_montgomery(inv_power);
+            power = arith.mul_mod(power, psi);
+            inv_power = arith.mul_mod(inv_power, psi_inv);
+        }
+        let n_inv = arith.pow_mod(n as u128, modulus - 2);
+        Self { arith, psi_powers, inv_psi_powers, n_inv: arith.to_montgomery(n_inv) }
+    }
+
+    /// A primitive `order`-th root of unity, `order` a power of two.
+    fn primitive_root(arith: &Montgomery, order: usize) -> u128 {
+        let q = arith.modulus;
+        for g in 2..1 << 16 {
+            let candidate = arith.pow_mod(g, (q - 1) / order as u128);
+            if arith.pow_mod(candidate, order as u128 / 2) == q - 1 {
+                return candidate;
+            }
+        }
+        panic!("no primitive {order}-th root of unity modulo {q}; is it prime?");
+    }
+
+    pub fn forward(&self, a: &mut [u128]) {
+        let arith = &self.arith;
+        let n = a.len();
+        let mut t = n;
+        let mut m = 1;
+        while m < n {
+            t /= 2;
+            for i in 0..m {
+                let s = self.psi_powers[m + i];
+                let start = 2 * i * t;
+                for j in start..start + t {
+                    let u = a[j];
+                    let v = arith.mul(a[j + t], s);
+                    a[j] = arith.add_mod(u, v);
+                    a[j + t] = arith.sub_mod(u, v);
+                }
+            }
+            m *= 2;
+        }
+    }
+
+    pub fn inverse(&self, a: &mut [u128]) {
+        let arith = &self.arith;
+        let n = a.len();
+        let mut t = 1;
+        let mut m = n;
+        while m > 1 {
+            let h = m / 2;
+            for i in 0..h {
+                let s = self.inv_psi_powers[h + i];
+                let start = 2 * i * t;
+                for j in start..start + t {
+                    let u = a[j];
+                    let v = a[j + t];
+                    a[j] = arith.add_mod(u, v);
+                    a[j + t] = arith.mul(arith.sub_mod(u, v), s);
+                }
+            }
+            t *= 2;
+            m = h;
+        }
+        for x in a.iter_mut() {
+            *x = arith.mul(*x, self.n_inv);
+        }
+    }
+}
+
+type WideNttTablesCache = Mutex<HashMap<(u128, usize), Arc<WideNttTables>>>;
+
+static WIDE_NTT_TABLES_CACHE: OnceLock<WideNttTablesCache> = OnceLock::new();
+
+pub fn wide_ntt_tables(modulus: u128, n: usize) -> Arc<WideNttTables> {
+    let cache = WIDE_NTT_TABLES_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    cache
+        .lock()
+        .unwrap()
+        .entry((modulus, n))
+        .or_insert_with(|| Arc::new(WideNttTables::new(modulus, n)))
+        .clone()
+}
+
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct WideCyclotomicRing<const MOD_Q: u128, const N: usize> {
+    pub data: [u128; N],
+    pub representation: Representation,
+}
+
+impl<const MOD_Q: u128, const N: usize> Add for WideCyclotomicRing<MOD_Q, N> {
+    type Output = Self;
+
+    fn add(mut self, mut other: Self) -> Self::Output {
+        other.adjust_representation(self.representation);
+        for i in 0..N {
+            self.data[i] = Self::ARITH.add_mod(self.data[i], other.data[i]);
+        }
+        self
+    }
+}
+
+impl<const MOD_Q: u128, const N: usize> Sub for WideCyclotomicRing<MOD_Q, N> {
+    type Output = Self;
+
+    fn sub(mut self, mut other: Self) -> Self::Output {
+        other.adjust_representation(self.representation);
+        for i in 0..N {
+            self.data[i] = Self::ARITH.sub_mod(self.data[i], other.data[i]);
+        }
+        self
+    }
+}
+
+impl<const MOD_Q: u128, const N: usize> Mul for WideCyclotomicRing<MOD_Q, N> {
+    type Output = Self;
+
+    fn mul(mut self, mut other: Self) -> Self::Output {
+        wide_ntt_multiplication(&mut self, &mut other)
+    }
+}
+
+impl<const MOD_Q: u128, const N: usize> Default for WideCyclotomicRing<MOD_Q, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const MOD_Q: u128, const N: usize> WideCyclotomicRing<MOD_Q, N> {
+    pub const ARITH: Montgomery = Montgomery::new(MOD_Q);
+
+    pub fn new() -> Self {
+        Self { data: [0u128; N], representation: Representation::Coefficient }
+    }
+
+    pub fn random() -> Self {
+        let mut rng = rand::rng();
+        let mut result = Self::new();
+        for i in 0..N {
+            result.data[i] = rng.random_range(0..MOD_Q);
+        }
+        result
+    }
+
+    pub fn random_bounded(bound: u128) -> Self {
+        let mut rng = rand::rng();
+        let mut result = Self::new();
+        for i in 0..N {
+            let value = rng.random_range(0..bound) % MOD_Q;
+            result.data[i] = if rng.random_bool(0.5) { Self::ARITH.sub_mod(0, value) } else { value };
+        }
+        result
+    }
+
+    pub fn constant(value: u128) -> Self {
+        let mut result = Self::new();
+        result.data[0] = value % MOD_Q;
+        result
+    }
+
+    pub fn one() -> Self {
+        Self::constant(1)
+    }
+
+    pub fn conjugate(&self) -> Self {
+        let mut conjugated = *self;
+        conjugated.to_coeff_representation();
+        let coefficients = conjugated.data;
+        for i in 1..N {
+            conjugated.data[i] = Self::ARITH.sub_mod(0, coefficients[N - i]);
+        }
+        conjugated.adjust_representation(self.representation);
+        conjugated
+    }
+
+    /// Coefficients as centered representatives in `(-q/2, q/2]`.
+    pub fn centered_coefficients(&self) -> [i128; N] {
+        let mut coefficients = *self;
+        coefficients.to_coeff_representation();
+        coefficients.data.map(|c| if c > MOD_Q / 2 { -((MOD_Q - c) as i128) } else { c as i128 })
+    }
+
+    pub fn infinity_norm(&self) -> u128 {
+        self.centered_coefficients().iter().map(|c| c.unsigned_abs()).max().unwrap_or(0)
+    }
+
+    fn adjust_representation(&mut self, new_representation: Representation) {
+        match new_representation {
+            Representation::Coefficient => self.to_coeff_representation(),
+            Representation::NTT => self.to_ntt_representation(),
+            Representation::IncompleteNTT => panic!("the 128-bit ring has no incomplete NTT"),
+        }
+    }
+
+    pub fn to_ntt_representation(&mut self) {
+        if self.representation == Representation::NTT {
+            return;
+        }
+        wide_ntt_tables(MOD_Q, N).forward(&mut self.data);
+        self.representation = Representation::NTT;
+    }
+
+    pub fn to_coeff_representation(&mut self) {
+        if self.representation == Representation::Coefficient {
+            return;
+        }
+        wide_ntt_tables(MOD_Q, N).inverse(&mut self.data);
+        self.representation = Representation::Coefficient;
+    }
+}
+
+/// Slot-wise product after a full NTT; requires MOD_Q ≡ 1 mod 2N. The
+/// result stays in NTT form.
+pub fn wide_ntt_multiplication<const MOD_Q: u128, const N: usize>(
+    operand1: &mut WideCyclotomicRing<MOD_Q, N>,
+    operand2: &mut WideCyclotomicRing<MOD_Q, N>,
+) -> WideCyclotomicRing<MOD_Q, N> {
+    operand1.to_ntt_representation();
+    operand2.to_ntt_representation();
+    let arith = WideCyclotomicRing::<MOD_Q, N>::ARITH;
+    let mut result = *operand1;
+    for i in 0..N {
+        result.data[i] = arith.mul_mod(operand1.data[i], operand2.data[i]);
+    }
+    result
+}
+
+pub fn wide_naive_multiply<const MOD_Q: u128, const N: usize>(
+    operand1: &mut WideCyclotomicRing<MOD_Q, N>,
+    operand2: &mut WideCyclotomicRing<MOD_Q, N>,
+) -> WideCyclotomicRing<MOD_Q, N> {
+    operand1.to_coeff_representation();
+    operand2.to_coeff_representation();
+    let arith = WideCyclotomicRing::<MOD_Q, N>::ARITH;
+    let mut result = WideCyclotomicRing::<MOD_Q, N>::new();
+    for i in 0..N {
+        for j in 0..N {
+            let product = arith.mul_mod(operand1.data[i], operand2.data[j]);
+            if i + j < N {
+                result.data[i + j] = arith.add_mod(result.data[i + j], product);
+            } else {
+                result.data[i + j - N] = arith.sub_mod(result.data[i + j - N], product);
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOD_Q_120: u128 = 1329227995784915872903807060273266689;
+    const MOD_Q_64: u128 = 18446744073707716609;
+    const N: usize = 64;
+
+    /// Shift-and-add multiplication, independent of the Montgomery code.
+    fn slow_mul_mod(a: u128, mut b: u128, q: u128) -> u128 {
+        let (mut result, mut a) = (0u128, a % q);
+        while b > 0 {
+            if b & 1 == 1 {
+                result = (result + a) % q;
+            }
+            a = (a << 1) % q;
+            b >>= 1;
+        }
+        result
+    }
+
+    #[test]
+    fn test_montgomery_matches_slow_multiplication() {
+        let arith = Montgomery::new(MOD_Q_120);
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let a = rng.random_range(0..MOD_Q_120);
+            let b = rng.random_range(0..MOD_Q_120);
+            assert_eq!(arith.mul_mod(a, b), slow_mul_mod(a, b, MOD_Q_120));
+            assert_eq!(arith.from_montgomery(arith.to_montgomery(a)), a);
+        }
+        assert_eq!(arith.pow_mod(3, MOD_Q_120 - 1), 1);
+    }
+
+    #[test]
+    fn test_ntt_roundtrip() {
+        let original = WideCyclotomicRing::<MOD_Q_120, N>::random();
+        let mut transformed = original;
+        transformed.to_ntt_representation();
+        assert_ne!(transformed.data, original.data);
+        transformed.to_coeff_representation();
+        assert_eq!(transformed, original);
+    }
+
+    #[test]
+    fn test_multiplication_matches_naive() {
+        fn check<const Q: u128>() {
+            let mut a = WideCyclotomicRing::<Q, N>::random();
+            let mut b = WideCyclotomicRing::<Q, N>::random();
+            let expected = wide_naive_multiply(&mut a, &mut b);
+            let mut product = a * b;
+            product.to_coeff_representation();
+            assert_eq!(product, expected);
+        }
+        check::<MOD_Q_64>();
+        check::<MOD_Q_120>();
+    }
+
+    #[test]
+    fn test_conjugate_and_norm() {
+        let mut a = WideCyclotomicRing::<MOD_Q_120, N>::new();
+        a.data[1] = 7;
+        let conjugated = a.conjugate();
+        assert_eq!(conjugated.centered_coefficients()[N - 1], -7);
+        assert_eq!(conjugated.infinity_norm(), 7);
+        assert_eq!((a + conjugated - a).data, conjugated.data);
+    }
+}